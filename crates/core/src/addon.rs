@@ -1,4 +1,5 @@
 use crate::{
+    config::{Config, Flavor, SortDirection, SortState},
     error::{ParseError, RepositoryError},
     repository::{
         Changelog, GitKind, GlobalReleaseChannel, ReleaseChannel, RemotePackage,
@@ -594,3 +595,234 @@ impl Ord for Addon {
 }
 
 impl Eq for Addon {}
+
+/// Returns `true` if `addon` should be shown. Addons ignored for `flavor` are
+/// hidden when `Config::hide_ignored_addons` is set, and addons without an
+/// update are hidden when `Config::show_updates_only` is set.
+pub fn is_addon_visible(addon: &Addon, config: &Config, flavor: &Flavor) -> bool {
+    let hidden_as_ignored =
+        config.hide_ignored_addons && config.addons.is_ignored(flavor, &addon.primary_folder_id);
+    let hidden_as_current = config.show_updates_only && addon.state != AddonState::Updatable;
+
+    !(hidden_as_ignored || hidden_as_current)
+}
+
+/// Returns the addons which should be shown for `flavor`.
+pub fn filter_visible_addons<'a>(
+    addons: &'a [Addon],
+    config: &Config,
+    flavor: &Flavor,
+) -> Vec<&'a Addon> {
+    addons
+        .iter()
+        .filter(|addon| is_addon_visible(addon, config, flavor))
+        .collect()
+}
+
+/// Returns the addons which have an update available.
+pub fn addons_with_updates(addons: &[Addon]) -> Vec<&Addon> {
+    addons
+        .iter()
+        .filter(|addon| addon.state == AddonState::Updatable)
+        .collect()
+}
+
+/// Sorts `addons` by the column and direction of `sort`. Columns which can't be
+/// sorted without more context, and unknown columns, sort by title. Ties are
+/// broken by title.
+pub fn sort_addons(addons: &mut [Addon], sort: &SortState) {
+    let title = |addon: &Addon| addon.title().to_lowercase();
+
+    addons.sort_by(|a, b| {
+        let ordering = match sort.column_key.as_str() {
+            "local" => a.version().cmp(&b.version()),
+            "status" => a.state.cmp(&b.state),
+            "channel" => a
+                .release_channel
+                .to_string()
+                .cmp(&b.release_channel.to_string()),
+            "author" => a.author().cmp(&b.author()),
+            "game_version" => a.game_version().cmp(&b.game_version()),
+            _ => Ordering::Equal,
+        }
+        .then_with(|| title(a).cmp(&title(b)));
+
+        match sort.direction {
+            SortDirection::Asc => ordering,
+            SortDirection::Desc => ordering.reverse(),
+        }
+    });
+}
+
+/// Returns the visible addons of every flavor with a directory set as one list,
+/// each tagged with its flavor. The list is sorted by title, then flavor.
+pub fn merged_addon_view<'a>(
+    config: &Config,
+    addons_by_flavor: &'a HashMap<Flavor, Vec<Addon>>,
+) -> Vec<(Flavor, &'a Addon)> {
+    let mut merged: Vec<_> = addons_by_flavor
+        .iter()
+        .filter(|(flavor, _)| config.wow.directories.contains_key(flavor))
+        .flat_map(|(flavor, addons)| {
+            filter_visible_addons(addons, config, flavor)
+                .into_iter()
+                .map(move |addon| (*flavor, addon))
+        })
+        .collect();
+
+    merged.sort_by(|(flavor_a, addon_a), (flavor_b, addon_b)| {
+        addon_a
+            .title()
+            .cmp(addon_b.title())
+            .then_with(|| flavor_a.cmp(flavor_b))
+    });
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visible_ids(addons: &[Addon], config: &Config) -> Vec<String> {
+        filter_visible_addons(addons, config, &Flavor::Retail)
+            .into_iter()
+            .map(|addon| addon.primary_folder_id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_addons() {
+        let mut addons: Vec<_> = ["Details", "bagnon", "WeakAuras", "Auctionator"]
+            .iter()
+            .map(|id| Addon::empty(id))
+            .collect();
+        let ids = |addons: &[Addon]| -> Vec<String> {
+            addons.iter().map(|a| a.primary_folder_id.clone()).collect()
+        };
+
+        let mut sort = SortState {
+            column_key: "title".to_owned(),
+            direction: SortDirection::Asc,
+        };
+        sort_addons(&mut addons, &sort);
+        let ascending = ids(&addons);
+        assert_eq!(
+            ascending,
+            vec!["Auctionator", "bagnon", "Details", "WeakAuras"]
+        );
+
+        sort.direction = SortDirection::Desc;
+        sort_addons(&mut addons, &sort);
+        let mut descending = ids(&addons);
+        descending.reverse();
+        assert_eq!(descending, ascending);
+
+        // Unknown columns sort by title
+        sort.column_key = "unknown".to_owned();
+        sort.direction = SortDirection::Asc;
+        sort_addons(&mut addons, &sort);
+        assert_eq!(ids(&addons), ascending);
+    }
+
+    #[test]
+    fn test_filter_visible_addons() {
+        let addons = vec![
+            Addon::empty("Foo"),
+            Addon::empty("Bar"),
+            Addon::empty("Baz"),
+        ];
+
+        let mut config = Config::default();
+        config
+            .addons
+            .ignored
+            .insert(Flavor::Retail, vec!["Bar".to_string()]);
+        config
+            .addons
+            .ignored
+            .insert(Flavor::Classic, vec!["Baz".to_string()]);
+
+        assert_eq!(visible_ids(&addons, &config), vec!["Foo", "Bar", "Baz"]);
+
+        config.hide_ignored_addons = true;
+
+        assert_eq!(visible_ids(&addons, &config), vec!["Foo", "Baz"]);
+        assert_eq!(
+            filter_visible_addons(&addons, &config, &Flavor::Classic).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_show_updates_only() {
+        let mut addons = vec![
+            Addon::empty("Foo"),
+            Addon::empty("Bar"),
+            Addon::empty("Baz"),
+        ];
+        addons[0].state = AddonState::Updatable;
+        addons[2].state = AddonState::Updatable;
+
+        let updatable: Vec<_> = addons_with_updates(&addons)
+            .into_iter()
+            .map(|addon| addon.primary_folder_id.as_str())
+            .collect();
+        assert_eq!(updatable, vec!["Foo", "Baz"]);
+
+        let mut config = Config::default();
+        assert_eq!(visible_ids(&addons, &config), vec!["Foo", "Bar", "Baz"]);
+
+        config.show_updates_only = true;
+        assert_eq!(visible_ids(&addons, &config), vec!["Foo", "Baz"]);
+
+        // Composes with hiding ignored addons.
+        config
+            .addons
+            .ignored
+            .insert(Flavor::Retail, vec!["Baz".to_string()]);
+        config.hide_ignored_addons = true;
+        assert_eq!(visible_ids(&addons, &config), vec!["Foo"]);
+
+        config.show_updates_only = false;
+        assert_eq!(visible_ids(&addons, &config), vec!["Foo", "Bar"]);
+    }
+
+    #[test]
+    fn test_merged_addon_view() {
+        let mut config = Config::default();
+        for flavor in [Flavor::Retail, Flavor::Classic].iter() {
+            config
+                .wow
+                .directories
+                .insert(*flavor, PathBuf::from(flavor.folder_name()));
+        }
+
+        let mut addons_by_flavor = HashMap::new();
+        addons_by_flavor.insert(
+            Flavor::Retail,
+            vec![Addon::empty("WeakAuras"), Addon::empty("Details")],
+        );
+        addons_by_flavor.insert(
+            Flavor::Classic,
+            vec![Addon::empty("Questie"), Addon::empty("Details")],
+        );
+        // Not configured, so it's left out.
+        addons_by_flavor.insert(Flavor::ClassicPTR, vec![Addon::empty("Details")]);
+
+        let merged = merged_addon_view(&config, &addons_by_flavor)
+            .into_iter()
+            .map(|(flavor, addon)| (flavor, addon.title()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            merged,
+            vec![
+                (Flavor::Retail, "Details"),
+                (Flavor::Classic, "Details"),
+                (Flavor::Classic, "Questie"),
+                (Flavor::Retail, "WeakAuras"),
+            ]
+        );
+    }
+}
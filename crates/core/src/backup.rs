@@ -1,58 +1,547 @@
+use crate::config::{Config, Flavor};
 use crate::error::FilesystemError;
-use crate::fs::backup::{Backup, ZipBackup};
+use crate::fs::backup::{Backup, Manifest, ZipBackup, MANIFEST_NAME};
+use crate::fs::PersistentData;
+use crate::sha256::sha256_hex;
 
-use chrono::{Local, NaiveDateTime};
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
+use zip::result::ZipError;
 
 /// Creates a .zip archive from the list of source folders and
-/// saves it to the dest folder.
+/// saves it to the dest folder, deflated at `compression_level`. Entries
+/// matching any of the `exclude_patterns` globs are skipped. `tag` is encoded
+/// in the name of the archive, which follows `filename_pattern` if set.
+///
+/// If an archive named after `filename_pattern` already exists, eg. because
+/// the pattern has no `{time}`, the default naming is used instead of
+/// overwriting it.
 pub async fn backup_folders(
     src_folders: Vec<BackupFolder>,
     mut dest: PathBuf,
+    tag: BackupTag,
+    compression_level: Option<u32>,
+    exclude_patterns: Vec<String>,
+    filename_pattern: Option<String>,
 ) -> Result<NaiveDateTime, FilesystemError> {
-    let now = Local::now();
+    // Archive names only have a precision of seconds.
+    let as_of = Local::now().naive_local().with_nanosecond(0).unwrap();
 
-    dest.push(format!(
-        "ajour_backup_{}.zip",
-        now.format("%Y-%m-%d_%H-%M-%S")
-    ));
+    std::fs::create_dir_all(&dest)?;
 
-    let zip_backup = ZipBackup::new(src_folders, &dest);
+    let name = match filename_pattern.as_deref() {
+        Some(pattern) => {
+            let name = render_archive_name(pattern, tag, as_of);
+
+            if dest.join(&name).exists() {
+                log::warn!(
+                    "backup {:?} already exists, using the default name instead",
+                    name
+                );
+
+                archive_file_name(tag, as_of)
+            } else {
+                name
+            }
+        }
+        None => archive_file_name(tag, as_of),
+    };
+    dest.push(name);
+
+    let zip_backup = ZipBackup::new(src_folders, &dest)
+        .compression_level(compression_level)
+        .exclude_patterns(&exclude_patterns);
 
     zip_backup.backup()?;
 
-    // Won't fail since we pass it the correct format
-    let as_of = Archive::try_from(dest).unwrap().as_of;
+    Ok(as_of)
+}
+
+/// Creates an archive with `backup_folders` for each tag and its source folders
+/// in `archives`, and returns the datetime of the last one. Tags without any
+/// source folders are skipped.
+pub async fn backup_archives(
+    archives: Vec<(BackupTag, Vec<BackupFolder>)>,
+    dest: PathBuf,
+    compression_level: Option<u32>,
+    exclude_patterns: Vec<String>,
+    filename_pattern: Option<String>,
+) -> Result<NaiveDateTime, FilesystemError> {
+    let mut as_of = Local::now().naive_local();
+
+    for (tag, src_folders) in archives {
+        if src_folders.is_empty() {
+            continue;
+        }
+
+        as_of = backup_folders(
+            src_folders,
+            dest.clone(),
+            tag,
+            compression_level,
+            exclude_patterns.clone(),
+            filename_pattern.clone(),
+        )
+        .await?;
+    }
 
     Ok(as_of)
 }
 
-/// Finds the latest archive in the supplied backup folder and returns
-/// the datetime it was saved
-pub async fn latest_backup(backup_dir: PathBuf) -> Option<NaiveDateTime> {
-    let pattern = format!("{}/ajour_backup_[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]_[0-9][0-9]-[0-9][0-9]-[0-9][0-9].zip", &backup_dir.display());
+/// Kind of content in a backup archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackupKind {
+    Addons,
+    Wtf,
+    Config,
+}
 
-    let mut backups = vec![];
+impl BackupKind {
+    const ALL: [BackupKind; 3] = [BackupKind::Addons, BackupKind::Wtf, BackupKind::Config];
 
-    for entry in glob::glob(&pattern).unwrap() {
-        if let Ok(path) = entry {
-            if let Ok(archive) = Archive::try_from(path) {
-                backups.push(archive.as_of);
+    const fn as_str(self) -> &'static str {
+        match self {
+            BackupKind::Addons => "addons",
+            BackupKind::Wtf => "wtf",
+            BackupKind::Config => "config",
+        }
+    }
+}
+
+/// What a backup archive holds, encoded in its file name. An archive with
+/// several flavors or kinds of content leaves that part unset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BackupTag {
+    pub flavor: Option<Flavor>,
+    pub kind: Option<BackupKind>,
+}
+
+/// Returns the name of the archive for `tag` created at `now`, eg.
+/// `ajour_backup_classic_era_wtf_2021-03-04_05-06-07.zip`.
+fn archive_file_name(tag: BackupTag, now: NaiveDateTime) -> String {
+    let mut name = "ajour_backup_".to_owned();
+
+    if let Some(flavor) = tag.flavor {
+        name.push_str(flavor.folder_name().trim_matches('_'));
+        name.push('_');
+    }
+    if let Some(kind) = tag.kind {
+        name.push_str(kind.as_str());
+        name.push('_');
+    }
+
+    name.push_str(&now.format(ARCHIVE_DATE_FORMAT).to_string());
+    name.push_str(".zip");
+
+    name
+}
+
+/// Format of the datetime at the end of an archive name.
+const ARCHIVE_DATE_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// Parses the tag and datetime out of the file stem of an archive.
+fn parse_archive_stem(stem: &str) -> Option<(BackupTag, NaiveDateTime)> {
+    let rest = stem.strip_prefix("ajour_backup_")?;
+
+    // `YYYY-MM-DD_HH-MM-SS`
+    let date_start = rest.len().checked_sub(19)?;
+    let as_of = NaiveDateTime::parse_from_str(rest.get(date_start..)?, ARCHIVE_DATE_FORMAT).ok()?;
+
+    let mut tags = rest.get(..date_start)?;
+    let mut tag = BackupTag::default();
+
+    if tags.is_empty() {
+        return Some((tag, as_of));
+    }
+    tags = tags.strip_suffix('_')?;
+
+    for kind in BackupKind::ALL.iter() {
+        if tags == kind.as_str() {
+            tags = "";
+        } else if let Some(stripped) = tags
+            .strip_suffix(kind.as_str())
+            .and_then(|t| t.strip_suffix('_'))
+        {
+            tags = stripped;
+        } else {
+            continue;
+        }
+
+        tag.kind = Some(*kind);
+        break;
+    }
+
+    if !tags.is_empty() {
+        tag.flavor = Some(
+            *Flavor::ALL
+                .iter()
+                .find(|flavor| flavor.folder_name().trim_matches('_') == tags)?,
+        );
+    }
+
+    Some((tag, as_of))
+}
+
+/// Returns the name of a backup archive, following `Config::backup_filename_pattern`.
+///
+/// Supported tokens are `{flavor}`, `{date}` (`YYYY-MM-DD`), `{time}`
+/// (`HH-MM-SS`) and `{kind}`. Unknown tokens are kept as they are. A `.zip`
+/// extension is added if missing.
+pub fn render_backup_filename(
+    pattern: &str,
+    flavor: Flavor,
+    kind: BackupKind,
+    now: NaiveDateTime,
+) -> String {
+    let tag = BackupTag {
+        flavor: Some(flavor),
+        kind: Some(kind),
+    };
+
+    render_archive_name(pattern, tag, now)
+}
+
+/// Like `render_backup_filename`, but for any `tag`. Flavors and kinds which
+/// aren't set are rendered as `all`.
+fn render_archive_name(pattern: &str, tag: BackupTag, now: NaiveDateTime) -> String {
+    let mut name = String::with_capacity(pattern.len());
+
+    for segment in pattern_segments(pattern) {
+        match segment {
+            PatternSegment::Literal(literal) => name.push_str(literal),
+            PatternSegment::Unknown(token) => {
+                log::warn!("unknown token {} in backup filename pattern", token);
+
+                name.push_str(token);
+            }
+            PatternSegment::Flavor => name.push_str(&flavor_tag(tag.flavor)),
+            PatternSegment::Kind => {
+                name.push_str(tag.kind.map_or(ARCHIVE_TAG_ALL, BackupKind::as_str))
             }
+            PatternSegment::Date => name.push_str(&now.format("%Y-%m-%d").to_string()),
+            PatternSegment::Time => name.push_str(&now.format("%H-%M-%S").to_string()),
+        }
+    }
+
+    if !name.ends_with(".zip") {
+        name.push_str(".zip");
+    }
+
+    name
+}
+
+/// Value of `{flavor}` and `{kind}` for archives holding several of them.
+const ARCHIVE_TAG_ALL: &str = "all";
+
+fn flavor_tag(flavor: Option<Flavor>) -> String {
+    flavor.map_or_else(
+        || ARCHIVE_TAG_ALL.to_owned(),
+        |flavor| flavor.folder_name().trim_matches('_').to_owned(),
+    )
+}
+
+/// Part of a backup filename pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatternSegment<'a> {
+    Literal(&'a str),
+    /// A token which isn't supported, including its braces.
+    Unknown(&'a str),
+    Flavor,
+    Kind,
+    Date,
+    Time,
+}
+
+/// Splits `pattern` into its literals and tokens.
+fn pattern_segments(pattern: &str) -> Vec<PatternSegment<'_>> {
+    let mut segments = vec![];
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+
+        if start > 0 {
+            segments.push(PatternSegment::Literal(&rest[..start]));
         }
+
+        segments.push(match &rest[start + 1..end] {
+            "flavor" => PatternSegment::Flavor,
+            "kind" => PatternSegment::Kind,
+            "date" => PatternSegment::Date,
+            "time" => PatternSegment::Time,
+            _ => PatternSegment::Unknown(&rest[start..=end]),
+        });
+
+        rest = &rest[end + 1..];
     }
 
+    if !rest.is_empty() {
+        segments.push(PatternSegment::Literal(rest));
+    }
+
+    segments
+}
+
+/// What was parsed out of an archive name so far by `match_pattern`.
+#[derive(Debug, Clone, Copy, Default)]
+struct PatternMatch {
+    tag: BackupTag,
+    date: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+}
+
+/// Parses the tag and datetime out of `file_name`, if it was rendered from
+/// `pattern`. Names without a `{date}` can't be ordered, and aren't matched.
+fn parse_patterned_name(pattern: &str, file_name: &str) -> Option<(BackupTag, NaiveDateTime)> {
+    let mut segments = pattern_segments(pattern);
+    if !pattern.ends_with(".zip") {
+        segments.push(PatternSegment::Literal(".zip"));
+    }
+
+    let matched = match_pattern(&segments, file_name, PatternMatch::default())?;
+    let time = matched.time.unwrap_or(NaiveTime::MIN);
+
+    Some((matched.tag, matched.date?.and_time(time)))
+}
+
+fn match_pattern(
+    segments: &[PatternSegment<'_>],
+    name: &str,
+    mut matched: PatternMatch,
+) -> Option<PatternMatch> {
+    let (segment, segments) = match segments.split_first() {
+        Some(split) => split,
+        None => return if name.is_empty() { Some(matched) } else { None },
+    };
+
+    match segment {
+        PatternSegment::Literal(literal) | PatternSegment::Unknown(literal) => {
+            match_pattern(segments, name.strip_prefix(literal)?, matched)
+        }
+        // Flavors can be prefixes of each other, eg. `classic` and
+        // `classic_era`, so each one has to be tried.
+        PatternSegment::Flavor => std::iter::once(None)
+            .chain(Flavor::ALL.iter().copied().map(Some))
+            .find_map(|flavor| {
+                let rest = name.strip_prefix(flavor_tag(flavor).as_str())?;
+                let tag = BackupTag {
+                    flavor,
+                    ..matched.tag
+                };
+
+                match_pattern(segments, rest, PatternMatch { tag, ..matched })
+            }),
+        PatternSegment::Kind => std::iter::once(None)
+            .chain(BackupKind::ALL.iter().copied().map(Some))
+            .find_map(|kind| {
+                let rest = name.strip_prefix(kind.map_or(ARCHIVE_TAG_ALL, BackupKind::as_str))?;
+                let tag = BackupTag {
+                    kind,
+                    ..matched.tag
+                };
+
+                match_pattern(segments, rest, PatternMatch { tag, ..matched })
+            }),
+        PatternSegment::Date => {
+            matched.date = Some(NaiveDate::parse_from_str(name.get(..10)?, "%Y-%m-%d").ok()?);
+            match_pattern(segments, name.get(10..)?, matched)
+        }
+        PatternSegment::Time => {
+            matched.time = Some(NaiveTime::parse_from_str(name.get(..8)?, "%H-%M-%S").ok()?);
+            match_pattern(segments, name.get(8..)?, matched)
+        }
+    }
+}
+
+/// Finds the latest archive in the supplied backup folder and returns
+/// the datetime it was saved. Archives named after `filename_pattern` are
+/// found as well.
+pub async fn latest_backup(
+    backup_dir: PathBuf,
+    filename_pattern: Option<String>,
+) -> Option<NaiveDateTime> {
+    let mut backups: Vec<_> = archives(&backup_dir, filename_pattern.as_deref())
+        .into_iter()
+        .map(|a| a.as_of)
+        .collect();
+
     // Apparently NaiveDateTime sorts in Desc order by default, no need to reverse
     backups.sort();
     backups.pop()
 }
 
+/// Deletes all but the newest `keep` archives of each `BackupTag` in the
+/// supplied backup folder and returns the paths which were removed. Only files
+/// following the Ajour backup naming scheme, or `filename_pattern`, are
+/// touched. The newest archive is always kept, even if `keep` is 0.
+pub fn prune_backups(
+    backup_dir: &Path,
+    keep: u32,
+    filename_pattern: Option<&str>,
+) -> Result<Vec<PathBuf>, FilesystemError> {
+    let mut removed = vec![];
+
+    for path in prunable_backups(backup_dir, keep.max(1), filename_pattern) {
+        std::fs::remove_file(&path)?;
+        removed.push(path);
+    }
+
+    Ok(removed)
+}
+
+/// Returns the archives in the supplied backup folder which `prune_backups`
+/// would remove.
+fn prunable_backups(backup_dir: &Path, keep: u32, filename_pattern: Option<&str>) -> Vec<PathBuf> {
+    let mut archives = archives(backup_dir, filename_pattern);
+
+    // Newest first
+    archives.sort_by_key(|a| std::cmp::Reverse(a.as_of));
+
+    let mut kept: HashMap<BackupTag, u32> = HashMap::new();
+
+    archives
+        .into_iter()
+        .filter(|a| {
+            let count = kept.entry(a.tag).or_default();
+            *count += 1;
+
+            *count > keep
+        })
+        .map(|a| a.path)
+        .collect()
+}
+
+/// An archive found by `list_backups`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    /// `None` if the archive holds several flavors, or its name couldn't be
+    /// parsed.
+    pub flavor: Option<Flavor>,
+    /// `None` if the archive holds several kinds of content, or its name
+    /// couldn't be parsed.
+    pub kind: Option<BackupKind>,
+    /// `None` if the name of the archive couldn't be parsed.
+    pub created: Option<NaiveDateTime>,
+}
+
+/// Returns every .zip archive in the supplied backup folder, newest first.
+/// Archives which follow neither the Ajour backup naming scheme nor
+/// `filename_pattern` are listed last, with only their path set.
+pub fn list_backups(backup_dir: &Path, filename_pattern: Option<&str>) -> Vec<BackupInfo> {
+    let entries = match std::fs::read_dir(backup_dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut backups: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension() == Some(OsStr::new("zip")))
+        .map(
+            |path| match Archive::from_path(path.clone(), filename_pattern) {
+                Some(archive) => BackupInfo {
+                    path,
+                    flavor: archive.tag.flavor,
+                    kind: archive.tag.kind,
+                    created: Some(archive.as_of),
+                },
+                None => BackupInfo {
+                    path,
+                    flavor: None,
+                    kind: None,
+                    created: None,
+                },
+            },
+        )
+        .collect();
+
+    backups.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| a.path.cmp(&b.path)));
+
+    backups
+}
+
+/// What a backup would do, as returned by `plan_backup`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackupPlan {
+    /// Files which would be archived.
+    pub files: Vec<PathBuf>,
+    /// Existing archives which would be pruned afterwards.
+    pub pruned: Vec<PathBuf>,
+}
+
+impl std::fmt::Display for BackupPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Files to back up ({}):", self.files.len())?;
+        for path in &self.files {
+            writeln!(f, "  {}", path.display())?;
+        }
+
+        writeln!(f, "Backups to prune ({}):", self.pruned.len())?;
+        for path in &self.pruned {
+            writeln!(f, "  {}", path.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns what `backup_folders` followed by `prune_backups` would do, without
+/// writing or removing anything. Pruning only happens if `keep` is set, and
+/// the new archive counts towards `keep`, which is at least 1.
+pub fn plan_backup(
+    src_folders: Vec<BackupFolder>,
+    backup_dir: &Path,
+    exclude_patterns: &[String],
+    keep: Option<u32>,
+    filename_pattern: Option<&str>,
+) -> BackupPlan {
+    let files = ZipBackup::new(src_folders, backup_dir)
+        .exclude_patterns(exclude_patterns)
+        .files();
+
+    let pruned = keep.map_or_else(Vec::new, |keep| {
+        prunable_backups(backup_dir, keep.max(1) - 1, filename_pattern)
+    });
+
+    BackupPlan { files, pruned }
+}
+
+/// Returns all archives in the supplied backup folder, named either after the
+/// default naming scheme or `filename_pattern`.
+fn archives(backup_dir: &Path, filename_pattern: Option<&str>) -> Vec<Archive> {
+    let pattern = format!(
+        "{}/*.zip",
+        glob::Pattern::escape(&backup_dir.display().to_string())
+    );
+
+    let mut archives = vec![];
+
+    for entry in glob::glob(&pattern).unwrap() {
+        if let Ok(path) = entry {
+            if let Some(archive) = Archive::from_path(path, filename_pattern) {
+                archives.push(archive);
+            }
+        }
+    }
+
+    archives
+}
+
 /// Specifies a folder that we want backed up. `prefix` will get stripped out of
-/// the path of each entry in the archive.
+/// the path of each entry in the archive. If `archive_dir` is set, entries are
+/// placed under it inside the archive.
 pub struct BackupFolder {
     pub path: PathBuf,
     pub prefix: PathBuf,
+    pub archive_dir: Option<PathBuf>,
 }
 
 impl BackupFolder {
@@ -60,30 +549,846 @@ impl BackupFolder {
         BackupFolder {
             path: path.as_ref().to_owned(),
             prefix: prefix.as_ref().to_owned(),
+            archive_dir: None,
+        }
+    }
+
+    /// Places the entries under `dir` inside the archive.
+    pub fn archive_dir(mut self, dir: impl AsRef<Path>) -> BackupFolder {
+        self.archive_dir = Some(dir.as_ref().to_owned());
+        self
+    }
+}
+
+/// Returns the Ajour config file and user themes inside `config_dir` as
+/// `BackupFolder`s, placed under `config/` inside the archive. Only existing
+/// paths are returned.
+pub fn config_backup_folders(config_dir: &Path) -> Vec<BackupFolder> {
+    [
+        config_dir.join(Config::relative_path()),
+        config_dir.join("themes"),
+    ]
+    .iter()
+    .filter(|path| path.exists())
+    .map(|path| BackupFolder::new(path, config_dir).archive_dir("config"))
+    .collect()
+}
+
+/// Outcome of `verify_backup`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Number of files which match their checksum.
+    pub verified: usize,
+    /// Files which don't match their checksum, or couldn't be read.
+    pub mismatched: Vec<String>,
+    /// Files listed in the manifest which aren't in the archive.
+    pub missing: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Returns `true` if every file in the manifest matches its checksum.
+    pub fn is_ok(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Checks every file in `archive` against the checksum in its manifest.
+///
+/// Archives created before manifests were added fail with
+/// `ZipError::FileNotFound`.
+pub fn verify_backup(archive: &Path) -> Result<VerifyReport, FilesystemError> {
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+
+    let manifest: Manifest = {
+        let mut contents = String::new();
+        zip.by_name(MANIFEST_NAME)?.read_to_string(&mut contents)?;
+
+        serde_json::from_str(&contents).map_err(io::Error::from)?
+    };
+
+    let mut report = VerifyReport::default();
+    for entry in manifest.files {
+        let file = match zip.by_name(&entry.path) {
+            Ok(file) => file,
+            Err(ZipError::FileNotFound) => {
+                report.missing.push(entry.path);
+                continue;
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        // A damaged entry fails its CRC check while reading.
+        if sha256_hex(file).is_ok_and(|sha256| sha256 == entry.sha256) {
+            report.verified += 1;
+        } else {
+            report.mismatched.push(entry.path);
+        }
+    }
+
+    Ok(report)
+}
+
+/// How `restore_backup` treats files which already exist in the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Replaces the existing file.
+    Overwrite,
+    /// Keeps the existing file.
+    Skip,
+    /// Renames the existing file to `<name>.bak` before restoring, or to
+    /// `<name>.bak.1`, `<name>.bak.2` and so on if that's taken.
+    Backup,
+}
+
+/// Outcome of `restore_backup`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    /// Files which were written.
+    pub restored: Vec<PathBuf>,
+    /// Files which already existed and were kept.
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Extracts `archive` into `target`, which should be the folder the archive
+/// was created from, eg. the World of Warcraft directory for addons and WTF
+/// backups.
+///
+/// `target` may also be the folder the archive holds, eg. `_retail_/WTF` for
+/// a WTF backup. Folders at the root of the archive which `target` already
+/// ends with aren't created again inside it.
+///
+/// Fails with `FilesystemError::RestoreKindMismatch` if `target` is a WTF
+/// folder and the archive doesn't hold WTF content, or the other way around
+/// for AddOns and Interface folders.
+pub fn restore_backup(
+    archive: &Path,
+    target: &Path,
+    on_conflict: ConflictPolicy,
+) -> Result<RestoreReport, FilesystemError> {
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+
+    let kind = Archive::try_from(archive.to_path_buf())
+        .ok()
+        .and_then(|a| a.tag.kind)
+        .or_else(|| content_kind(&zip));
+
+    if let Some(kind) = kind {
+        if target_kind(target).is_some_and(|target_kind| target_kind != kind) {
+            return Err(FilesystemError::RestoreKindMismatch {
+                kind,
+                target: target.to_path_buf(),
+            });
+        }
+    }
+
+    let mut report = RestoreReport::default();
+
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+
+        let name = match file.enclosed_name() {
+            Some(name) => name.to_path_buf(),
+            None => {
+                log::warn!(
+                    "skipping {} in {:?}, as it's outside the archive",
+                    file.name(),
+                    archive
+                );
+                continue;
+            }
+        };
+        if name == Path::new(MANIFEST_NAME) {
+            continue;
+        }
+
+        let path = target.join(strip_target_overlap(target, &name));
+
+        if file.is_dir() {
+            std::fs::create_dir_all(&path)?;
+            continue;
         }
+
+        if path.exists() {
+            match on_conflict {
+                ConflictPolicy::Overwrite => {}
+                ConflictPolicy::Skip => {
+                    report.skipped.push(path);
+                    continue;
+                }
+                ConflictPolicy::Backup => {
+                    std::fs::rename(&path, unused_backup_path(&path))?;
+                }
+            }
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        io::copy(&mut file, &mut File::create(&path)?)?;
+        report.restored.push(path);
+    }
+
+    Ok(report)
+}
+
+/// Strips the leading folders of `name` which `target` already ends with, so
+/// `WTF/Config.wtf` restored into `_retail_/WTF` isn't written to
+/// `_retail_/WTF/WTF/Config.wtf`.
+fn strip_target_overlap(target: &Path, name: &Path) -> PathBuf {
+    let target: Vec<_> = target.components().collect();
+    let name: Vec<_> = name.components().collect();
+
+    let overlap = (1..=target.len().min(name.len()))
+        .rev()
+        .find(|len| {
+            target[target.len() - len..]
+                .iter()
+                .zip(&name[..*len])
+                .all(|(a, b)| {
+                    a.as_os_str()
+                        .to_string_lossy()
+                        .eq_ignore_ascii_case(&b.as_os_str().to_string_lossy())
+                })
+        })
+        .unwrap_or(0);
+
+    name[overlap..].iter().collect()
+}
+
+/// Returns `<path>.bak`, or the first of `<path>.bak.1`, `<path>.bak.2`, ...
+/// which doesn't exist yet.
+fn unused_backup_path(path: &Path) -> PathBuf {
+    let mut backup_path = path.as_os_str().to_owned();
+    backup_path.push(".bak");
+
+    let mut candidate = PathBuf::from(&backup_path);
+    let mut n = 1;
+    while candidate.exists() {
+        let mut numbered = backup_path.clone();
+        numbered.push(format!(".{}", n));
+        candidate = PathBuf::from(numbered);
+        n += 1;
+    }
+
+    candidate
+}
+
+/// Returns the kind of content in `zip` by its top level folders, or `None`
+/// if it holds several kinds.
+fn content_kind(zip: &zip::ZipArchive<File>) -> Option<BackupKind> {
+    let mut kinds = zip
+        .file_names()
+        .filter(|name| *name != MANIFEST_NAME && !name.ends_with('/'))
+        .map(|name| {
+            let components: Vec<_> = name.split('/').take(2).collect();
+
+            if components[0] == "config" {
+                return Some(BackupKind::Config);
+            }
+
+            // Addons and WTF folders can be nested in their flavor folder
+            components.iter().find_map(|component| match *component {
+                "Interface" => Some(BackupKind::Addons),
+                "WTF" => Some(BackupKind::Wtf),
+                _ => None,
+            })
+        });
+
+    let first = kinds.next()??;
+
+    if kinds.all(|kind| kind == Some(first)) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+/// Returns the kind of content `target` is meant for, if it's a WTF or
+/// addons folder.
+fn target_kind(target: &Path) -> Option<BackupKind> {
+    let name = target.file_name()?.to_str()?.to_lowercase();
+
+    match name.as_str() {
+        "wtf" => Some(BackupKind::Wtf),
+        "addons" | "interface" => Some(BackupKind::Addons),
+        _ => None,
     }
 }
 
 /// Metadata for our archive saved on the filesystem. Converted from a `PathBuf` with
 /// the correct naming convention
 struct Archive {
+    pub path: PathBuf,
+    pub tag: BackupTag,
     pub as_of: NaiveDateTime,
 }
 
+impl Archive {
+    /// Parses `path` following `filename_pattern`, falling back to the default
+    /// naming scheme.
+    fn from_path(path: PathBuf, filename_pattern: Option<&str>) -> Option<Archive> {
+        let parsed = filename_pattern.and_then(|pattern| {
+            let file_name = path.file_name()?.to_str()?;
+
+            parse_patterned_name(pattern, file_name)
+        });
+
+        match parsed {
+            Some((tag, as_of)) => Some(Archive { path, tag, as_of }),
+            None => Archive::try_from(path).ok(),
+        }
+    }
+}
+
 impl TryFrom<PathBuf> for Archive {
-    type Error = chrono::ParseError;
+    type Error = PathBuf;
+
+    fn try_from(path: PathBuf) -> Result<Archive, PathBuf> {
+        let parsed = path
+            .file_stem()
+            .and_then(OsStr::to_str)
+            .and_then(parse_archive_stem);
+
+        match parsed {
+            Some((tag, as_of)) => Ok(Archive { path, tag, as_of }),
+            None => Err(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn try_from(path: PathBuf) -> Result<Archive, chrono::ParseError> {
-        let file_stem = path.file_stem().unwrap().to_str().unwrap();
+    use chrono::NaiveDate;
+    use std::fs::{create_dir_all, write, File};
 
-        let date_str = format!(
-            "{} {}",
-            file_stem.split('_').nth(2).unwrap_or_default(),
-            file_stem.split('_').nth(3).unwrap_or_default()
+    #[test]
+    fn test_render_backup_filename() {
+        let now = NaiveDate::from_ymd_opt(2021, 3, 4)
+            .unwrap()
+            .and_hms_opt(5, 6, 7)
+            .unwrap();
+
+        assert_eq!(
+            render_backup_filename(
+                "{kind}_{flavor}_{date}_{time}",
+                Flavor::ClassicEra,
+                BackupKind::Wtf,
+                now
+            ),
+            "wtf_classic_era_2021-03-04_05-06-07.zip"
+        );
+        assert_eq!(
+            render_backup_filename("{date}-{kind}.zip", Flavor::Retail, BackupKind::Addons, now),
+            "2021-03-04-addons.zip"
         );
+        assert_eq!(
+            render_backup_filename("{user}_{kind}_{", Flavor::Retail, BackupKind::Config, now),
+            "{user}_config_{.zip"
+        );
+    }
+
+    #[test]
+    fn test_parse_patterned_name() {
+        let now = NaiveDate::from_ymd_opt(2021, 3, 4)
+            .unwrap()
+            .and_hms_opt(5, 6, 7)
+            .unwrap();
+        let pattern = "{flavor}_{kind}_{date}_{time}";
 
-        let as_of = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H-%M-%S")?;
+        for flavor in [None, Some(Flavor::Classic), Some(Flavor::ClassicEra)].iter() {
+            for kind in [None, Some(BackupKind::Wtf)].iter() {
+                let tag = BackupTag {
+                    flavor: *flavor,
+                    kind: *kind,
+                };
+                let name = render_archive_name(pattern, tag, now);
 
-        Ok(Archive { as_of })
+                assert_eq!(parse_patterned_name(pattern, &name), Some((tag, now)));
+            }
+        }
+
+        // Without a time, archives are from midnight.
+        assert_eq!(
+            parse_patterned_name("backup {date}.zip", "backup 2021-03-04.zip"),
+            Some((BackupTag::default(), now.date().and_time(NaiveTime::MIN)))
+        );
+        assert_eq!(parse_patterned_name("{flavor}", "retail.zip"), None);
+        assert_eq!(parse_patterned_name("{date}", "notes.zip"), None);
+        assert_eq!(parse_patterned_name("{date}", "2021-03-04.zip.zip"), None);
+    }
+
+    #[test]
+    fn test_patterned_backups() {
+        let root = tempfile::tempdir().unwrap();
+        let wtf_dir = root.path().join("_retail_/WTF");
+        create_dir_all(&wtf_dir).unwrap();
+        write(wtf_dir.join("Config.wtf"), "").unwrap();
+
+        let dest = root.path().join("backups");
+        let tag = BackupTag {
+            flavor: Some(Flavor::Retail),
+            kind: Some(BackupKind::Wtf),
+        };
+        let pattern = "{flavor}-{kind}-{date}";
+        let backup = || {
+            async_std::task::block_on(backup_folders(
+                vec![BackupFolder::new(&wtf_dir, root.path().join("_retail_"))],
+                dest.clone(),
+                tag,
+                None,
+                vec![],
+                Some(pattern.to_owned()),
+            ))
+            .unwrap()
+        };
+
+        let first = backup();
+        let patterned = dest.join(format!("retail-wtf-{}.zip", first.format("%Y-%m-%d")));
+        assert!(patterned.exists());
+
+        // The second backup of the day can't reuse the name.
+        let second = backup();
+        let default = dest.join(archive_file_name(tag, second));
+        assert!(default.exists());
+
+        let backups = list_backups(&dest, Some(pattern));
+        assert!(backups
+            .iter()
+            .all(|backup| backup.flavor == Some(Flavor::Retail) && backup.created.is_some()));
+        assert_eq!(
+            async_std::task::block_on(latest_backup(dest.clone(), Some(pattern.to_owned()))),
+            Some(second)
+        );
+
+        assert_eq!(
+            prune_backups(&dest, 1, Some(pattern)).unwrap(),
+            vec![patterned]
+        );
+        assert!(default.exists());
+    }
+
+    #[test]
+    fn test_backup_config() {
+        let root = tempfile::tempdir().unwrap();
+        let config_path = root.path().join("ajour");
+        create_dir_all(config_path.join("themes")).unwrap();
+        write(config_path.join("ajour.yml"), "---\n").unwrap();
+        write(config_path.join("themes/custom.yml"), "---\n").unwrap();
+
+        let wtf_dir = root.path().join("_retail_/WTF");
+        create_dir_all(&wtf_dir).unwrap();
+        write(wtf_dir.join("Config.wtf"), "").unwrap();
+
+        let mut src_folders = config_backup_folders(&config_path);
+        src_folders.push(BackupFolder::new(&wtf_dir, root.path().join("_retail_")));
+
+        let dest = root.path().join("backups");
+        create_dir_all(&dest).unwrap();
+        async_std::task::block_on(backup_folders(
+            src_folders,
+            dest.clone(),
+            BackupTag::default(),
+            None,
+            vec![],
+            None,
+        ))
+        .unwrap();
+
+        let archive = std::fs::read_dir(&dest).unwrap().next().unwrap().unwrap();
+        let zip = zip::ZipArchive::new(File::open(archive.path()).unwrap()).unwrap();
+        let names: Vec<_> = zip.file_names().collect();
+
+        assert!(names.contains(&"config/ajour.yml"));
+        assert!(names.contains(&"config/themes/custom.yml"));
+        assert!(names.contains(&"WTF/Config.wtf"));
+    }
+
+    #[test]
+    fn test_verify_backup() {
+        let root = tempfile::tempdir().unwrap();
+        let wtf_dir = root.path().join("_retail_/WTF");
+        create_dir_all(&wtf_dir).unwrap();
+        write(wtf_dir.join("Config.wtf"), "SET foo \"bar\"").unwrap();
+        write(wtf_dir.join("Macros.txt"), "/cast Fireball").unwrap();
+        write(wtf_dir.join("Bindings.txt"), "bind 1").unwrap();
+
+        let dest = root.path().join("backups");
+        async_std::task::block_on(backup_folders(
+            vec![BackupFolder::new(&wtf_dir, root.path().join("_retail_"))],
+            dest.clone(),
+            BackupTag::default(),
+            None,
+            vec![],
+            None,
+        ))
+        .unwrap();
+
+        let archive = std::fs::read_dir(&dest)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let report = verify_backup(&archive).unwrap();
+        assert_eq!(report.verified, 3);
+        assert!(report.is_ok());
+
+        // Rewrite the archive with one file changed and one left out.
+        let tampered = root.path().join("tampered.zip");
+        {
+            let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+            let mut writer = zip::ZipWriter::new(File::create(&tampered).unwrap());
+
+            for i in 0..zip.len() {
+                let mut file = zip.by_index(i).unwrap();
+                let name = file.name().to_owned();
+                let mut contents = vec![];
+                file.read_to_end(&mut contents).unwrap();
+
+                match name.as_str() {
+                    "WTF/Macros.txt" => continue,
+                    "WTF/Config.wtf" => contents = b"SET foo \"baz\"".to_vec(),
+                    _ => {}
+                }
+
+                if file.is_dir() {
+                    writer.add_directory(name, Default::default()).unwrap();
+                } else {
+                    writer.start_file(name, Default::default()).unwrap();
+                    std::io::Write::write_all(&mut writer, &contents).unwrap();
+                }
+            }
+
+            writer.finish().unwrap();
+        }
+
+        let report = verify_backup(&tampered).unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(report.verified, 1);
+        assert_eq!(report.mismatched, vec!["WTF/Config.wtf".to_owned()]);
+        assert_eq!(report.missing, vec!["WTF/Macros.txt".to_owned()]);
+    }
+
+    #[test]
+    fn test_restore_backup() {
+        let root = tempfile::tempdir().unwrap();
+        let wtf_dir = root.path().join("_retail_/WTF");
+        create_dir_all(&wtf_dir).unwrap();
+        write(wtf_dir.join("Config.wtf"), "backed up").unwrap();
+        write(wtf_dir.join("Macros.txt"), "backed up").unwrap();
+
+        let dest = root.path().join("backups");
+        let tag = BackupTag {
+            flavor: Some(Flavor::Retail),
+            kind: Some(BackupKind::Wtf),
+        };
+        async_std::task::block_on(backup_folders(
+            vec![BackupFolder::new(&wtf_dir, root.path().join("_retail_"))],
+            dest.clone(),
+            tag,
+            None,
+            vec![],
+            None,
+        ))
+        .unwrap();
+        let archive = list_backups(&dest, None).remove(0).path;
+
+        let target = root.path().join("restore");
+        let config_wtf = target.join("WTF/Config.wtf");
+        let macros = target.join("WTF/Macros.txt");
+        create_dir_all(target.join("WTF")).unwrap();
+        write(&config_wtf, "existing").unwrap();
+
+        let report = restore_backup(&archive, &target, ConflictPolicy::Skip).unwrap();
+        assert_eq!(report.restored, vec![macros.clone()]);
+        assert_eq!(report.skipped, vec![config_wtf.clone()]);
+        assert_eq!(std::fs::read_to_string(&config_wtf).unwrap(), "existing");
+        assert_eq!(std::fs::read_to_string(&macros).unwrap(), "backed up");
+        assert!(!target.join(MANIFEST_NAME).exists());
+
+        let mut report = restore_backup(&archive, &target, ConflictPolicy::Overwrite).unwrap();
+        report.restored.sort();
+        assert_eq!(report.restored, vec![config_wtf.clone(), macros]);
+        assert!(report.skipped.is_empty());
+        assert_eq!(std::fs::read_to_string(&config_wtf).unwrap(), "backed up");
+
+        write(&config_wtf, "existing").unwrap();
+        restore_backup(&archive, &target, ConflictPolicy::Backup).unwrap();
+        assert_eq!(std::fs::read_to_string(&config_wtf).unwrap(), "backed up");
+        assert_eq!(
+            std::fs::read_to_string(target.join("WTF/Config.wtf.bak")).unwrap(),
+            "existing"
+        );
+
+        // An earlier `.bak` is kept.
+        restore_backup(&archive, &target, ConflictPolicy::Backup).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(target.join("WTF/Config.wtf.bak")).unwrap(),
+            "existing"
+        );
+        assert_eq!(
+            std::fs::read_to_string(target.join("WTF/Config.wtf.bak.1")).unwrap(),
+            "backed up"
+        );
+
+        // Restoring straight into a WTF folder doesn't nest another WTF folder.
+        let wtf_target = root.path().join("restore_wtf/WTF");
+        let report = restore_backup(&archive, &wtf_target, ConflictPolicy::Skip).unwrap();
+        assert_eq!(report.restored.len(), 2);
+        assert!(wtf_target.join("Config.wtf").exists());
+        assert!(wtf_target.join("Macros.txt").exists());
+        assert!(!wtf_target.join("WTF").exists());
+
+        // WTF content can't be restored into an AddOns folder, and addons
+        // can't be restored into a WTF folder, even from an untagged archive.
+        let addons_dir = target.join("Interface/AddOns");
+        create_dir_all(&addons_dir).unwrap();
+        assert!(matches!(
+            restore_backup(&archive, &addons_dir, ConflictPolicy::Skip),
+            Err(FilesystemError::RestoreKindMismatch {
+                kind: BackupKind::Wtf,
+                ..
+            })
+        ));
+
+        let untagged = root.path().join("addons.zip");
+        {
+            let mut writer = zip::ZipWriter::new(File::create(&untagged).unwrap());
+            writer
+                .start_file("_retail_/Interface/AddOns/Foo/Foo.toc", Default::default())
+                .unwrap();
+            writer.finish().unwrap();
+        }
+        assert!(matches!(
+            restore_backup(&untagged, &target.join("WTF"), ConflictPolicy::Skip),
+            Err(FilesystemError::RestoreKindMismatch {
+                kind: BackupKind::Addons,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_plan_backup() {
+        let root = tempfile::tempdir().unwrap();
+        let wtf_dir = root.path().join("_retail_/WTF");
+        create_dir_all(wtf_dir.join("Logs")).unwrap();
+        write(wtf_dir.join("Config.wtf"), "").unwrap();
+        write(wtf_dir.join("Logs/combat.log"), "").unwrap();
+
+        let dest = root.path().join("backups");
+        create_dir_all(&dest).unwrap();
+        for name in [
+            "ajour_backup_2021-01-01_10-00-00.zip",
+            "ajour_backup_2021-02-01_10-00-00.zip",
+        ]
+        .iter()
+        {
+            write(dest.join(name), "").unwrap();
+        }
+
+        let plan = plan_backup(
+            vec![BackupFolder::new(&wtf_dir, root.path().join("_retail_"))],
+            &dest,
+            &["**/*.log".to_owned()],
+            Some(2),
+            None,
+        );
+
+        assert_eq!(plan.files, vec![wtf_dir.join("Config.wtf")]);
+        assert_eq!(
+            plan.pruned,
+            vec![dest.join("ajour_backup_2021-01-01_10-00-00.zip")]
+        );
+        assert!(plan.to_string().contains("Backups to prune (1):"));
+
+        // Nothing is written or removed.
+        assert_eq!(std::fs::read_dir(&dest).unwrap().count(), 2);
+        assert!(wtf_dir.join("Logs/combat.log").exists());
+
+        let plan = plan_backup(vec![], &dest, &[], None, None);
+        assert_eq!(plan, BackupPlan::default());
+    }
+
+    #[test]
+    fn test_list_backups() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for name in [
+            "ajour_backup_retail_2021-01-01_10-00-00.zip",
+            "ajour_backup_classic_era_wtf_2021-03-01_10-00-00.zip",
+            "ajour_backup_ptr_addons_2021-02-01_10-00-00.zip",
+            "ajour_backup_config_2021-02-01_09-00-00.zip",
+            "ajour_backup_2020-12-24_08-30-00.zip",
+            "ajour_backup_unknown_2021-01-01_10-00-00.zip",
+            "my backup.zip",
+            "notes.txt",
+        ]
+        .iter()
+        {
+            write(dir.path().join(name), "").unwrap();
+        }
+
+        let date = |m, d, h| {
+            Some(
+                NaiveDate::from_ymd_opt(2021, m, d)
+                    .unwrap()
+                    .and_hms_opt(h, 0, 0)
+                    .unwrap(),
+            )
+        };
+        let info = |name: &str, flavor, kind, created| BackupInfo {
+            path: dir.path().join(name),
+            flavor,
+            kind,
+            created,
+        };
+
+        assert_eq!(
+            list_backups(dir.path(), None),
+            vec![
+                info(
+                    "ajour_backup_classic_era_wtf_2021-03-01_10-00-00.zip",
+                    Some(Flavor::ClassicEra),
+                    Some(BackupKind::Wtf),
+                    date(3, 1, 10)
+                ),
+                info(
+                    "ajour_backup_ptr_addons_2021-02-01_10-00-00.zip",
+                    Some(Flavor::RetailPTR),
+                    Some(BackupKind::Addons),
+                    date(2, 1, 10)
+                ),
+                info(
+                    "ajour_backup_config_2021-02-01_09-00-00.zip",
+                    None,
+                    Some(BackupKind::Config),
+                    date(2, 1, 9)
+                ),
+                info(
+                    "ajour_backup_retail_2021-01-01_10-00-00.zip",
+                    Some(Flavor::Retail),
+                    None,
+                    date(1, 1, 10)
+                ),
+                info(
+                    "ajour_backup_2020-12-24_08-30-00.zip",
+                    None,
+                    None,
+                    Some(
+                        NaiveDate::from_ymd_opt(2020, 12, 24)
+                            .unwrap()
+                            .and_hms_opt(8, 30, 0)
+                            .unwrap()
+                    )
+                ),
+                info(
+                    "ajour_backup_unknown_2021-01-01_10-00-00.zip",
+                    None,
+                    None,
+                    None
+                ),
+                info("my backup.zip", None, None, None),
+            ]
+        );
+
+        let now = NaiveDate::from_ymd_opt(2021, 3, 4)
+            .unwrap()
+            .and_hms_opt(5, 6, 7)
+            .unwrap();
+        let tag = BackupTag {
+            flavor: Some(Flavor::ClassicPTR),
+            kind: Some(BackupKind::Addons),
+        };
+        let name = archive_file_name(tag, now);
+        assert_eq!(
+            name,
+            "ajour_backup_classic_ptr_addons_2021-03-04_05-06-07.zip"
+        );
+        assert_eq!(
+            parse_archive_stem(name.trim_end_matches(".zip")),
+            Some((tag, now))
+        );
+    }
+
+    #[test]
+    fn test_prune_backups_per_tag() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for name in [
+            "ajour_backup_retail_2021-01-01_10-00-00.zip",
+            "ajour_backup_retail_2021-02-01_10-00-00.zip",
+            "ajour_backup_classic_2021-01-01_10-00-00.zip",
+        ]
+        .iter()
+        {
+            write(dir.path().join(name), "").unwrap();
+        }
+
+        assert_eq!(
+            prune_backups(dir.path(), 1, None).unwrap(),
+            vec![dir
+                .path()
+                .join("ajour_backup_retail_2021-01-01_10-00-00.zip")]
+        );
+    }
+
+    #[test]
+    fn test_prune_backups() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let archives = [
+            "ajour_backup_2021-01-01_10-00-00.zip",
+            "ajour_backup_2021-03-01_10-00-00.zip",
+            "ajour_backup_2021-02-01_10-00-00.zip",
+            "ajour_backup_2020-12-24_08-30-00.zip",
+        ];
+        for name in archives.iter() {
+            write(dir.path().join(name), "").unwrap();
+        }
+
+        // Unrelated files must be left alone.
+        write(dir.path().join("ajour_backup_notes.zip"), "").unwrap();
+        write(dir.path().join("notes.txt"), "").unwrap();
+
+        let mut removed = prune_backups(dir.path(), 2, None).unwrap();
+        removed.sort();
+
+        assert_eq!(
+            removed,
+            vec![
+                dir.path().join("ajour_backup_2020-12-24_08-30-00.zip"),
+                dir.path().join("ajour_backup_2021-01-01_10-00-00.zip"),
+            ]
+        );
+
+        let mut remaining: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        remaining.sort();
+
+        assert_eq!(
+            remaining,
+            vec![
+                "ajour_backup_2021-02-01_10-00-00.zip",
+                "ajour_backup_2021-03-01_10-00-00.zip",
+                "ajour_backup_notes.zip",
+                "notes.txt",
+            ]
+        );
+
+        // Keeping none still keeps the newest archive.
+        assert_eq!(
+            prune_backups(dir.path(), 0, None).unwrap(),
+            vec![dir.path().join("ajour_backup_2021-02-01_10-00-00.zip")]
+        );
+        assert!(dir
+            .path()
+            .join("ajour_backup_2021-03-01_10-00-00.zip")
+            .exists());
     }
 }
@@ -4,18 +4,21 @@ use crate::parse::Fingerprint;
 use crate::repository::RepositoryKind;
 use crate::{
     addon::{Addon, AddonFolder},
-    catalog::{download_catalog, Catalog},
+    catalog::{self, catalog_url, download_catalog, Catalog},
 };
 use crate::{config::Flavor, error::DownloadError};
 
 use async_std::fs::rename;
 use async_std::sync::{Arc, Mutex};
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
 #[derive(Serialize, Deserialize, Default, Debug)]
 pub struct FingerprintCache(HashMap<Flavor, Vec<Fingerprint>>);
@@ -78,7 +81,7 @@ impl AddonCache {
 
 impl PersistentData for AddonCache {
     fn relative_path() -> PathBuf {
-        PathBuf::from("cache/addons.yml")
+        Path::new("cache").join(ADDON_CACHE_FILE)
     }
 }
 
@@ -243,6 +246,8 @@ pub enum ExternalReleaseId {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CatalogCache {
     etag: String,
+    #[serde(default)]
+    url: Option<String>,
     catalog: Catalog,
 }
 
@@ -252,20 +257,31 @@ impl PersistentData for CatalogCache {
     }
 }
 
-pub async fn catalog_download_latest_or_use_cache() -> Result<Catalog, DownloadError> {
-    let maybe_cached_catalog = CatalogCache::load();
+pub async fn catalog_download_latest_or_use_cache(
+    source: Option<catalog::Source>,
+) -> Result<Catalog, DownloadError> {
+    let url = catalog_url(source.as_ref())?;
+    let default_url = catalog_url(None)?;
+
+    // Cache is only used if it was downloaded from the same url. Caches
+    // without a url are from the official catalog.
+    let maybe_cached_catalog = CatalogCache::load()
+        .ok()
+        .filter(|c| c.url.as_ref().unwrap_or(&default_url) == &url);
 
     // If no cache file exists yet, this will be None and download_catalog will
     // always download the latest catalog
-    let cached_etag = maybe_cached_catalog.as_ref().map(|c| c.etag.clone()).ok();
+    let cached_etag = maybe_cached_catalog.as_ref().map(|c| c.etag.clone());
 
-    if let Some((downloaded_etag, downloaded_catalog)) = download_catalog(cached_etag).await? {
+    if let Some((downloaded_etag, downloaded_catalog)) = download_catalog(&url, cached_etag).await?
+    {
         // Etag didn't match latest catalog, so we downloaded new one. Let's update
         // our cache with it
         if let Some(etag) = downloaded_etag {
             // Save it as cache
             let new_cache = CatalogCache {
                 catalog: downloaded_catalog.clone(),
+                url: Some(url),
                 etag,
             };
             new_cache.save()?;
@@ -274,12 +290,113 @@ pub async fn catalog_download_latest_or_use_cache() -> Result<Catalog, DownloadE
         Ok(downloaded_catalog)
     } else {
         // If download_catalog returns None, we have the latest cache file, so use it
-        let cache = maybe_cached_catalog?;
+        let cache = maybe_cached_catalog.ok_or(DownloadError::CatalogFailed)?;
 
         Ok(cache.catalog)
     }
 }
 
+/// Name of the `AddonCache` file inside the cache directory. It's kept when the
+/// cache is cleared, as the sources of addons installed from outside the
+/// catalog can't be rebuilt.
+const ADDON_CACHE_FILE: &str = "addons.yml";
+
+/// Addon archives inside the cache which are being downloaded or are waiting
+/// to be installed. Clearing the cache leaves them alone.
+static IN_FLIGHT_DOWNLOADS: Lazy<std::sync::Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
+
+/// Marks the addon archive at `path` as in flight, until `unmark_in_flight`.
+pub(crate) fn mark_in_flight(path: &Path) {
+    IN_FLIGHT_DOWNLOADS
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf());
+}
+
+/// Clears the mark set by `mark_in_flight`.
+pub(crate) fn unmark_in_flight(path: &Path) {
+    IN_FLIGHT_DOWNLOADS.lock().unwrap().remove(path);
+}
+
+/// What `Config::clear_cache` removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheClearReport {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+}
+
+/// Removes everything inside `cache_dir`, but not `cache_dir` itself, except
+/// for the `AddonCache` and addon archives in flight. Symlinks are removed
+/// without touching what they point to.
+pub(crate) fn clear_cache_dir(cache_dir: &Path) -> Result<CacheClearReport, FilesystemError> {
+    let mut report = CacheClearReport::default();
+
+    if !cache_dir.is_dir() {
+        return Ok(report);
+    }
+
+    let mut kept: Vec<_> = IN_FLIGHT_DOWNLOADS
+        .lock()
+        .unwrap()
+        .iter()
+        .cloned()
+        .collect();
+    kept.push(cache_dir.join(ADDON_CACHE_FILE));
+
+    clear_dir(cache_dir, &kept, &mut report)?;
+
+    log::debug!(
+        "cleared {:?}: {} files, {} bytes",
+        cache_dir,
+        report.files_removed,
+        report.bytes_freed
+    );
+
+    Ok(report)
+}
+
+/// Removes everything inside `dir` except for the `kept` paths, tallying it in
+/// `report`. Directories holding a kept path are cleared instead of removed.
+fn clear_dir(
+    dir: &Path,
+    kept: &[PathBuf],
+    report: &mut CacheClearReport,
+) -> Result<(), FilesystemError> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let metadata = fs::symlink_metadata(&path)?;
+
+        if kept.contains(&path) {
+            continue;
+        }
+
+        if metadata.is_dir() && kept.iter().any(|kept| kept.starts_with(&path)) {
+            clear_dir(&path, kept, report)?;
+            continue;
+        }
+
+        // Walking doesn't follow symlinks, so only files inside the cache are
+        // counted.
+        for file in WalkDir::new(&path).into_iter().filter_map(Result::ok) {
+            if !file.file_type().is_dir() {
+                report.files_removed += 1;
+
+                if file.file_type().is_file() {
+                    report.bytes_freed += file.metadata().map_or(0, |m| m.len());
+                }
+            }
+        }
+
+        if metadata.is_dir() {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -354,4 +471,50 @@ mod test {
             assert_eq!(names, vec!["Test2".to_string()]);
         });
     }
+
+    #[test]
+    fn test_clear_cache_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let cache_dir = root.path().join("cache");
+        let config_file = root.path().join("ajour.yml");
+        fs::write(&config_file, "theme: Dark").unwrap();
+
+        // A missing cache is nothing to clear.
+        assert_eq!(
+            clear_cache_dir(&cache_dir).unwrap(),
+            CacheClearReport::default()
+        );
+
+        fs::create_dir_all(cache_dir.join("addons/retail")).unwrap();
+        fs::create_dir_all(cache_dir.join("addons/classic")).unwrap();
+        fs::write(cache_dir.join("catalog.yml"), vec![0; 100]).unwrap();
+        fs::write(cache_dir.join("fingerprints.yml"), vec![0; 20]).unwrap();
+        fs::write(cache_dir.join(ADDON_CACHE_FILE), vec![0; 50]).unwrap();
+        fs::write(cache_dir.join("addons/retail/WeakAuras.zip"), vec![0; 3]).unwrap();
+        fs::write(cache_dir.join("addons/classic/Questie"), vec![0; 40]).unwrap();
+        fs::write(cache_dir.join("addons/classic/Details"), vec![0; 60]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&config_file, cache_dir.join("linked.yml")).unwrap();
+
+        let in_flight = cache_dir.join("addons/classic/Details");
+        mark_in_flight(&in_flight);
+        let report = clear_cache_dir(&cache_dir).unwrap();
+        unmark_in_flight(&in_flight);
+
+        assert_eq!(report.bytes_freed, 163);
+        #[cfg(unix)]
+        assert_eq!(report.files_removed, 5);
+
+        assert!(cache_dir.is_dir());
+        let mut left: Vec<_> = WalkDir::new(&cache_dir)
+            .min_depth(1)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect();
+        left.sort();
+        assert_eq!(left, vec![in_flight, cache_dir.join(ADDON_CACHE_FILE)]);
+        assert_eq!(fs::read_to_string(&config_file).unwrap(), "theme: Dark");
+    }
 }
@@ -1,12 +1,17 @@
 use crate::config::Flavor;
-use crate::error::DownloadError;
-use crate::network::request_async;
+use crate::error::{CatalogError, DownloadError};
+use crate::network::{head_async, request_async};
 
 use chrono::prelude::*;
+use fuzzy_matcher::skim::{SkimMatcherV2, SkimScoreConfig};
+use fuzzy_matcher::FuzzyMatcher;
 use isahc::AsyncReadResponseExt;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
-const CATALOG_URL: &str = "https://github.com/ajour/ajour-catalog/raw/master/catalog-2.0.json";
+const CATALOG_BASE_URL: &str = "https://github.com/ajour/ajour-catalog/raw/master";
+const CATALOG_FILE: &str = "catalog-2.0.json";
 
 type Etag = Option<String>;
 
@@ -44,17 +49,46 @@ async fn get_catalog_addons_from(
 }
 
 pub(crate) async fn download_catalog(
+    url: &str,
     cached_etag: Etag,
 ) -> Result<Option<(Etag, Catalog)>, DownloadError> {
-    let response = get_catalog_addons_from(CATALOG_URL, cached_etag)
+    let response = get_catalog_addons_from(url, cached_etag)
         .await?
         .map(|(etag, addons)| (etag, Catalog { addons }));
 
     Ok(response)
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Returns the url of the catalog to download for the chosen source.
+///
+/// A `Source::Custom` url is treated as the base url hosting the standard catalog
+/// file, every other source uses the official catalog.
+pub(crate) fn catalog_url(source: Option<&Source>) -> Result<String, DownloadError> {
+    match source {
+        Some(Source::Custom { url: base_url }) => {
+            let url = format!("{}/{}", base_url.trim_end_matches('/'), CATALOG_FILE);
+
+            let is_http = url
+                .parse::<isahc::http::Uri>()
+                .ok()
+                .and_then(|uri| uri.scheme_str().map(|s| s == "http" || s == "https"))
+                .unwrap_or_default();
+
+            if is_http {
+                Ok(url)
+            } else {
+                Err(DownloadError::InvalidCatalogUrl {
+                    url: base_url.clone(),
+                })
+            }
+        }
+        _ => Ok(format!("{}/{}", CATALOG_BASE_URL, CATALOG_FILE)),
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Source {
+    #[default]
     #[serde(alias = "curse")]
     Curse,
     #[serde(alias = "tukui")]
@@ -63,10 +97,96 @@ pub enum Source {
     WowI,
     #[serde(alias = "townlong-yak")]
     TownlongYak,
+    /// Privately hosted catalog, `url` being the base url of the catalog file.
+    Custom { url: String },
     #[serde(other)]
     Other,
 }
 
+impl Source {
+    /// Returns the sources of the official catalog. `Custom` and `Other` aren't
+    /// included, as they can't be picked from a list.
+    pub fn all() -> Vec<Source> {
+        vec![
+            Source::Curse,
+            Source::Tukui,
+            Source::WowI,
+            Source::TownlongYak,
+        ]
+    }
+
+    /// Returns the source which replaces self, if self is no longer
+    /// supported. Add a line here when a source is retired.
+    pub fn migrate(self) -> Source {
+        match self {
+            // Sources not known to this version of Ajour.
+            Source::Other => Source::default(),
+            source @ Source::Curse
+            | source @ Source::Tukui
+            | source @ Source::WowI
+            | source @ Source::TownlongYak
+            | source @ Source::Custom { .. } => source,
+        }
+    }
+
+    /// Sends a HEAD request to the catalog of this source and returns how long
+    /// it took to respond, and whether the catalog can be read.
+    ///
+    /// The bundled catalog is always in a supported format, so only a `Custom`
+    /// catalog is downloaded to check its contents.
+    ///
+    /// Fails if the catalog url is invalid or can't be reached, or responds
+    /// with an error status.
+    pub async fn check(&self) -> Result<SourceHealth, CatalogError> {
+        let url = catalog_url(Some(self)).map_err(|error| match error {
+            DownloadError::InvalidCatalogUrl { url } => CatalogError::InvalidUrl { url },
+            error => error.into(),
+        })?;
+
+        let started = Instant::now();
+        let response = head_async(&url, Some(30)).await?;
+        let latency = started.elapsed();
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(CatalogError::InvalidStatusCode { code: status, url });
+        }
+
+        let health = match self {
+            Source::Custom { .. } => {
+                let mut response = request_async(&url, vec![], Some(30)).await?;
+                let body = response.text().await.map_err(DownloadError::from)?;
+                let addons = serde_json::from_str::<Vec<CatalogAddon>>(&body).ok();
+
+                SourceHealth {
+                    latency,
+                    schema_supported: addons.is_some(),
+                    addons: addons.map(|addons| addons.len()),
+                }
+            }
+            _ => SourceHealth {
+                latency,
+                schema_supported: true,
+                addons: None,
+            },
+        };
+        log::debug!("catalog source {} is reachable: {:?}", self, health);
+
+        Ok(health)
+    }
+}
+
+/// Outcome of `Source::check`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceHealth {
+    /// Time until the catalog responded.
+    pub latency: Duration,
+    /// `true` if the catalog is in a format this version of Ajour can read.
+    pub schema_supported: bool,
+    /// Number of addons in the catalog, if it was downloaded.
+    pub addons: Option<usize>,
+}
+
 impl std::fmt::Display for Source {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
@@ -74,6 +194,7 @@ impl std::fmt::Display for Source {
             Source::Tukui => "Tukui",
             Source::WowI => "WowInterface",
             Source::TownlongYak => "TownlongYak",
+            Source::Custom { .. } => "Custom",
 
             // This is a fallback option.
             Source::Other => "Unknown",
@@ -121,6 +242,67 @@ pub struct CatalogAddon {
     pub game_versions: Vec<GameVersion>,
 }
 
+impl CatalogAddon {
+    /// Returns how well `query` matches this addon, or `None` if it doesn't.
+    /// Matches in the summary count half as much as matches in the name.
+    pub fn search_score(&self, query: &str) -> Option<i64> {
+        let name_score = fuzzy_match(query, &self.name).unwrap_or_default();
+        let summary_score = fuzzy_match(query, &self.summary).unwrap_or_default() / 2;
+
+        Some(name_score.max(summary_score)).filter(|score| *score > 0)
+    }
+}
+
+static FUZZY_MATCHER: Lazy<SkimMatcherV2> = Lazy::new(|| {
+    // Increase penalty for gaps between matching characters
+    let score_config = SkimScoreConfig {
+        gap_start: -12,
+        gap_extension: -6,
+        ..Default::default()
+    };
+
+    SkimMatcherV2::default()
+        .score_config(score_config)
+        .ignore_case()
+});
+
+/// Returns a relevance score for `name` against `query`, where higher is
+/// better, or `None` if it doesn't match. Matching is case-insensitive and
+/// whitespace in `query` is ignored, so `bigwig` matches `BigWigs Bossmods`.
+pub fn fuzzy_match(query: &str, name: &str) -> Option<i64> {
+    let query: String = query.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if query.is_empty() {
+        return None;
+    }
+
+    FUZZY_MATCHER
+        .fuzzy_match(name, &query)
+        .filter(|score| *score > 0)
+}
+
+/// Returns the entries which match `query` together with their score, best
+/// match first. Equal scores are ordered by number of downloads.
+pub fn rank_catalog_results<'a>(
+    query: &str,
+    entries: &'a [CatalogAddon],
+) -> Vec<(&'a CatalogAddon, i64)> {
+    let mut ranked: Vec<_> = entries
+        .iter()
+        .filter_map(|entry| entry.search_score(query).map(|score| (entry, score)))
+        .collect();
+
+    ranked.sort_by(|(entry_a, score_a), (entry_b, score_b)| {
+        score_b.cmp(score_a).then_with(|| {
+            entry_b
+                .number_of_downloads
+                .cmp(&entry_a.number_of_downloads)
+        })
+    });
+
+    ranked
+}
+
 mod null_to_default {
     use serde::{self, Deserialize, Deserializer};
 
@@ -186,10 +368,151 @@ mod date_parser {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_all_sources() {
+        // Fails to compile when a variant is added without updating this match.
+        let is_listed = |source: &Source| match source {
+            Source::Curse | Source::Tukui | Source::WowI | Source::TownlongYak => true,
+            Source::Custom { .. } | Source::Other => false,
+        };
+
+        let all = Source::all();
+        assert!(all.iter().all(is_listed));
+        assert_eq!(all.len(), 4);
+
+        for source in all.iter() {
+            assert!(!source.to_string().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("bigwig", "BigWigs Bossmods").is_some());
+        assert!(fuzzy_match("BIG WIGS", "BigWigs Bossmods").is_some());
+        assert!(fuzzy_match("bossmods", "Boss Mods").is_some());
+        assert_eq!(fuzzy_match("bigwig", "Details! Damage Meter"), None);
+        assert_eq!(fuzzy_match(" ", "BigWigs Bossmods"), None);
+    }
+
+    #[test]
+    fn test_rank_catalog_results() {
+        let addon = |name: &str, summary: &str, number_of_downloads: u64| {
+            let mut addon: CatalogAddon = serde_json::from_str(
+                r#"{"id": 1,"websiteUrl": "","dateReleased": "2020-11-20T02:29:43.46Z","name": "","summary": "","numberOfDownloads": 0,"categories": [],"flavors": [],"gameVersions": [],"source":"curse"}"#,
+            )
+            .unwrap();
+            addon.name = name.to_owned();
+            addon.summary = summary.to_owned();
+            addon.number_of_downloads = number_of_downloads;
+            addon
+        };
+
+        let entries = vec![
+            addon("Details! Damage Meter", "Damage meter", 100),
+            addon("Boss Timers", "Like bigwigs, but smaller", 10),
+            addon("BigWigs Bossmods", "Boss timers", 50),
+            addon("WeakAuras", "Display anything", 200),
+        ];
+
+        let ranked = rank_catalog_results("bigwig", &entries);
+        let names: Vec<_> = ranked.iter().map(|(a, _)| a.name.as_str()).collect();
+
+        assert_eq!(names, vec!["BigWigs Bossmods", "Boss Timers"]);
+        assert!(ranked[0].1 > ranked[1].1);
+    }
+
+    #[test]
+    fn test_migrate() {
+        let retired: Source = serde_yaml::from_str("Retired").unwrap();
+        assert_eq!(retired.migrate(), Source::default());
+
+        for source in Source::all() {
+            assert_eq!(source.clone().migrate(), source);
+        }
+
+        let custom = Source::Custom {
+            url: "https://example.com".to_owned(),
+        };
+        assert_eq!(custom.clone().migrate(), custom);
+    }
+
+    /// Serves `response` on a local port to each request and returns its url.
+    /// HEAD requests get the headers only.
+    fn serve(response: String) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().filter_map(Result::ok) {
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+
+                let response = if request.starts_with(b"HEAD ") {
+                    let headers_end = response.find("\r\n\r\n").unwrap() + 4;
+                    &response[..headers_end]
+                } else {
+                    &response[..]
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        url
+    }
+
+    fn http_response(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    #[test]
+    fn test_source_check() {
+        let catalog = r#"[{"id":1,"websiteUrl":"","dateReleased":"2021-01-01T00:00:00Z","name":"Details","categories":[],"summary":"","numberOfDownloads":0,"source":"curse","flavors":[],"gameVersions":[]}]"#;
+
+        async_std::task::block_on(async {
+            let url = serve(http_response("200 OK", catalog));
+            let health = Source::Custom { url }.check().await.unwrap();
+            assert!(health.schema_supported);
+            assert_eq!(health.addons, Some(1));
+
+            // Reachable, but not a catalog
+            let url = serve(http_response("200 OK", r#"{"addons":"none"}"#));
+            let health = Source::Custom { url }.check().await.unwrap();
+            assert!(!health.schema_supported);
+            assert_eq!(health.addons, None);
+
+            let url = serve(http_response("404 Not Found", ""));
+            assert!(matches!(
+                Source::Custom { url }.check().await,
+                Err(CatalogError::InvalidStatusCode { .. })
+            ));
+
+            let url = "not a url".to_owned();
+            assert!(matches!(
+                Source::Custom { url }.check().await,
+                Err(CatalogError::InvalidUrl { .. })
+            ));
+        });
+    }
+
     #[test]
     fn test_catalog_download() {
         async_std::task::block_on(async {
-            let catalog = download_catalog(None).await;
+            let url = catalog_url(None).unwrap();
+            let catalog = download_catalog(&url, None).await;
 
             if let Err(e) = catalog {
                 panic!("{}", e);
@@ -208,4 +531,31 @@ mod tests {
             serde_json::from_str::<Vec<CatalogAddon>>(test).unwrap();
         }
     }
+
+    #[test]
+    fn test_custom_source() {
+        let source = Source::Custom {
+            url: "https://example.com/catalog/".to_owned(),
+        };
+
+        let yaml = serde_yaml::to_string(&Some(source.clone())).unwrap();
+        let deser: Option<Source> = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(deser, Some(source.clone()));
+
+        let deser: Source = serde_yaml::from_str("curse").unwrap();
+        assert_eq!(deser, Source::Curse);
+
+        assert_eq!(
+            catalog_url(Some(&source)).unwrap(),
+            "https://example.com/catalog/catalog-2.0.json"
+        );
+
+        let invalid = Source::Custom {
+            url: "not a url".to_owned(),
+        };
+        assert!(matches!(
+            catalog_url(Some(&invalid)),
+            Err(DownloadError::InvalidCatalogUrl { .. })
+        ));
+    }
 }
@@ -0,0 +1,204 @@
+use crate::addon::Addon;
+use crate::catalog::{Catalog, CatalogAddon, Source};
+use crate::config::{Config, Flavor};
+use crate::repository::RepositoryKind;
+
+use serde::{Deserialize, Serialize};
+
+/// Portable list of the addons installed for a flavor, which can be shared
+/// and installed elsewhere.
+///
+/// Only repository identifiers and names are kept, never local paths.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddonList {
+    pub flavor: Flavor,
+    pub catalog_source: Source,
+    pub addons: Vec<AddonListEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddonListEntry {
+    pub name: String,
+    /// `None` for addons which aren't matched to any repository.
+    #[serde(default)]
+    pub source: Option<RepositoryKind>,
+    /// Repository id, or the source url for git repositories.
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+/// Result of resolving an `AddonList` for installation.
+#[derive(Debug, Clone)]
+pub struct AddonListImport<'a> {
+    /// Addons which can be installed from the catalog.
+    pub catalog: Vec<&'a CatalogAddon>,
+    /// Source urls of addons hosted on git repositories.
+    pub source_urls: Vec<String>,
+    /// Entries which couldn't be found.
+    pub unresolved: Vec<AddonListEntry>,
+}
+
+impl AddonList {
+    pub(crate) fn new(config: &Config, flavor: Flavor, addons: &[Addon]) -> Self {
+        let mut addons: Vec<_> = addons
+            .iter()
+            .map(|addon| AddonListEntry {
+                name: addon.title().to_owned(),
+                source: addon.repository_kind(),
+                id: addon.repository_id().map(str::to_owned),
+            })
+            .collect();
+        addons.sort_by_key(|a| a.name.to_lowercase());
+
+        AddonList {
+            flavor,
+            catalog_source: config.effective_catalog_source(),
+            addons,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub(crate) fn resolve<'a>(&self, config: &Config, catalog: &'a Catalog) -> AddonListImport<'a> {
+        let fallback_source = config.effective_catalog_source();
+
+        let mut import = AddonListImport {
+            catalog: vec![],
+            source_urls: vec![],
+            unresolved: vec![],
+        };
+
+        for entry in &self.addons {
+            match (entry.source, entry.id.as_deref()) {
+                (Some(RepositoryKind::Git(_)), Some(url)) => {
+                    import.source_urls.push(url.to_owned());
+                    continue;
+                }
+                (Some(kind), Some(id)) => {
+                    let found = catalog_source(kind).and_then(|source| {
+                        catalog
+                            .addons
+                            .iter()
+                            .find(|a| a.source == source && a.id.to_string() == id)
+                    });
+
+                    if let Some(addon) = found {
+                        import.catalog.push(addon);
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+
+            // Falls back to finding the addon by name in our own catalog source,
+            // among the addons available for the list's flavor.
+            let found = catalog.addons.iter().find(|a| {
+                a.source == fallback_source
+                    && a.name.eq_ignore_ascii_case(&entry.name)
+                    && a.game_versions
+                        .iter()
+                        .any(|gv| gv.flavor.base_flavor() == self.flavor.base_flavor())
+            });
+
+            match found {
+                Some(addon) => import.catalog.push(addon),
+                None => import.unresolved.push(entry.clone()),
+            }
+        }
+
+        import
+    }
+}
+
+/// Returns the catalog `Source` addons of `kind` are listed under.
+fn catalog_source(kind: RepositoryKind) -> Option<Source> {
+    match kind {
+        RepositoryKind::Curse => Some(Source::Curse),
+        RepositoryKind::Tukui => Some(Source::Tukui),
+        RepositoryKind::WowI => Some(Source::WowI),
+        RepositoryKind::TownlongYak => Some(Source::TownlongYak),
+        RepositoryKind::Git(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::GameVersion;
+    use crate::repository::RepositoryPackage;
+
+    fn catalog_addon(id: i32, name: &str, source: Source, flavor: Flavor) -> CatalogAddon {
+        let json = format!(
+            r#"{{"id":{},"websiteUrl":"","dateReleased":"2021-01-01T00:00:00Z","name":"{}","categories":[],"summary":"","numberOfDownloads":0,"source":"{:?}","flavors":[],"gameVersions":[]}}"#,
+            id, name, source
+        );
+        let mut addon: CatalogAddon = serde_json::from_str(&json).unwrap();
+        addon.game_versions = vec![GameVersion {
+            game_version: String::new(),
+            flavor,
+        }];
+        addon
+    }
+
+    #[test]
+    fn test_addon_list_round_trip() {
+        let mut curse_addon = Addon::empty("DBM-Core");
+        curse_addon.set_repository(
+            RepositoryPackage::from_repo_id(
+                Flavor::Retail,
+                RepositoryKind::Curse,
+                "3358".to_owned(),
+            )
+            .unwrap(),
+        );
+        let mut git_addon = Addon::empty("WeakAuras");
+        git_addon.set_repository(
+            RepositoryPackage::from_source_url(
+                Flavor::Retail,
+                "https://github.com/WeakAuras/WeakAuras2".parse().unwrap(),
+            )
+            .unwrap(),
+        );
+        let local_addon = Addon::empty("Details");
+        let unknown_addon = Addon::empty("MyOwnAddon");
+
+        let config = Config::default();
+        let list = config.export_addon_list(
+            Flavor::Retail,
+            &[curse_addon, git_addon, local_addon, unknown_addon],
+        );
+
+        let json = list.to_json().unwrap();
+        assert!(!json.contains("World of Warcraft"));
+        assert!(json.contains("3358"));
+
+        let parsed = AddonList::from_json(&json).unwrap();
+        assert_eq!(parsed, list);
+        assert_eq!(parsed.flavor, Flavor::Retail);
+        assert_eq!(parsed.addons.len(), 4);
+
+        let catalog = Catalog {
+            addons: vec![
+                catalog_addon(3358, "Deadly Boss Mods", Source::Curse, Flavor::Retail),
+                catalog_addon(61285, "Details", Source::Curse, Flavor::Classic),
+                catalog_addon(61284, "Details", Source::Curse, Flavor::Retail),
+            ],
+        };
+
+        let import = config.import_addon_list(&parsed, &catalog);
+        let ids: Vec<_> = import.catalog.iter().map(|a| a.id).collect();
+        assert_eq!(ids, vec![3358, 61284]);
+        assert_eq!(
+            import.source_urls,
+            vec!["https://github.com/WeakAuras/WeakAuras2".to_owned()]
+        );
+        assert_eq!(import.unresolved.len(), 1);
+        assert_eq!(import.unresolved[0].name, "MyOwnAddon");
+    }
+}
@@ -31,6 +31,33 @@ impl Default for Addons {
     }
 }
 
+impl Addons {
+    /// Returns `true` if the addon folder is ignored for the flavor.
+    pub fn is_ignored(&self, flavor: &Flavor, folder: &str) -> bool {
+        self.ignored
+            .get(flavor)
+            .map(|ignored| ignored.iter().any(|i| i == folder))
+            .unwrap_or_default()
+    }
+
+    /// Ignores the addon folder for the flavor. Does nothing if it is already
+    /// ignored.
+    pub fn ignore(&mut self, flavor: Flavor, folder: String) {
+        let ignored = self.ignored.entry(flavor).or_default();
+
+        if !ignored.contains(&folder) {
+            ignored.push(folder);
+        }
+    }
+
+    /// Removes the addon folder from the ignore list of the flavor.
+    pub fn unignore(&mut self, flavor: &Flavor, folder: &str) {
+        if let Some(ignored) = self.ignored.get_mut(flavor) {
+            ignored.retain(|i| i != folder);
+        }
+    }
+}
+
 mod de {
     use crate::config::Flavor;
     use serde::{
@@ -59,15 +86,18 @@ mod de {
             where
                 A: SeqAccess<'de>,
             {
-                let mut map = HashMap::new();
                 let mut ignored = vec![];
 
                 while let Ok(Some(value)) = seq.next_element::<String>() {
                     ignored.push(value);
                 }
 
-                map.insert(Flavor::Retail, ignored.clone());
-                map.insert(Flavor::Classic, ignored);
+                // Old configs had a single global ignore list, which now
+                // applies to every flavor.
+                let map = Flavor::ALL
+                    .iter()
+                    .map(|flavor| (*flavor, ignored.clone()))
+                    .collect();
 
                 Ok(map)
             }
@@ -83,3 +113,34 @@ mod de {
         deserializer.deserialize_any(DeIgnored)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_per_flavor() {
+        let mut addons = Addons::default();
+        addons.ignore(Flavor::Retail, "Foo".to_owned());
+        addons.ignore(Flavor::Retail, "Foo".to_owned());
+
+        assert!(addons.is_ignored(&Flavor::Retail, "Foo"));
+        assert!(!addons.is_ignored(&Flavor::Classic, "Foo"));
+        assert_eq!(addons.ignored[&Flavor::Retail].len(), 1);
+
+        addons.unignore(&Flavor::Retail, "Foo");
+        assert!(!addons.is_ignored(&Flavor::Retail, "Foo"));
+    }
+
+    #[test]
+    fn test_global_ignore_list_migration() {
+        let addons: Addons = serde_yaml::from_str("---\nignored:\n  - Foo\n").unwrap();
+
+        for flavor in Flavor::ALL.iter() {
+            assert!(addons.is_ignored(flavor, "Foo"));
+        }
+
+        let addons: Addons = serde_yaml::from_str("---\n{}").unwrap();
+        assert!(addons.ignored.is_empty());
+    }
+}
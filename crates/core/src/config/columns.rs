@@ -0,0 +1,663 @@
+use super::Flavor;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Selects one of the column lists of a `ColumnConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnSection {
+    MyAddons,
+    Catalog,
+    Aura,
+}
+
+/// Column and direction addons are sorted by.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct SortState {
+    /// Key of the column, as in `ColumnConfigV2::key`.
+    pub column_key: String,
+    pub direction: SortDirection,
+}
+
+impl Default for SortState {
+    fn default() -> Self {
+        SortState {
+            column_key: "status".to_owned(),
+            direction: SortDirection::Desc,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub enum ColumnConfig {
+    V1 {
+        local_version_width: u16,
+        remote_version_width: u16,
+        status_width: u16,
+    },
+    V2 {
+        columns: Vec<ColumnConfigV2>,
+    },
+    V3 {
+        my_addons_columns: Vec<ColumnConfigV2>,
+        catalog_columns: Vec<ColumnConfigV2>,
+        #[serde(default)]
+        aura_columns: Vec<ColumnConfigV2>,
+        /// My Addons columns of flavors which don't use `my_addons_columns`.
+        #[serde(default)]
+        flavor_my_addons_columns: HashMap<Flavor, Vec<ColumnConfigV2>>,
+    },
+}
+
+impl ColumnConfig {
+    /// Returns the My Addons columns shared by flavors without columns of their
+    /// own, in the V3 shape.
+    ///
+    /// Older versions are upgraded on the fly, without touching `self`.
+    pub fn shared_my_addons_columns(&self) -> Vec<ColumnConfigV2> {
+        let columns = match self {
+            ColumnConfig::V1 {
+                local_version_width,
+                remote_version_width,
+                status_width,
+            } => default_my_addons_columns()
+                .into_iter()
+                .map(|mut column| {
+                    match column.key.as_str() {
+                        "local" => column.width = Some(*local_version_width),
+                        "remote" => column.width = Some(*remote_version_width),
+                        "status" => column.width = Some(*status_width),
+                        _ => {}
+                    }
+
+                    column
+                })
+                .collect(),
+            ColumnConfig::V2 { columns } => {
+                with_missing_defaults(columns, default_my_addons_columns())
+            }
+            ColumnConfig::V3 {
+                my_addons_columns, ..
+            } => my_addons_columns.clone(),
+        };
+
+        with_min_widths(columns)
+    }
+
+    /// Returns the My Addons columns of `flavor`, falling back to the shared
+    /// `my_addons_columns` if `flavor` has no columns of its own.
+    pub fn my_addons_columns(&self, flavor: &Flavor) -> Vec<ColumnConfigV2> {
+        match self {
+            ColumnConfig::V3 {
+                flavor_my_addons_columns,
+                ..
+            } => match flavor_my_addons_columns.get(flavor) {
+                Some(columns) => with_min_widths(columns.clone()),
+                None => self.shared_my_addons_columns(),
+            },
+            _ => self.shared_my_addons_columns(),
+        }
+    }
+
+    /// Sets the My Addons columns of `flavor`, leaving other flavors as they
+    /// are.
+    ///
+    /// Older versions are migrated to V3 first.
+    pub fn set_my_addons_columns_for(&mut self, flavor: Flavor, columns: Vec<ColumnConfigV2>) {
+        self.flavor_columns_mut().insert(flavor, columns);
+    }
+
+    /// Makes `flavor` use the shared My Addons columns again.
+    pub fn reset_my_addons_columns_for(&mut self, flavor: &Flavor) {
+        if let ColumnConfig::V3 {
+            flavor_my_addons_columns,
+            ..
+        } = self
+        {
+            flavor_my_addons_columns.remove(flavor);
+        }
+    }
+
+    /// Gives every flavor its own copy of the shared My Addons columns, so
+    /// they can be changed per flavor. Flavors which already have their own
+    /// columns are kept.
+    ///
+    /// Older versions are migrated to V3 first.
+    pub fn migrate_to_per_flavor(&mut self) {
+        let shared = self.shared_my_addons_columns();
+        let flavor_columns = self.flavor_columns_mut();
+
+        for flavor in Flavor::ALL.iter() {
+            flavor_columns
+                .entry(*flavor)
+                .or_insert_with(|| shared.clone());
+        }
+    }
+
+    /// Returns the Catalog columns in the V3 shape.
+    ///
+    /// Catalog columns didn't exist before V3, so older versions get the
+    /// default columns.
+    pub fn catalog_columns(&self) -> Vec<ColumnConfigV2> {
+        let columns = match self {
+            ColumnConfig::V1 { .. } | ColumnConfig::V2 { .. } => default_catalog_columns(),
+            ColumnConfig::V3 {
+                catalog_columns, ..
+            } => catalog_columns.clone(),
+        };
+
+        with_min_widths(columns)
+    }
+
+    /// Returns the Aura columns in the V3 shape.
+    ///
+    /// Aura columns were added to V3 later on, so older versions and V3
+    /// configs without them get the default columns.
+    pub fn aura_columns(&self) -> Vec<ColumnConfigV2> {
+        let columns = match self {
+            ColumnConfig::V3 { aura_columns, .. } if !aura_columns.is_empty() => {
+                aura_columns.clone()
+            }
+            _ => default_aura_columns(),
+        };
+
+        with_min_widths(columns)
+    }
+
+    /// Returns the keys of the columns in `section` which aren't hidden, in
+    /// display order.
+    ///
+    /// Older versions are upgraded on the fly, without touching `self`.
+    pub fn visible_keys(&self, section: ColumnSection) -> Vec<String> {
+        let columns = match section {
+            ColumnSection::MyAddons => self.shared_my_addons_columns(),
+            ColumnSection::Catalog => self.catalog_columns(),
+            ColumnSection::Aura => self.aura_columns(),
+        };
+
+        columns
+            .into_iter()
+            .filter(|column| !column.hidden)
+            .map(|column| column.key)
+            .collect()
+    }
+
+    /// Converts a V1 or V2 config into V3 in place. Columns without an older
+    /// equivalent get the default columns.
+    ///
+    /// Does nothing if already V3.
+    pub fn migrate_to_v3(&mut self) {
+        if let ColumnConfig::V3 { .. } = self {
+            return;
+        }
+
+        *self = ColumnConfig::V3 {
+            my_addons_columns: self.shared_my_addons_columns(),
+            catalog_columns: self.catalog_columns(),
+            aura_columns: self.aura_columns(),
+            flavor_my_addons_columns: HashMap::new(),
+        };
+    }
+
+    /// Moves the column with `key` in `section` to `to_index`, which is clamped
+    /// to the last position. Does nothing if there is no such column.
+    ///
+    /// Older versions are migrated to V3 first.
+    pub fn move_column(&mut self, section: ColumnSection, key: &str, to_index: usize) {
+        let columns = self.columns_mut(section);
+
+        if let Some(from_index) = columns.iter().position(|c| c.key == key) {
+            let column = columns.remove(from_index);
+            let to_index = to_index.min(columns.len());
+
+            columns.insert(to_index, column);
+        }
+    }
+
+    /// Replaces the columns of `section` with the default columns.
+    ///
+    /// Older versions are migrated to V3 first.
+    pub fn reset_section(&mut self, section: ColumnSection) {
+        *self.columns_mut(section) = default_columns(section);
+    }
+
+    /// Returns a V3 config with the default columns of every section.
+    pub fn default_v3() -> Self {
+        ColumnConfig::V3 {
+            my_addons_columns: default_columns(ColumnSection::MyAddons),
+            catalog_columns: default_columns(ColumnSection::Catalog),
+            aura_columns: default_columns(ColumnSection::Aura),
+            flavor_my_addons_columns: HashMap::new(),
+        }
+    }
+
+    /// Returns the columns of `section`, migrating to V3 first.
+    fn columns_mut(&mut self, section: ColumnSection) -> &mut Vec<ColumnConfigV2> {
+        self.migrate_to_v3();
+
+        match self {
+            ColumnConfig::V3 {
+                my_addons_columns,
+                catalog_columns,
+                aura_columns,
+                ..
+            } => match section {
+                ColumnSection::MyAddons => my_addons_columns,
+                ColumnSection::Catalog => catalog_columns,
+                ColumnSection::Aura => {
+                    if aura_columns.is_empty() {
+                        *aura_columns = default_aura_columns();
+                    }
+
+                    aura_columns
+                }
+            },
+            _ => unreachable!("column config was migrated to V3"),
+        }
+    }
+
+    /// Returns the My Addons columns per flavor, migrating to V3 first.
+    fn flavor_columns_mut(&mut self) -> &mut HashMap<Flavor, Vec<ColumnConfigV2>> {
+        self.migrate_to_v3();
+
+        match self {
+            ColumnConfig::V3 {
+                flavor_my_addons_columns,
+                ..
+            } => flavor_my_addons_columns,
+            _ => unreachable!("column config was migrated to V3"),
+        }
+    }
+}
+
+impl Default for ColumnConfig {
+    fn default() -> Self {
+        ColumnConfig::V1 {
+            local_version_width: 150,
+            remote_version_width: 150,
+            status_width: 85,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct ColumnConfigV2 {
+    pub key: String,
+    pub width: Option<u16>,
+    pub hidden: bool,
+}
+
+impl ColumnConfigV2 {
+    fn new(key: &str, width: Option<u16>, hidden: bool) -> Self {
+        ColumnConfigV2 {
+            key: key.to_owned(),
+            width,
+            hidden,
+        }
+    }
+
+    /// Returns the width of the column, or `default` if it has none.
+    pub fn width_or_default(&self, default: u16) -> u16 {
+        self.width.unwrap_or(default)
+    }
+
+    /// Clamps the width of the column between `min` and `max`. A column without
+    /// a width is left as is.
+    pub fn clamp_width(&mut self, min: u16, max: u16) {
+        if let Some(width) = self.width.as_mut() {
+            *width = (*width).clamp(min, max.max(min));
+        }
+    }
+}
+
+/// Returns the smallest width a column with `key` is rendered with, so it
+/// can't be dragged out of sight.
+fn min_width(key: &str) -> u16 {
+    match key {
+        "status" | "install" | "channel" | "type" => 60,
+        _ => 40,
+    }
+}
+
+/// Applies `min_width` to every column.
+fn with_min_widths(mut columns: Vec<ColumnConfigV2>) -> Vec<ColumnConfigV2> {
+    for column in columns.iter_mut() {
+        column.clamp_width(min_width(&column.key), u16::MAX);
+    }
+
+    columns
+}
+
+/// Appends the default columns which are missing from `columns`.
+fn with_missing_defaults(
+    columns: &[ColumnConfigV2],
+    defaults: Vec<ColumnConfigV2>,
+) -> Vec<ColumnConfigV2> {
+    let mut columns = columns.to_vec();
+
+    for default in defaults {
+        if !columns.iter().any(|c| c.key == default.key) {
+            columns.push(default);
+        }
+    }
+
+    columns
+}
+
+/// Default columns of `section`, in display order.
+fn default_columns(section: ColumnSection) -> Vec<ColumnConfigV2> {
+    match section {
+        ColumnSection::MyAddons => default_my_addons_columns(),
+        ColumnSection::Catalog => default_catalog_columns(),
+        ColumnSection::Aura => default_aura_columns(),
+    }
+}
+
+/// Default My Addons columns, in display order.
+fn default_my_addons_columns() -> Vec<ColumnConfigV2> {
+    vec![
+        ColumnConfigV2::new("title", None, false),
+        ColumnConfigV2::new("local", Some(150), false),
+        ColumnConfigV2::new("remote", Some(150), false),
+        ColumnConfigV2::new("status", Some(85), false),
+        ColumnConfigV2::new("channel", Some(85), true),
+        ColumnConfigV2::new("author", Some(85), true),
+        ColumnConfigV2::new("game_version", Some(110), true),
+        ColumnConfigV2::new("date_released", Some(110), true),
+        ColumnConfigV2::new("source", Some(110), true),
+        ColumnConfigV2::new("summary", Some(110), true),
+    ]
+}
+
+/// Default Catalog columns, in display order.
+fn default_catalog_columns() -> Vec<ColumnConfigV2> {
+    vec![
+        ColumnConfigV2::new("addon", None, false),
+        ColumnConfigV2::new("description", Some(150), false),
+        ColumnConfigV2::new("source", Some(110), true),
+        ColumnConfigV2::new("num_downloads", Some(105), true),
+        ColumnConfigV2::new("game_version", Some(105), true),
+        ColumnConfigV2::new("date_released", Some(105), false),
+        ColumnConfigV2::new("install", Some(85), false),
+        ColumnConfigV2::new("categories", Some(85), true),
+    ]
+}
+
+/// Default Aura columns, in display order.
+fn default_aura_columns() -> Vec<ColumnConfigV2> {
+    vec![
+        ColumnConfigV2::new("title", None, false),
+        ColumnConfigV2::new("local", Some(120), false),
+        ColumnConfigV2::new("remote", Some(120), false),
+        ColumnConfigV2::new("author", Some(85), false),
+        ColumnConfigV2::new("type", Some(85), false),
+        ColumnConfigV2::new("status", Some(110), false),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn width_of(columns: &[ColumnConfigV2], key: &str) -> Option<u16> {
+        columns.iter().find(|c| c.key == key).and_then(|c| c.width)
+    }
+
+    #[test]
+    fn test_v1_accessors() {
+        let config = ColumnConfig::V1 {
+            local_version_width: 120,
+            remote_version_width: 130,
+            status_width: 90,
+        };
+
+        let my_addons_columns = config.shared_my_addons_columns();
+        assert_eq!(my_addons_columns.len(), default_my_addons_columns().len());
+        assert_eq!(width_of(&my_addons_columns, "title"), None);
+        assert_eq!(width_of(&my_addons_columns, "local"), Some(120));
+        assert_eq!(width_of(&my_addons_columns, "remote"), Some(130));
+        assert_eq!(width_of(&my_addons_columns, "status"), Some(90));
+        assert_eq!(width_of(&my_addons_columns, "author"), Some(85));
+
+        assert_eq!(config.catalog_columns(), default_catalog_columns());
+        assert_eq!(config.aura_columns(), default_aura_columns());
+    }
+
+    #[test]
+    fn test_v2_accessors() {
+        let config = ColumnConfig::V2 {
+            columns: vec![
+                ColumnConfigV2::new("status", Some(60), true),
+                ColumnConfigV2::new("title", None, false),
+            ],
+        };
+
+        let my_addons_columns = config.shared_my_addons_columns();
+        assert_eq!(
+            my_addons_columns[0],
+            ColumnConfigV2::new("status", Some(60), true)
+        );
+        assert_eq!(my_addons_columns[1].key, "title");
+        assert_eq!(my_addons_columns.len(), default_my_addons_columns().len());
+    }
+
+    #[test]
+    fn test_width_or_default() {
+        assert_eq!(
+            ColumnConfigV2::new("title", None, false).width_or_default(100),
+            100
+        );
+        assert_eq!(
+            ColumnConfigV2::new("local", Some(0), false).width_or_default(100),
+            0
+        );
+        assert_eq!(
+            ColumnConfigV2::new("local", Some(120), false).width_or_default(100),
+            120
+        );
+    }
+
+    #[test]
+    fn test_clamp_width() {
+        let mut column = ColumnConfigV2::new("title", None, false);
+        column.clamp_width(40, 300);
+        assert_eq!(column.width, None);
+
+        let mut column = ColumnConfigV2::new("local", Some(0), false);
+        column.clamp_width(40, 300);
+        assert_eq!(column.width, Some(40));
+
+        let mut column = ColumnConfigV2::new("local", Some(5000), false);
+        column.clamp_width(40, 300);
+        assert_eq!(column.width, Some(300));
+    }
+
+    #[test]
+    fn test_accessors_apply_min_width() {
+        let config = ColumnConfig::V3 {
+            my_addons_columns: vec![
+                ColumnConfigV2::new("title", None, false),
+                ColumnConfigV2::new("local", Some(0), false),
+                ColumnConfigV2::new("status", Some(10), false),
+            ],
+            catalog_columns: vec![ColumnConfigV2::new("install", Some(0), false)],
+            aura_columns: vec![ColumnConfigV2::new("author", Some(1), false)],
+            flavor_my_addons_columns: HashMap::new(),
+        };
+
+        let my_addons_columns = config.shared_my_addons_columns();
+        assert_eq!(width_of(&my_addons_columns, "title"), None);
+        assert_eq!(width_of(&my_addons_columns, "local"), Some(40));
+        assert_eq!(width_of(&my_addons_columns, "status"), Some(60));
+        assert_eq!(width_of(&config.catalog_columns(), "install"), Some(60));
+        assert_eq!(width_of(&config.aura_columns(), "author"), Some(40));
+    }
+
+    #[test]
+    fn test_move_column() {
+        let mut config = ColumnConfig::default();
+        let columns = config.shared_my_addons_columns();
+
+        config.move_column(ColumnSection::MyAddons, "title", usize::MAX);
+
+        let moved = config.shared_my_addons_columns();
+        assert!(matches!(config, ColumnConfig::V3 { .. }));
+        assert_eq!(moved.last(), columns.first());
+        assert_eq!(&moved[..moved.len() - 1], &columns[1..]);
+
+        config.move_column(ColumnSection::MyAddons, "title", 0);
+        assert_eq!(config.shared_my_addons_columns(), columns);
+
+        config.move_column(ColumnSection::Aura, "status", 1);
+        assert_eq!(config.aura_columns()[1].key, "status");
+    }
+
+    #[test]
+    fn test_visible_keys() {
+        let mut config = ColumnConfig::default();
+        assert_eq!(
+            config.visible_keys(ColumnSection::MyAddons),
+            vec!["title", "local", "remote", "status"]
+        );
+
+        config.move_column(ColumnSection::MyAddons, "status", 0);
+        if let ColumnConfig::V3 {
+            my_addons_columns, ..
+        } = &mut config
+        {
+            my_addons_columns
+                .iter_mut()
+                .filter(|column| column.key == "local")
+                .for_each(|column| column.hidden = true);
+        }
+
+        assert_eq!(
+            config.visible_keys(ColumnSection::MyAddons),
+            vec!["status", "title", "remote"]
+        );
+    }
+
+    #[test]
+    fn test_move_unknown_column() {
+        let mut config = ColumnConfig::default_v3();
+        let unchanged = config.clone();
+
+        config.move_column(ColumnSection::Catalog, "unknown", 0);
+
+        assert_eq!(config, unchanged);
+    }
+
+    #[test]
+    fn test_migrate_v1_to_v3() {
+        let mut config = ColumnConfig::V1 {
+            local_version_width: 120,
+            remote_version_width: 130,
+            status_width: 90,
+        };
+        let my_addons_columns = config.shared_my_addons_columns();
+
+        config.migrate_to_v3();
+
+        assert_eq!(
+            config,
+            ColumnConfig::V3 {
+                my_addons_columns,
+                catalog_columns: default_catalog_columns(),
+                aura_columns: default_aura_columns(),
+                flavor_my_addons_columns: HashMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_migrate_v2_to_v3() {
+        let mut config = ColumnConfig::V2 {
+            columns: vec![
+                ColumnConfigV2::new("title", None, false),
+                ColumnConfigV2::new("author", Some(200), false),
+                ColumnConfigV2::new("status", Some(60), true),
+            ],
+        };
+
+        config.migrate_to_v3();
+
+        let my_addons_columns = config.shared_my_addons_columns();
+        assert_eq!(
+            my_addons_columns[1],
+            ColumnConfigV2::new("author", Some(200), false)
+        );
+        assert_eq!(
+            my_addons_columns[2],
+            ColumnConfigV2::new("status", Some(60), true)
+        );
+        assert_eq!(config.aura_columns(), default_aura_columns());
+
+        // Migrating an already migrated config is a no-op.
+        let migrated = config.clone();
+        config.migrate_to_v3();
+        assert_eq!(config, migrated);
+    }
+
+    #[test]
+    fn test_migrate_to_per_flavor() {
+        let mut config = ColumnConfig::V2 {
+            columns: vec![ColumnConfigV2::new("author", Some(200), false)],
+        };
+        let shared = config.shared_my_addons_columns();
+
+        // Without columns of their own, flavors use the shared columns.
+        assert_eq!(config.my_addons_columns(&Flavor::Classic), shared);
+
+        config.migrate_to_per_flavor();
+        for flavor in Flavor::ALL.iter() {
+            assert_eq!(config.my_addons_columns(flavor), shared);
+        }
+
+        // Migrating again keeps the columns of each flavor.
+        config.set_my_addons_columns_for(Flavor::Classic, default_my_addons_columns());
+        config.migrate_to_per_flavor();
+        assert_eq!(
+            config.my_addons_columns(&Flavor::Classic),
+            default_my_addons_columns()
+        );
+    }
+
+    #[test]
+    fn test_per_flavor_columns_diverge() {
+        let mut config = ColumnConfig::default_v3();
+        let mut classic_columns = default_my_addons_columns();
+        classic_columns.reverse();
+
+        config.set_my_addons_columns_for(Flavor::Classic, classic_columns.clone());
+
+        assert_eq!(config.my_addons_columns(&Flavor::Classic), classic_columns);
+        assert_eq!(
+            config.my_addons_columns(&Flavor::Retail),
+            default_my_addons_columns()
+        );
+        assert_eq!(
+            config.shared_my_addons_columns(),
+            default_my_addons_columns()
+        );
+
+        // Per flavor columns survive a round trip.
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert_eq!(serde_yaml::from_str::<ColumnConfig>(&yaml).unwrap(), config);
+
+        // V3 configs from before per flavor columns still parse.
+        let yaml = "V3:\n  my_addons_columns: []\n  catalog_columns: []\n";
+        let old: ColumnConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(old.my_addons_columns(&Flavor::Retail).is_empty());
+
+        config.reset_my_addons_columns_for(&Flavor::Classic);
+        assert_eq!(
+            config.my_addons_columns(&Flavor::Classic),
+            default_my_addons_columns()
+        );
+    }
+}
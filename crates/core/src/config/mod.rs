@@ -1,23 +1,134 @@
+use crate::addon::Addon;
+use crate::backup::BackupKind;
+use crate::cache::{clear_cache_dir, CacheClearReport};
 use crate::catalog;
-use crate::error::FilesystemError;
+use crate::error::{DirectoryError, DirectoryIssue, DownloadError, FilesystemError, ThemeError};
+use crate::network::DownloadLimiter;
+#[cfg(feature = "gui")]
+use crate::theme::Theme;
 use glob::MatchOptions;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
-use std::fs::create_dir_all;
-use std::path::PathBuf;
+use std::fs::{remove_file, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+mod addon_list;
 mod addons;
+mod columns;
+mod notifications;
+mod summary;
 mod wow;
 
-use crate::fs::PersistentData;
+use crate::fs::{
+    config_dir, config_dir_path, create_dir_all, deserialize, read_to_string, watch_file,
+    FileWatcher, PersistentData,
+};
+use crate::utility::expand_path;
 
+pub use crate::config::addon_list::{AddonList, AddonListEntry, AddonListImport};
 pub use crate::config::addons::Addons;
-pub use crate::config::wow::{Flavor, Wow};
+pub use crate::config::columns::{
+    ColumnConfig, ColumnConfigV2, ColumnSection, SortDirection, SortState,
+};
+pub use crate::config::notifications::{NotificationConfig, NotificationEvent};
+pub use crate::config::summary::{ConfigSummary, FlavorSummary};
+pub use crate::config::wow::{BaseFlavor, Flavor, Wow};
+
+/// Resolved addon directories, keyed by the flavor directory they were
+/// resolved from.
+static ADDON_DIRECTORY_CACHE: Lazy<RwLock<HashMap<PathBuf, PathBuf>>> = Lazy::new(Default::default);
+
+/// Clears the resolved addon directories. Should be called when the user
+/// changes a flavor directory.
+pub fn clear_directory_cache() {
+    ADDON_DIRECTORY_CACHE.write().unwrap().clear();
+}
+
+/// Smallest UI scale which is allowed.
+pub const MIN_SCALE: f64 = 0.5;
+
+/// Largest UI scale which is allowed.
+pub const MAX_SCALE: f64 = 2.0;
+
+/// UI scales which `Config::cycle_scale` steps through.
+const SCALE_PRESETS: [f64; 4] = [1.0, 1.25, 1.5, 2.0];
+
+/// How long the config file has to be left alone before an external edit is
+/// reported by `Config::watch`.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Shortest interval allowed between automatic update checks, to avoid
+/// hammering the addon providers.
+pub const MIN_AUTO_UPDATE_INTERVAL_MINUTES: u32 = 15;
+
+/// Number of pixels of the window, in both directions, which has to be on a
+/// display for the saved window position to be used.
+pub const MIN_VISIBLE_WINDOW_PIXELS: u32 = 50;
+
+/// Number of addons downloaded at the same time when none is set.
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+/// Version of the config format. Only bumped on breaking changes, together
+/// with a migration in `SCHEMA_MIGRATIONS`.
+pub const CONFIG_SCHEMA_VERSION: u32 = 1;
+
+/// Version of config files written before the format was versioned.
+const UNVERSIONED_SCHEMA: u32 = 0;
+
+/// Migrates a config to the version it's registered with.
+type SchemaMigration = (u32, fn(&mut Config));
+
+/// Migrations of the config format, ordered by the version they migrate to.
+const SCHEMA_MIGRATIONS: &[SchemaMigration] = &[(1, migrate_unversioned)];
+
+/// Upgrades a config from before the format was versioned, which can still
+/// have older column configs, retired catalog sources and single WeakAuras
+/// accounts.
+fn migrate_unversioned(config: &mut Config) {
+    config.migrate_columns_to_v3();
+    config.migrate_catalog_source();
+    config.migrate_weak_auras_accounts();
+}
+
+/// Theme used when none is set, or the one set is not available.
+pub const DEFAULT_THEME: &str = "Dark";
+
+/// Names of the themes in `Theme::bundled()`.
+const BUNDLED_THEME_NAMES: &[&str] = &[
+    "Alliance",
+    "Ayu",
+    "Dark",
+    "Dracula",
+    "Ferra",
+    "Forest Night",
+    "Gruvbox",
+    "Horde",
+    "Light",
+    "Nord",
+    "One Dark",
+    "Outrun",
+    "Solarized Dark",
+    "Solarized Light",
+    "Sort",
+];
+
+/// Returns the names of the themes bundled with Ajour.
+pub fn bundled_theme_names() -> &'static [&'static str] {
+    BUNDLED_THEME_NAMES
+}
 
 /// Config struct.
-#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 pub struct Config {
+    /// Version of the config format the file was written with. Files without
+    /// one are from before the format was versioned.
+    #[serde(default = "unversioned_schema")]
+    pub schema_version: u32,
+
     #[serde(default)]
     pub wow: Wow,
 
@@ -31,6 +142,25 @@ pub struct Config {
 
     pub window_size: Option<(u32, u32)>,
 
+    /// Position of the top left corner of the window. Can be negative on
+    /// setups with multiple displays.
+    #[serde(default)]
+    pub window_position: Option<(i32, i32)>,
+
+    /// If the window was maximized when closed. `window_size` and
+    /// `window_position` are then the bounds to restore to.
+    #[serde(default)]
+    pub window_maximized: bool,
+
+    /// Starts Ajour without showing the window, eg. when launched on login.
+    #[serde(default)]
+    pub start_minimized: bool,
+
+    /// Hides the window to the system tray instead of the taskbar when it's
+    /// minimized.
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+
     pub scale: Option<f64>,
 
     pub backup_directory: Option<PathBuf>,
@@ -41,15 +171,74 @@ pub struct Config {
     #[serde(default)]
     pub backup_wtf: bool,
 
+    #[serde(default)]
+    pub backup_config: bool,
+
+    #[serde(default)]
+    pub backup_keep_count: Option<u32>,
+
+    /// Pattern for the names of backup archives, see
+    /// `backup::render_backup_filename`. If not set, the default naming is used.
+    #[serde(default)]
+    pub backup_filename_pattern: Option<String>,
+
+    /// Deflate level of backup archives, from 0 (stored) to 9 (smallest). If
+    /// not set, a balanced level is used. Higher values are clamped to 9.
+    #[serde(default)]
+    pub backup_compression_level: Option<u32>,
+
+    /// Number of addons downloaded at the same time, to avoid rate limits of
+    /// the addon providers.
+    #[serde(default)]
+    pub max_concurrent_downloads: Option<usize>,
+
+    /// Globs of files and folders to leave out of backups, relative to the
+    /// flavor folder, eg. `**/*.log`.
+    #[serde(default)]
+    pub backup_exclude_patterns: Vec<String>,
+
     #[serde(default)]
     pub hide_ignored_addons: bool,
 
+    /// Only shows addons which have an update available.
+    #[serde(default)]
+    pub show_updates_only: bool,
+
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+
+    /// Shows the addons of all flavors in a single list, instead of one list
+    /// per flavor.
+    #[serde(default)]
+    pub merge_flavor_view: bool,
+
+    /// Flavor to show when Ajour is opened. If not set, the flavor shown last
+    /// is kept.
+    #[serde(default)]
+    pub default_flavor: Option<Flavor>,
+
+    /// Order of the flavor tabs. Flavors which aren't listed follow in the
+    /// default order.
+    #[serde(default, deserialize_with = "wow::de_flavor_order")]
+    pub flavor_order: Vec<Flavor>,
+
     #[serde(default)]
     pub self_update_channel: SelfUpdateChannel,
 
+    /// Base url of a mirror of the GitHub API to fetch releases from.
+    #[serde(default)]
+    pub self_update_url_override: Option<String>,
+
+    /// Single WeakAuras account per flavor, superseded by
+    /// `weak_auras_accounts`. Moved over by `migrate_weak_auras_accounts`.
     #[serde(default)]
     pub weak_auras_account: HashMap<Flavor, String>,
 
+    /// WeakAuras accounts per flavor. The first one is shown in My WeakAuras,
+    /// while auras are updated for all of them.
+    #[serde(default)]
+    pub weak_auras_accounts: HashMap<Flavor, Vec<String>>,
+
     #[serde(default = "default_true")]
     pub alternating_row_colors: bool,
 
@@ -61,6 +250,97 @@ pub struct Config {
 
     #[serde(default)]
     pub auto_update: bool,
+
+    #[serde(default)]
+    pub auto_update_interval_minutes: Option<u32>,
+
+    #[serde(default)]
+    pub auto_update_flavors: HashMap<Flavor, bool>,
+
+    /// When addons of each flavor were last checked for updates, in unix
+    /// seconds.
+    #[serde(default)]
+    pub last_update_check: HashMap<Flavor, i64>,
+
+    /// How My Addons was last sorted.
+    #[serde(default)]
+    pub addon_sort: Option<SortState>,
+
+    /// Addon folders of each flavor which are kept at a version, and skipped
+    /// when updating all addons.
+    #[serde(default)]
+    pub pinned_addons: HashMap<Flavor, HashMap<String, String>>,
+
+    /// Format to save the config in. If not set, the format of the existing
+    /// config file is kept.
+    #[serde(default)]
+    pub config_format: Option<ConfigFormat>,
+
+    /// Never save the config, eg. when it's managed centrally. Changes made
+    /// in Ajour only last until it's closed.
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// Opts in to sending anonymous usage events, see `telemetry::Telemetry`.
+    #[serde(default)]
+    pub telemetry_enabled: bool,
+
+    /// Resolves symlinks in the addon directory, so files are managed in the
+    /// directory it points to.
+    #[serde(default = "default_true")]
+    pub follow_symlinks: bool,
+}
+
+/// Matches the defaults used when deserializing, so a new config is the same
+/// as one loaded from an empty file.
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            schema_version: current_schema(),
+            wow: Default::default(),
+            addons: Default::default(),
+            theme: Default::default(),
+            column_config: Default::default(),
+            window_size: Default::default(),
+            window_position: Default::default(),
+            window_maximized: Default::default(),
+            start_minimized: Default::default(),
+            minimize_to_tray: Default::default(),
+            scale: Default::default(),
+            backup_directory: Default::default(),
+            backup_addons: Default::default(),
+            backup_wtf: Default::default(),
+            backup_config: Default::default(),
+            backup_keep_count: Default::default(),
+            backup_filename_pattern: Default::default(),
+            backup_compression_level: None,
+            max_concurrent_downloads: Default::default(),
+            backup_exclude_patterns: Default::default(),
+            hide_ignored_addons: Default::default(),
+            show_updates_only: Default::default(),
+            notifications: Default::default(),
+            merge_flavor_view: Default::default(),
+            default_flavor: Default::default(),
+            flavor_order: Default::default(),
+            self_update_channel: Default::default(),
+            self_update_url_override: Default::default(),
+            weak_auras_account: Default::default(),
+            weak_auras_accounts: Default::default(),
+            alternating_row_colors: default_true(),
+            language: Default::default(),
+            catalog_source: Default::default(),
+            auto_update: Default::default(),
+            auto_update_interval_minutes: Default::default(),
+            auto_update_flavors: Default::default(),
+            last_update_check: Default::default(),
+            addon_sort: Default::default(),
+            pinned_addons: Default::default(),
+            config_format: Default::default(),
+            read_only: Default::default(),
+            telemetry_enabled: Default::default(),
+            follow_symlinks: default_true(),
+        }
+    }
 }
 
 impl Config {
@@ -70,264 +350,3714 @@ impl Config {
     }
 
     /// Returns a `Option<PathBuf>` to the root directory of the Flavor.
+    /// This will return `None` if the flavor directory has no parent, eg. `/`.
     pub fn get_root_directory_for_flavor(&self, flavor: &Flavor) -> Option<PathBuf> {
-        if let Some(flavor_dir) = self.wow.directories.get(flavor) {
-            Some(flavor_dir.parent().unwrap().to_path_buf())
+        self.wow
+            .directory(flavor)
+            .and_then(|flavor_dir| flavor_dir.parent().map(Path::to_path_buf))
+    }
+
+    /// Returns a `Option<PathBuf>` to the directory containing the addons,
+    /// which might not exist. Nothing is created. Symlinks are resolved if
+    /// `follow_symlinks` is set.
+    /// This will return `None` if no `wow_directory` is set in the config.
+    pub fn resolve_addon_directory(&self, flavor: &Flavor) -> Option<PathBuf> {
+        self.addon_directory_in_flavor(flavor)
+            .map(|addon_dir| self.apply_follow_symlinks(addon_dir))
+    }
+
+    /// Like `resolve_addon_directory`, but symlinks are never resolved, so the
+    /// path stays inside the flavor directory. Backups rely on this to name
+    /// their entries relative to the WoW directory.
+    pub fn addon_directory_in_flavor(&self, flavor: &Flavor) -> Option<PathBuf> {
+        self.wow
+            .directory(flavor)
+            .map(|dir| cached_addon_directory(&dir, resolve_addon_directory))
+    }
+
+    fn apply_follow_symlinks(&self, path: PathBuf) -> PathBuf {
+        if self.follow_symlinks {
+            resolve_symlinks(path)
         } else {
-            None
+            path
         }
     }
 
-    /// Returns a `Option<PathBuf>` to the directory containing the addons.
-    /// This will return `None` if no `wow_directory` is set in the config.
-    pub fn get_addon_directory_for_flavor(&self, flavor: &Flavor) -> Option<PathBuf> {
-        let dir = self.wow.directories.get(flavor);
-        match dir {
-            Some(dir) => {
-                // The path to the addons directory
-                let mut addon_dir = dir.join("Interface/AddOns");
-
-                // If path doesn't exist, it could have been modified by the user.
-                // Check for a case-insensitive version and use that instead.
-                if !addon_dir.exists() {
-                    let options = MatchOptions {
-                        case_sensitive: false,
-                        ..Default::default()
-                    };
-
-                    // For some reason the case insensitive pattern doesn't work
-                    // unless we add an actual pattern symbol, hence the `?`.
-                    let pattern = format!("{}/?nterface/?ddons", dir.display());
-
-                    for entry in glob::glob_with(&pattern, options).unwrap() {
-                        if let Ok(path) = entry {
-                            addon_dir = path;
-                        }
-                    }
-                }
+    /// Returns the directory containing the addons, creating it if the flavor
+    /// directory exists but the addon directory doesn't. This state can happen
+    /// if you do a fresh install of WoW and launch Ajour before you launch WoW.
+    pub fn ensure_addon_directory(&self, flavor: &Flavor) -> Result<PathBuf, FilesystemError> {
+        let dir = self
+            .wow
+            .directory(flavor)
+            .ok_or(FilesystemError::NoFlavorDirectory { flavor: *flavor })?;
 
-                // If flavor dir exists but not addon dir we try to create it.
-                // This state can happen if you do a fresh install of WoW and
-                // launch Ajour before you launch WoW.
-                if dir.exists() && !addon_dir.exists() {
-                    let _ = create_dir_all(&addon_dir);
-                }
+        if !dir.is_dir() {
+            return Err(FilesystemError::FileDoesntExist { path: dir });
+        }
 
-                Some(addon_dir)
-            }
-            None => None,
+        let addon_dir = cached_addon_directory(&dir, resolve_addon_directory);
+
+        if !addon_dir.exists() {
+            create_dir_all(&addon_dir)?;
         }
+
+        Ok(self.apply_follow_symlinks(addon_dir))
     }
 
-    /// Returns a `Option<PathBuf>` to the directory which will hold the
-    /// temporary zip archives.
-    /// This will return `None` if flavor does not have a directory.
-    pub fn get_download_directory_for_flavor(&self, flavor: Flavor) -> Option<PathBuf> {
-        self.wow.directories.get(&flavor).cloned()
+    /// Returns `true` if `flavor` has a directory set and its addon directory
+    /// exists. Nothing is created.
+    pub fn addon_directory_exists(&self, flavor: &Flavor) -> bool {
+        self.resolve_addon_directory(flavor)
+            .is_some_and(|dir| dir.is_dir())
     }
 
-    /// Returns a `Option<PathBuf>` to the WTF directory.
-    /// This will return `None` if no `wow_directory` is set in the config.
-    pub fn get_wtf_directory_for_flavor(&self, flavor: &Flavor) -> Option<PathBuf> {
-        let dir = self.wow.directories.get(flavor);
-        match dir {
-            Some(dir) => {
-                // The path to the WTF directory
-                let mut addon_dir = dir.join("WTF");
-
-                // If path doesn't exist, it could have been modified by the user.
-                // Check for a case-insensitive version and use that instead.
-                if !addon_dir.exists() {
-                    let options = MatchOptions {
-                        case_sensitive: false,
-                        ..Default::default()
-                    };
-
-                    // For some reason the case insensitive pattern doesn't work
-                    // unless we add an actual pattern symbol, hence the `?`.
-                    let pattern = format!("{}/?tf", dir.display());
-
-                    for entry in glob::glob_with(&pattern, options).unwrap() {
-                        if let Ok(path) = entry {
-                            addon_dir = path;
-                        }
-                    }
-                }
+    /// Returns the flavor directories in display order, as set out in
+    /// `flavor_order`.
+    pub fn sorted_directories(&self) -> Vec<(Flavor, &Path)> {
+        self.wow.ordered_directories(&self.flavor_order)
+    }
 
-                Some(addon_dir)
-            }
-            None => None,
+    /// Returns the flavors which have a directory set with an existing addon
+    /// directory, in display order. Nothing is created.
+    pub fn known_flavors(&self) -> Vec<Flavor> {
+        self.sorted_directories()
+            .into_iter()
+            .map(|(flavor, _)| flavor)
+            .filter(|flavor| self.addon_directory_exists(flavor))
+            .collect()
+    }
+
+    /// Returns the backup directory. If none is set, a `Ajour Backups` folder
+    /// in the user's documents directory is used instead.
+    pub fn backup_directory_or_default(&self) -> PathBuf {
+        self.backup_directory_or(dirs_next::document_dir())
+    }
+
+    fn backup_directory_or(&self, documents_dir: Option<PathBuf>) -> PathBuf {
+        match &self.backup_directory {
+            Some(dir) => dir.clone(),
+            None => documents_dir
+                .map(|dir| dir.join("Ajour Backups"))
+                .unwrap_or_else(|| config_dir().join("backups")),
         }
     }
-}
 
-impl PersistentData for Config {
-    fn relative_path() -> PathBuf {
-        PathBuf::from("ajour.yml")
+    /// Returns the name of the theme to use. If the stored theme is neither
+    /// bundled nor found in the user themes folder, `DEFAULT_THEME` is returned.
+    pub fn theme_or_default(&self) -> String {
+        self.theme_or_default_in(&Config::theme_directory())
     }
-}
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
-pub enum ColumnConfig {
-    V1 {
-        local_version_width: u16,
-        remote_version_width: u16,
-        status_width: u16,
-    },
-    V2 {
-        columns: Vec<ColumnConfigV2>,
-    },
-    V3 {
-        my_addons_columns: Vec<ColumnConfigV2>,
-        catalog_columns: Vec<ColumnConfigV2>,
-        #[serde(default)]
-        aura_columns: Vec<ColumnConfigV2>,
-    },
-}
+    /// Returns a `PathBuf` to the directory holding user themes. Will create
+    /// it if it doesn't exist.
+    pub fn theme_directory() -> PathBuf {
+        let theme_dir = config_dir().join("themes");
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
-pub struct ColumnConfigV2 {
-    pub key: String,
-    pub width: Option<u16>,
-    pub hidden: bool,
-}
+        if !theme_dir.exists() {
+            let _ = create_dir_all(&theme_dir);
+        }
 
-impl Default for ColumnConfig {
-    fn default() -> Self {
-        ColumnConfig::V1 {
-            local_version_width: 150,
-            remote_version_width: 150,
-            status_width: 85,
+        theme_dir
+    }
+
+    fn theme_or_default_in(&self, theme_dir: &Path) -> String {
+        let theme = match &self.theme {
+            Some(theme) => theme,
+            None => return DEFAULT_THEME.to_owned(),
+        };
+
+        if bundled_theme_names().contains(&theme.as_str())
+            || list_themes_in(theme_dir)
+                .unwrap_or_default()
+                .contains(theme)
+        {
+            theme.clone()
+        } else {
+            log::warn!(
+                "theme {:?} is not available, using {:?} instead",
+                theme,
+                DEFAULT_THEME
+            );
+
+            DEFAULT_THEME.to_owned()
         }
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum SelfUpdateChannel {
-    Stable,
-    Beta,
-}
+    /// Returns the config as a JSON profile which can be imported on another
+    /// machine. Machine specific paths are left out.
+    pub fn export_profile(&self) -> Result<String, serde_json::Error> {
+        let mut profile = self.clone();
+        profile.strip_machine_specific();
 
-impl SelfUpdateChannel {
-    pub const fn all() -> [Self; 2] {
-        [SelfUpdateChannel::Stable, SelfUpdateChannel::Beta]
+        serde_json::to_string_pretty(&profile)
     }
-}
 
-impl Default for SelfUpdateChannel {
-    fn default() -> Self {
-        SelfUpdateChannel::Stable
+    /// Replaces the settings with the ones from a JSON profile created by
+    /// `export_profile`. Machine specific paths and the telemetry opt-in are
+    /// kept as they are.
+    pub fn import_profile(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let mut profile: Config = serde_json::from_str(json)?;
+        profile.strip_machine_specific();
+
+        let current = std::mem::replace(self, profile);
+
+        self.wow.directory = current.wow.directory;
+        self.wow.directories = current.wow.directories;
+        self.backup_directory = current.backup_directory;
+        // Only the user can opt in to telemetry.
+        self.telemetry_enabled = current.telemetry_enabled;
+
+        Ok(())
     }
-}
 
-impl Display for SelfUpdateChannel {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let s = match self {
-            SelfUpdateChannel::Stable => "Stable",
-            SelfUpdateChannel::Beta => "Beta",
-        };
+    /// Removes absolute paths which only make sense on this machine.
+    pub fn strip_machine_specific(&mut self) {
+        self.wow.directory = None;
+        self.wow.directories.clear();
+        self.backup_directory = None;
+    }
 
-        write!(f, "{}", s)
+    /// Applies the settings of `overlay` on top of `self`, eg. a local config on
+    /// top of a shared base config.
+    ///
+    /// Precedence rules:
+    /// - Optional settings are taken from `overlay` when set.
+    /// - Maps, like the flavor directories, are merged and entries of `overlay`
+    ///   win on conflicts.
+    /// - Other settings are taken from `overlay` when they differ from the value
+    ///   a missing key deserializes to, as an unset setting can't be told apart
+    ///   from one explicitly set to that value.
+    /// - The telemetry opt-in is always taken from `overlay`.
+    pub fn merge(&mut self, overlay: Config) {
+        merge_option(&mut self.wow.directory, overlay.wow.directory);
+        self.wow.directories.extend(overlay.wow.directories);
+        merge_non_default(&mut self.wow.flavor, overlay.wow.flavor);
+
+        merge_non_default(
+            &mut self.addons.global_release_channel,
+            overlay.addons.global_release_channel,
+        );
+        self.addons.ignored.extend(overlay.addons.ignored);
+        for (flavor, release_channels) in overlay.addons.release_channels {
+            self.addons
+                .release_channels
+                .entry(flavor)
+                .or_default()
+                .extend(release_channels);
+        }
+        merge_non_default(
+            &mut self.addons.delete_saved_variables,
+            overlay.addons.delete_saved_variables,
+        );
+
+        merge_option(&mut self.theme, overlay.theme);
+        merge_non_default(&mut self.column_config, overlay.column_config);
+        merge_option(&mut self.window_size, overlay.window_size);
+        merge_option(&mut self.window_position, overlay.window_position);
+        merge_non_default(&mut self.window_maximized, overlay.window_maximized);
+        merge_non_default(&mut self.start_minimized, overlay.start_minimized);
+        merge_non_default(&mut self.minimize_to_tray, overlay.minimize_to_tray);
+        merge_option(&mut self.scale, overlay.scale);
+        merge_option(&mut self.backup_directory, overlay.backup_directory);
+        merge_non_default(&mut self.backup_addons, overlay.backup_addons);
+        merge_non_default(&mut self.backup_wtf, overlay.backup_wtf);
+        merge_non_default(&mut self.backup_config, overlay.backup_config);
+        merge_option(&mut self.backup_keep_count, overlay.backup_keep_count);
+        merge_option(
+            &mut self.backup_filename_pattern,
+            overlay.backup_filename_pattern,
+        );
+        merge_option(
+            &mut self.backup_compression_level,
+            overlay.backup_compression_level,
+        );
+        merge_option(
+            &mut self.max_concurrent_downloads,
+            overlay.max_concurrent_downloads,
+        );
+        merge_non_default(
+            &mut self.backup_exclude_patterns,
+            overlay.backup_exclude_patterns,
+        );
+        merge_non_default(&mut self.hide_ignored_addons, overlay.hide_ignored_addons);
+        merge_non_default(&mut self.show_updates_only, overlay.show_updates_only);
+        merge_non_default(&mut self.notifications, overlay.notifications);
+        merge_non_default(&mut self.merge_flavor_view, overlay.merge_flavor_view);
+        merge_option(&mut self.default_flavor, overlay.default_flavor);
+        merge_non_default(&mut self.flavor_order, overlay.flavor_order);
+        merge_non_default(&mut self.self_update_channel, overlay.self_update_channel);
+        merge_option(
+            &mut self.self_update_url_override,
+            overlay.self_update_url_override,
+        );
+        self.weak_auras_account.extend(overlay.weak_auras_account);
+        self.weak_auras_accounts.extend(overlay.weak_auras_accounts);
+        if overlay.alternating_row_colors != default_true() {
+            self.alternating_row_colors = overlay.alternating_row_colors;
+        }
+        merge_non_default(&mut self.language, overlay.language);
+        merge_option(&mut self.catalog_source, overlay.catalog_source);
+        merge_non_default(&mut self.auto_update, overlay.auto_update);
+        merge_option(
+            &mut self.auto_update_interval_minutes,
+            overlay.auto_update_interval_minutes,
+        );
+        self.auto_update_flavors.extend(overlay.auto_update_flavors);
+        self.last_update_check.extend(overlay.last_update_check);
+        self.pinned_addons.extend(overlay.pinned_addons);
+        merge_option(&mut self.addon_sort, overlay.addon_sort);
+        merge_option(&mut self.config_format, overlay.config_format);
+        merge_non_default(&mut self.read_only, overlay.read_only);
+        // Only the user can opt in to telemetry, never a shared base config.
+        self.telemetry_enabled = overlay.telemetry_enabled;
+        if overlay.follow_symlinks != default_true() {
+            self.follow_symlinks = overlay.follow_symlinks;
+        }
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Hash, PartialOrd, Ord)]
-pub enum Language {
-    Czech,
-    Norwegian,
-    English,
-    Danish,
-    German,
-    French,
-    Hungarian,
-    Portuguese,
-    Russian,
-    Slovak,
-    Swedish,
-    Spanish,
-    Turkish,
-    Ukrainian,
-}
+    /// Returns the number of addons to download at the same time, which is
+    /// `DEFAULT_MAX_CONCURRENT_DOWNLOADS` if none is set, and at least one.
+    pub fn effective_max_concurrent_downloads(&self) -> usize {
+        self.max_concurrent_downloads
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_DOWNLOADS)
+            .max(1)
+    }
 
-impl std::fmt::Display for Language {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Language::Czech => "Čeština",
-                Language::Danish => "Dansk",
-                Language::English => "English",
-                Language::French => "Français",
-                Language::German => "Deutsch",
-                Language::Hungarian => "Magyar",
-                Language::Norwegian => "Norsk Bokmål",
-                Language::Portuguese => "Português",
-                Language::Russian => "Pусский",
-                Language::Slovak => "Slovenčina",
-                Language::Spanish => "Español",
-                Language::Swedish => "Svenska",
-                Language::Turkish => "Türkçe",
-                Language::Ukrainian => "Yкраїнська",
+    /// Returns a `DownloadLimiter` for `effective_max_concurrent_downloads`.
+    pub fn download_limiter(&self) -> DownloadLimiter {
+        DownloadLimiter::new(self.effective_max_concurrent_downloads())
+    }
+
+    /// Returns the catalog source to use, which is `catalog::Source::default()`
+    /// if none is set. A source which is no longer supported is replaced as
+    /// set out in `catalog::Source::migrate`.
+    pub fn effective_catalog_source(&self) -> catalog::Source {
+        match &self.catalog_source {
+            Some(source) => migrated_catalog_source(source),
+            None => catalog::Source::default(),
+        }
+    }
+
+    /// Returns the saved position and size of the window as `(x, y, width,
+    /// height)`, if both are set.
+    pub fn window_bounds(&self) -> Option<(i32, i32, u32, u32)> {
+        let (x, y) = self.window_position?;
+        let (width, height) = self.window_size?;
+
+        Some((x, y, width, height))
+    }
+
+    /// Updates the saved window state. The size and position of a maximized
+    /// window aren't saved, so the window can be restored to its previous
+    /// bounds. A `position` of `None` keeps the saved position.
+    pub fn set_window_state(
+        &mut self,
+        size: (u32, u32),
+        position: Option<(i32, i32)>,
+        maximized: bool,
+    ) {
+        if !maximized {
+            self.window_size = Some(size);
+
+            if position.is_some() {
+                self.window_position = position;
             }
-        )
+        }
+
+        self.window_maximized = maximized;
     }
-}
 
-impl Language {
-    // Alphabetically sorted based on their local name (@see `impl Display`).
-    pub const ALL: [Language; 14] = [
-        Language::Czech,
-        Language::Danish,
-        Language::German,
-        Language::English,
-        Language::Spanish,
-        Language::French,
-        Language::Hungarian,
-        Language::Norwegian,
-        Language::Portuguese,
-        Language::Russian,
-        Language::Slovak,
-        Language::Swedish,
-        Language::Turkish,
-        Language::Ukrainian,
-    ];
+    /// Forgets the saved window position if less than
+    /// `MIN_VISIBLE_WINDOW_PIXELS` of the window would be on any of `displays`,
+    /// given as `(x, y, width, height)`. This happens when a display has been
+    /// disconnected since the position was saved.
+    ///
+    /// Nothing is changed if `displays` is empty.
+    pub fn clamp_window_to(&mut self, displays: &[(i32, i32, u32, u32)]) {
+        let (x, y) = match self.window_position {
+            Some(position) if !displays.is_empty() => position,
+            _ => return,
+        };
+        let (width, height) = self
+            .window_size
+            .unwrap_or((MIN_VISIBLE_WINDOW_PIXELS, MIN_VISIBLE_WINDOW_PIXELS));
 
-    pub const fn language_code(self) -> &'static str {
-        match self {
-            Language::Czech => "cs_CZ",
-            Language::English => "en_US",
-            Language::Danish => "da_DK",
-            Language::German => "de_DE",
-            Language::French => "fr_FR",
-            Language::Russian => "ru_RU",
-            Language::Swedish => "se_SE",
-            Language::Spanish => "es_ES",
-            Language::Hungarian => "hu_HU",
-            Language::Norwegian => "nb_NO",
-            Language::Slovak => "sk_SK",
-            Language::Turkish => "tr_TR",
-            Language::Portuguese => "pt_PT",
-            Language::Ukrainian => "uk_UA",
+        // Length of the overlap of two ranges, given by their start and length.
+        let overlap = |a: i32, a_len: u32, b: i32, b_len: u32| {
+            let start = i64::from(a).max(i64::from(b));
+            let end = (i64::from(a) + i64::from(a_len)).min(i64::from(b) + i64::from(b_len));
+
+            (end - start).max(0)
+        };
+        let min_visible = i64::from(MIN_VISIBLE_WINDOW_PIXELS.min(width).min(height));
+
+        let is_visible = displays.iter().any(|&(dx, dy, dwidth, dheight)| {
+            overlap(x, width, dx, dwidth) >= min_visible
+                && overlap(y, height, dy, dheight) >= min_visible
+        });
+
+        if !is_visible {
+            log::debug!("window position {:?} is off-screen, ignoring it", (x, y));
+
+            self.window_position = None;
         }
     }
-}
 
-impl Default for Language {
-    fn default() -> Language {
-        Language::English
+    /// Returns `true` if addons of `flavor` should be updated automatically.
+    /// A per flavor override takes precedence over the global `auto_update`.
+    pub fn should_auto_update(&self, flavor: &Flavor) -> bool {
+        self.auto_update_flavors
+            .get(flavor)
+            .copied()
+            .unwrap_or(self.auto_update)
     }
-}
 
-/// Returns a Config.
-///
-/// This functions handles the initialization of a Config.
-pub async fn load_config() -> Result<Config, FilesystemError> {
-    log::debug!("loading config");
+    /// Remembers that My Addons is sorted by the column `key` in `direction`.
+    pub fn set_sort(&mut self, key: String, direction: SortDirection) {
+        self.addon_sort = Some(SortState {
+            column_key: key,
+            direction,
+        });
+    }
 
-    Ok(Config::load_or_default()?)
-}
+    /// Returns how My Addons was last sorted, by status descending if it never
+    /// was.
+    pub fn sort_state(&self) -> SortState {
+        self.addon_sort.clone().unwrap_or_default()
+    }
 
-const fn default_true() -> bool {
+    /// Returns `true` if a desktop notification should be shown for `event`.
+    pub fn should_notify(&self, event: NotificationEvent) -> bool {
+        self.notifications.should_notify(event)
+    }
+
+    /// Returns `true` if the addon folder is pinned for the flavor.
+    pub fn is_pinned(&self, flavor: &Flavor, folder: &str) -> bool {
+        self.pinned_version(flavor, folder).is_some()
+    }
+
+    /// Returns the version the addon folder is pinned at for the flavor.
+    pub fn pinned_version(&self, flavor: &Flavor, folder: &str) -> Option<&str> {
+        self.pinned_addons
+            .get(flavor)
+            .and_then(|pinned| pinned.get(folder))
+            .map(String::as_str)
+    }
+
+    /// Pins the addon folder at `version` for the flavor, replacing any
+    /// version it was pinned at before.
+    pub fn pin(&mut self, flavor: Flavor, folder: String, version: String) {
+        self.pinned_addons
+            .entry(flavor)
+            .or_default()
+            .insert(folder, version);
+    }
+
+    /// Removes the pin of the addon folder for the flavor.
+    pub fn unpin(&mut self, flavor: &Flavor, folder: &str) {
+        if let Some(pinned) = self.pinned_addons.get_mut(flavor) {
+            pinned.remove(folder);
+
+            if pinned.is_empty() {
+                self.pinned_addons.remove(flavor);
+            }
+        }
+    }
+
+    /// Returns `true` if the addon folder should be left out when updating all
+    /// addons of the flavor, because it's ignored or pinned.
+    pub fn should_skip_update(&self, flavor: &Flavor, folder: &str) -> bool {
+        self.addons.is_ignored(flavor, folder) || self.is_pinned(flavor, folder)
+    }
+
+    /// Records that addons of `flavor` were checked for updates at `now`.
+    pub fn record_update_check(&mut self, flavor: Flavor, now: SystemTime) {
+        let secs = match now.duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs() as i64,
+            Err(error) => -(error.duration().as_secs() as i64),
+        };
+
+        self.last_update_check.insert(flavor, secs);
+    }
+
+    /// Returns when addons of `flavor` were last checked for updates.
+    /// This will return `None` if they never were.
+    pub fn last_update_check(&self, flavor: Flavor) -> Option<SystemTime> {
+        let secs = *self.last_update_check.get(&flavor)?;
+
+        if secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(secs as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))
+        }
+    }
+
+    /// Returns the interval between automatic update checks, which is at least
+    /// `MIN_AUTO_UPDATE_INTERVAL_MINUTES`.
+    /// This will return `None` if no interval is set.
+    pub fn auto_update_interval(&self) -> Option<Duration> {
+        self.auto_update_interval_minutes.map(|minutes| {
+            let minutes = minutes.max(MIN_AUTO_UPDATE_INTERVAL_MINUTES);
+
+            Duration::from_secs(u64::from(minutes) * 60)
+        })
+    }
+
+    /// Returns `scale` clamped between `MIN_SCALE` and `MAX_SCALE`.
+    /// This will return `1.0` if no scale is set.
+    pub fn normalized_scale(&self) -> f64 {
+        match self.scale {
+            Some(scale) if scale.is_finite() => scale.clamp(MIN_SCALE, MAX_SCALE),
+            _ => 1.0,
+        }
+    }
+
+    /// Returns the UI scales which `Config::cycle_scale` steps through, in
+    /// ascending order.
+    pub fn scale_presets() -> &'static [f64] {
+        &SCALE_PRESETS
+    }
+
+    /// Sets `scale` to the preset after the one nearest to the current scale,
+    /// wrapping around after the last one, and returns it.
+    pub fn cycle_scale(&mut self) -> f64 {
+        let current = self.normalized_scale();
+        let presets = Config::scale_presets();
+
+        let nearest = presets
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                (*a - current)
+                    .abs()
+                    .partial_cmp(&(*b - current).abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map_or(0, |(index, _)| index);
+
+        let scale = presets[(nearest + 1) % presets.len()];
+        self.scale = Some(scale);
+
+        scale
+    }
+
+    /// Stores the normalized scale back into `scale`.
+    pub fn clamp_scale(&mut self) {
+        if self.scale.is_some() {
+            self.scale = Some(self.normalized_scale());
+        }
+    }
+
+    /// Sets the directory of `flavor` after normalizing it.
+    ///
+    /// An existing path is canonicalized, and a trailing `Interface/AddOns` or
+    /// `Interface` is stripped in case the addon folder was picked instead of
+    /// the flavor folder. Paths which don't look like a flavor folder are
+    /// rejected.
+    ///
+    /// Returns `ConfigChange::DirectoryChanged` if the directory differs from
+    /// the one already set.
+    pub fn set_flavor_directory(
+        &mut self,
+        flavor: Flavor,
+        path: PathBuf,
+    ) -> Result<Option<ConfigChange>, DirectoryError> {
+        let path = normalize_flavor_directory(&expand_path(&path));
+
+        if !path.is_dir() {
+            return Err(DirectoryError::DoesntExist { path });
+        }
+
+        let is_flavor_folder = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Flavor::from_folder_name)
+            .is_some();
+        let has_wow_folders = resolve_addon_directory(&path)
+            .parent()
+            .is_some_and(Path::is_dir)
+            || resolve_case_insensitive_subdir(&path, "WTF").is_dir();
+
+        if !is_flavor_folder && !has_wow_folders {
+            return Err(DirectoryError::NotFlavorDirectory { flavor, path });
+        }
+
+        if self.wow.directories.get(&flavor) == Some(&path) {
+            return Ok(None);
+        }
+
+        self.wow.directories.insert(flavor, path);
+        clear_directory_cache();
+
+        Ok(Some(ConfigChange::DirectoryChanged(flavor)))
+    }
+
+    /// Sets the theme and returns `ConfigChange::ThemeChanged` if it differs.
+    /// `None` resets it to the default theme.
+    ///
+    /// Fails with `ThemeError::UnknownTheme` unless `theme` is bundled or found
+    /// in `Config::theme_directory()`, in which case the theme is left as is.
+    pub fn set_theme(&mut self, theme: Option<String>) -> Result<Option<ConfigChange>, ThemeError> {
+        self.set_theme_in(theme, &Config::theme_directory())
+    }
+
+    fn set_theme_in(
+        &mut self,
+        theme: Option<String>,
+        theme_dir: &Path,
+    ) -> Result<Option<ConfigChange>, ThemeError> {
+        if let Some(name) = &theme {
+            let exists = bundled_theme_names().contains(&name.as_str())
+                || list_themes_in(theme_dir).unwrap_or_default().contains(name);
+
+            if !exists {
+                return Err(ThemeError::UnknownTheme { name: name.clone() });
+            }
+        }
+
+        Ok(change_if_different(
+            &mut self.theme,
+            theme,
+            ConfigChange::ThemeChanged,
+        ))
+    }
+
+    /// Sets the language and returns `ConfigChange::LanguageChanged` if it
+    /// differs.
+    pub fn set_language(&mut self, language: Language) -> Option<ConfigChange> {
+        change_if_different(&mut self.language, language, ConfigChange::LanguageChanged)
+    }
+
+    /// Sets the scale and returns `ConfigChange::ScaleChanged` if it differs.
+    pub fn set_scale(&mut self, scale: Option<f64>) -> Option<ConfigChange> {
+        change_if_different(&mut self.scale, scale, ConfigChange::ScaleChanged)
+    }
+
+    /// Points `flavor` to `new_path`, like `set_flavor_directory`, and keeps
+    /// the WeakAuras account of `flavor` only if it also exists in the new
+    /// WTF directory.
+    pub fn rename_flavor_directory(
+        &mut self,
+        flavor: Flavor,
+        new_path: PathBuf,
+    ) -> Result<Option<ConfigChange>, DirectoryError> {
+        let change = self.set_flavor_directory(flavor, new_path)?;
+
+        if self.weak_auras_account.contains_key(&flavor)
+            && self.weak_auras_account_path(&flavor).is_none()
+        {
+            if let Some(account) = self.weak_auras_account.remove(&flavor) {
+                log::warn!(
+                    "WeakAuras account {} doesn't exist for {}, clearing it",
+                    account,
+                    flavor
+                );
+            }
+        }
+
+        let existing = self.list_wtf_accounts(&flavor);
+        if let Some(accounts) = self.weak_auras_accounts.get_mut(&flavor) {
+            accounts.retain(|account| {
+                let exists = existing.contains(account);
+                if !exists {
+                    log::warn!(
+                        "WeakAuras account {} doesn't exist for {}, clearing it",
+                        account,
+                        flavor
+                    );
+                }
+                exists
+            });
+
+            if accounts.is_empty() {
+                self.weak_auras_accounts.remove(&flavor);
+            }
+        }
+
+        Ok(change)
+    }
+
+    /// Removes `flavor` together with its WeakAuras account, ignored addons,
+    /// release channels, auto update override and last update check. Does
+    /// nothing if `flavor` has no directory.
+    ///
+    /// If `flavor` is the current or default flavor, the first remaining
+    /// flavor in display order takes its place.
+    pub fn clear_flavor(&mut self, flavor: &Flavor) {
+        if self.wow.directories.remove(flavor).is_none() {
+            return;
+        }
+
+        let replacement = self.sorted_directories().first().map(|(flavor, _)| *flavor);
+        if self.wow.flavor == *flavor {
+            self.wow.flavor = replacement.unwrap_or_default();
+        }
+        if self.default_flavor == Some(*flavor) {
+            self.default_flavor = replacement;
+        }
+
+        self.weak_auras_account.remove(flavor);
+        self.weak_auras_accounts.remove(flavor);
+        self.auto_update_flavors.remove(flavor);
+        self.addons.ignored.remove(flavor);
+        self.addons.release_channels.remove(flavor);
+        self.pinned_addons.remove(flavor);
+        self.last_update_check.remove(flavor);
+
+        clear_directory_cache();
+    }
+
+    /// Runs the migrations of `SCHEMA_MIGRATIONS` newer than `schema_version`.
+    ///
+    /// A config written by a newer version of Ajour is loaded as it is, with a
+    /// warning, as settings this version doesn't know about are lost once it's
+    /// saved.
+    pub fn migrate_schema(&mut self) {
+        self.migrate_schema_with(CONFIG_SCHEMA_VERSION, SCHEMA_MIGRATIONS);
+    }
+
+    fn migrate_schema_with(&mut self, current: u32, migrations: &[SchemaMigration]) {
+        if self.schema_version > current {
+            log::warn!(
+                "config was written by a newer version of Ajour (schema {}, supported {}), unknown settings are dropped when saving",
+                self.schema_version,
+                current
+            );
+            return;
+        }
+
+        for (version, migrate) in migrations {
+            if *version > self.schema_version && *version <= current {
+                log::info!("migrating config to schema {}", version);
+                migrate(self);
+                self.schema_version = *version;
+            }
+        }
+
+        self.schema_version = current;
+    }
+
+    /// Replaces a catalog source which is no longer supported, as set out in
+    /// `catalog::Source::migrate`.
+    pub fn migrate_catalog_source(&mut self) {
+        self.catalog_source = self.catalog_source.as_ref().map(migrated_catalog_source);
+    }
+
+    /// Moves the accounts of `weak_auras_account` to the front of
+    /// `weak_auras_accounts`.
+    pub fn migrate_weak_auras_accounts(&mut self) {
+        for (flavor, account) in self.weak_auras_account.drain() {
+            let accounts = self.weak_auras_accounts.entry(flavor).or_default();
+            accounts.retain(|a| a != &account);
+            accounts.insert(0, account);
+        }
+    }
+
+    /// Converts `column_config` into the V3 representation, with a copy of
+    /// the My Addons columns for every flavor, so it can be persisted once and
+    /// read without further migration.
+    pub fn migrate_columns_to_v3(&mut self) {
+        self.column_config.migrate_to_v3();
+        self.column_config.migrate_to_per_flavor();
+    }
+
+    /// Checks every configured flavor directory and returns the issues found.
+    ///
+    /// Nothing is created or modified, except for a short-lived probe file
+    /// used to test whether the addon directory is writable.
+    pub fn validate_directories(&self) -> Vec<DirectoryIssue> {
+        let mut issues = vec![];
+
+        for (flavor, dir) in self.wow.sorted_directories() {
+            let dir = expand_path(dir);
+
+            if !dir.is_dir() {
+                issues.push(DirectoryIssue::MissingRoot { flavor });
+                continue;
+            }
+
+            let addon_dir = resolve_addon_directory(&dir);
+            if !addon_dir.is_dir() {
+                issues.push(DirectoryIssue::MissingAddonDir { flavor });
+                continue;
+            }
+
+            if !is_writable(&addon_dir) {
+                issues.push(DirectoryIssue::NotWritable {
+                    flavor,
+                    path: addon_dir,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Returns a `Option<PathBuf>` to the directory which will hold the
+    /// temporary zip archives.
+    /// This will return `None` if flavor does not have a directory.
+    pub fn get_download_directory_for_flavor(&self, flavor: Flavor) -> Option<PathBuf> {
+        self.wow.directory(&flavor)
+    }
+
+    /// Returns a `Option<PathBuf>` to the WTF directory.
+    /// This will return `None` if no `wow_directory` is set in the config.
+    pub fn get_wtf_directory_for_flavor(&self, flavor: &Flavor) -> Option<PathBuf> {
+        self.wow
+            .directory(flavor)
+            .map(|dir| resolve_case_insensitive_subdir(&dir, "WTF"))
+    }
+
+    /// Returns a `Option<PathBuf>` to the Screenshots directory, which might
+    /// not exist. Nothing is created.
+    /// This will return `None` if no `wow_directory` is set in the config.
+    pub fn get_screenshots_directory_for_flavor(&self, flavor: &Flavor) -> Option<PathBuf> {
+        self.wow
+            .directory(flavor)
+            .map(|dir| resolve_case_insensitive_subdir(&dir, "Screenshots"))
+    }
+
+    /// Returns a `Option<PathBuf>` to the Logs directory, which might not
+    /// exist. Nothing is created.
+    /// This will return `None` if no `wow_directory` is set in the config.
+    pub fn get_logs_directory_for_flavor(&self, flavor: &Flavor) -> Option<PathBuf> {
+        self.wow
+            .directory(flavor)
+            .map(|dir| resolve_case_insensitive_subdir(&dir, "Logs"))
+    }
+
+    /// Deletes the files in the Logs directory of `flavor` which haven't been
+    /// modified for `older_than`. Subdirectories are left alone. Returns the
+    /// number of deleted files.
+    pub fn clean_logs(
+        &self,
+        flavor: &Flavor,
+        older_than: Duration,
+    ) -> Result<usize, FilesystemError> {
+        let flavor_dir = self
+            .wow
+            .directory(flavor)
+            .ok_or(FilesystemError::NoFlavorDirectory { flavor: *flavor })?;
+        let logs_dir = resolve_case_insensitive_subdir(&flavor_dir, "Logs");
+
+        if !logs_dir.is_dir() {
+            return Ok(0);
+        }
+
+        clean_logs_dir(&flavor_dir, &logs_dir, older_than, SystemTime::now())
+    }
+
+    /// Returns a `Option<PathBuf>` to the Fonts directory, which might not
+    /// exist. Nothing is created.
+    /// This will return `None` if no `wow_directory` is set in the config.
+    pub fn get_fonts_directory_for_flavor(&self, flavor: &Flavor) -> Option<PathBuf> {
+        self.wow
+            .directory(flavor)
+            .map(|dir| resolve_case_insensitive_subdir(&dir, "Fonts"))
+    }
+
+    /// Returns the Fonts directory, creating it if the flavor directory exists
+    /// but the Fonts directory doesn't.
+    pub fn ensure_fonts_directory(&self, flavor: &Flavor) -> Result<PathBuf, FilesystemError> {
+        let dir = self
+            .wow
+            .directory(flavor)
+            .ok_or(FilesystemError::NoFlavorDirectory { flavor: *flavor })?;
+
+        if !dir.is_dir() {
+            return Err(FilesystemError::FileDoesntExist { path: dir });
+        }
+
+        let fonts_dir = resolve_case_insensitive_subdir(&dir, "Fonts");
+
+        if !fonts_dir.exists() {
+            create_dir_all(&fonts_dir)?;
+        }
+
+        Ok(fonts_dir)
+    }
+
+    /// Returns what a backup of `flavor` covers with the enabled backup flags:
+    /// the `Interface` folder holding the addons, the WTF folder and the Ajour
+    /// config directory. Only existing paths are returned.
+    pub fn backup_targets(&self, flavor: &Flavor) -> Vec<(BackupKind, PathBuf)> {
+        self.backup_targets_in(flavor, &config_dir_path())
+    }
+
+    fn backup_targets_in(&self, flavor: &Flavor, config_dir: &Path) -> Vec<(BackupKind, PathBuf)> {
+        let mut targets = vec![];
+
+        if self.backup_addons {
+            let interface_dir = self
+                .addon_directory_in_flavor(flavor)
+                .and_then(|dir| dir.parent().map(Path::to_path_buf));
+
+            if let Some(dir) = interface_dir {
+                targets.push((BackupKind::Addons, dir));
+            }
+        }
+
+        if self.backup_wtf {
+            if let Some(dir) = self.get_wtf_directory_for_flavor(flavor) {
+                targets.push((BackupKind::Wtf, dir));
+            }
+        }
+
+        if self.backup_config {
+            targets.push((BackupKind::Config, config_dir.to_path_buf()));
+        }
+
+        targets.retain(|(_, path)| path.exists());
+        targets
+    }
+
+    /// Returns a `Option<PathBuf>` to the addon cache directory of `flavor`,
+    /// inside the Ajour config directory. Will create it if it doesn't exist.
+    /// This will return `None` if the directory couldn't be created.
+    pub fn addon_cache_directory(&self, flavor: &Flavor) -> Option<PathBuf> {
+        addon_cache_directory_in(&config_dir(), flavor)
+    }
+
+    /// Returns the directory holding the caches of Ajour, inside the Ajour
+    /// config directory.
+    pub fn cache_directory(&self) -> PathBuf {
+        config_dir_path().join("cache")
+    }
+
+    /// Removes everything inside `cache_directory`, except for the addon cache
+    /// and addon archives which are being downloaded or installed. Nothing
+    /// outside of it is touched, including files symlinked from it.
+    pub fn clear_cache(&self) -> Result<CacheClearReport, FilesystemError> {
+        clear_cache_dir(&self.cache_directory())
+    }
+
+    /// Returns a sanitized summary of the config to attach to bug reports.
+    pub fn summary(&self) -> ConfigSummary {
+        ConfigSummary::new(self)
+    }
+
+    /// Calls `callback` from a background thread whenever the config file at
+    /// `path` is edited outside of Ajour, so it can be reloaded. Bursts of
+    /// edits are reported once, and Ajour's own saves are ignored.
+    ///
+    /// Watching stops when the returned `FileWatcher` is dropped.
+    pub fn watch(path: impl AsRef<Path>, callback: impl FnMut() + Send + 'static) -> FileWatcher {
+        watch_file(path, WATCH_DEBOUNCE, callback)
+    }
+
+    /// Returns a shareable list of `addons` installed for `flavor`.
+    pub fn export_addon_list(&self, flavor: Flavor, addons: &[Addon]) -> AddonList {
+        AddonList::new(self, flavor, addons)
+    }
+
+    /// Resolves the entries of `list` against `catalog` for installation.
+    ///
+    /// Entries which aren't found by their repository id are looked up by
+    /// name in our `catalog_source`.
+    pub fn import_addon_list<'a>(
+        &self,
+        list: &AddonList,
+        catalog: &'a catalog::Catalog,
+    ) -> AddonListImport<'a> {
+        list.resolve(self, catalog)
+    }
+
+    /// Returns the WeakAuras accounts of `flavor`, with the account of the
+    /// singular `weak_auras_account` first if it hasn't been migrated yet.
+    pub fn weak_auras_accounts(&self, flavor: &Flavor) -> Vec<String> {
+        let mut accounts: Vec<String> = self
+            .weak_auras_account
+            .get(flavor)
+            .cloned()
+            .into_iter()
+            .collect();
+
+        for account in self.weak_auras_accounts.get(flavor).into_iter().flatten() {
+            if !accounts.contains(account) {
+                accounts.push(account.clone());
+            }
+        }
+
+        accounts
+    }
+
+    /// Makes `account` the first WeakAuras account of `flavor`, adding it if
+    /// it isn't one yet.
+    pub fn select_weak_auras_account(&mut self, flavor: Flavor, account: String) {
+        let mut accounts = self.weak_auras_accounts(&flavor);
+        accounts.retain(|a| a != &account);
+        accounts.insert(0, account);
+
+        self.weak_auras_account.remove(&flavor);
+        self.weak_auras_accounts.insert(flavor, accounts);
+    }
+
+    /// Removes `account` from the WeakAuras accounts of `flavor`.
+    pub fn remove_weak_auras_account(&mut self, flavor: &Flavor, account: &str) {
+        if self.weak_auras_account.get(flavor).map(String::as_str) == Some(account) {
+            self.weak_auras_account.remove(flavor);
+        }
+
+        if let Some(accounts) = self.weak_auras_accounts.get_mut(flavor) {
+            accounts.retain(|a| a != account);
+
+            if accounts.is_empty() {
+                self.weak_auras_accounts.remove(flavor);
+            }
+        }
+    }
+
+    /// Returns a `Option<PathBuf>` to the folder of the first WeakAuras account
+    /// of `flavor`. This will return `None` if no account is set or the folder
+    /// doesn't exist.
+    pub fn weak_auras_account_path(&self, flavor: &Flavor) -> Option<PathBuf> {
+        let account = self.weak_auras_accounts(flavor).into_iter().next()?;
+        let path = self
+            .get_wtf_directory_for_flavor(flavor)?
+            .join("Account")
+            .join(account);
+
+        if path.is_dir() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the sorted names of the account folders in the WTF directory of
+    /// `flavor`.
+    pub fn list_wtf_accounts(&self, flavor: &Flavor) -> Vec<String> {
+        let account_dir = match self.get_wtf_directory_for_flavor(flavor) {
+            Some(wtf_dir) => wtf_dir.join("Account"),
+            None => return vec![],
+        };
+
+        let entries = match std::fs::read_dir(account_dir) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+
+        let mut accounts: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_owned))
+            .filter(|name| name != "SavedVariables")
+            .collect();
+
+        accounts.sort();
+        accounts
+    }
+
+    /// Restores the default layout of every column section.
+    pub fn reset_columns(&mut self) {
+        self.column_config = ColumnConfig::default_v3();
+    }
+
+    /// Restores the default layout of the columns in `section`.
+    pub fn reset_columns_section(&mut self, section: ColumnSection) {
+        self.column_config.reset_section(section);
+    }
+
+    /// Returns the absolute path of the config file. Unlike
+    /// `PersistentData::path()`, this doesn't create any directories.
+    pub fn on_disk_path() -> PathBuf {
+        config_dir_path().join(Config::relative_path())
+    }
+
+    /// Loads the config like `load_config`, but also returns the keys in the
+    /// file which aren't recognized, eg. due to a typo. Unknown keys are
+    /// logged as a warning and otherwise ignored.
+    pub fn load_strict() -> Result<(Config, Vec<String>), FilesystemError> {
+        let path = Config::path()?;
+
+        if !path.exists() {
+            return Ok((Config::load_or_default()?, vec![]));
+        }
+
+        let raw = deserialize(&path, &read_to_string(&path)?)?;
+        let (config, unknown_keys) = parse_strict(raw)?;
+
+        for key in unknown_keys.iter() {
+            log::warn!("unknown key in {:?}: {}", path, key);
+        }
+
+        Ok((config, unknown_keys))
+    }
+}
+
+/// Parses a config and returns it along with the keys which aren't part of
+/// `Config`, or of its `wow` and `addons` sections.
+fn parse_strict(raw: serde_yaml::Value) -> Result<(Config, Vec<String>), serde_yaml::Error> {
+    let config: Config = serde_yaml::from_value(raw.clone())?;
+
+    // Every field is serialized, so any key missing from the serialized
+    // config must be unknown.
+    let known = serde_yaml::to_value(&config)?;
+
+    let mut unknown_keys = unknown_mapping_keys(&raw, &known, None);
+
+    for section in ["wow", "addons"].iter() {
+        if let (Some(raw), Some(known)) = (raw.get(section), known.get(section)) {
+            unknown_keys.extend(unknown_mapping_keys(raw, known, Some(section)));
+        }
+    }
+
+    Ok((config, unknown_keys))
+}
+
+/// Returns the keys of the `raw` mapping which are missing from `known`,
+/// prefixed with `section` if set.
+fn unknown_mapping_keys(
+    raw: &serde_yaml::Value,
+    known: &serde_yaml::Value,
+    section: Option<&str>,
+) -> Vec<String> {
+    let (raw, known) = match (raw.as_mapping(), known.as_mapping()) {
+        (Some(raw), Some(known)) => (raw, known),
+        _ => return vec![],
+    };
+
+    raw.iter()
+        .map(|(key, _)| key)
+        .filter(|key| !known.contains_key(key))
+        .map(|key| {
+            let key = match key {
+                serde_yaml::Value::String(key) => key.clone(),
+                key => serde_yaml::to_string(key)
+                    .map(|s| s.trim_start_matches("---").trim().to_owned())
+                    .unwrap_or_default(),
+            };
+
+            match section {
+                Some(section) => format!("{}.{}", section, key),
+                None => key,
+            }
+        })
+        .collect()
+}
+
+/// Replaces `target` with `value` if it's set.
+fn merge_option<T>(target: &mut Option<T>, value: Option<T>) {
+    if value.is_some() {
+        *target = value;
+    }
+}
+
+/// Returns the replacement of `source`, logging it if `source` was migrated.
+fn migrated_catalog_source(source: &catalog::Source) -> catalog::Source {
+    let migrated = source.clone().migrate();
+
+    if &migrated != source {
+        log::warn!(
+            "catalog source {} is no longer supported, using {} instead",
+            source,
+            migrated
+        );
+    }
+
+    migrated
+}
+
+/// Replaces `target` with `value` and returns `change` if they differ.
+fn change_if_different<T: PartialEq>(
+    target: &mut T,
+    value: T,
+    change: ConfigChange,
+) -> Option<ConfigChange> {
+    if *target == value {
+        return None;
+    }
+
+    *target = value;
+    Some(change)
+}
+
+/// Replaces `target` with `value` if it isn't the default.
+fn merge_non_default<T: Default + PartialEq>(target: &mut T, value: T) {
+    if value != T::default() {
+        *target = value;
+    }
+}
+
+/// Returns the addon directory of `dir` from the cache, using `resolve` to
+/// resolve and cache it on the first lookup.
+fn cached_addon_directory<F>(dir: &Path, resolve: F) -> PathBuf
+where
+    F: FnOnce(&Path) -> PathBuf,
+{
+    if let Some(addon_dir) = ADDON_DIRECTORY_CACHE.read().unwrap().get(dir) {
+        return addon_dir.clone();
+    }
+
+    let addon_dir = resolve(dir);
+
+    ADDON_DIRECTORY_CACHE
+        .write()
+        .unwrap()
+        .insert(dir.to_path_buf(), addon_dir.clone());
+
+    addon_dir
+}
+
+fn addon_cache_directory_in(root: &Path, flavor: &Flavor) -> Option<PathBuf> {
+    let folder_name = flavor.folder_name();
+    let dir = root
+        .join("cache")
+        .join("addons")
+        .join(folder_name.trim_matches('_'));
+
+    create_dir_all(&dir).ok()?;
+
+    Some(dir)
+}
+
+/// Canonicalizes `path` if it exists and strips a trailing `Interface/AddOns`
+/// or `Interface`, ignoring case.
+fn normalize_flavor_directory(path: &Path) -> PathBuf {
+    // Canonical paths on Windows use the `\\?\` prefix, which glob can't handle.
+    #[cfg(not(windows))]
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    #[cfg(windows)]
+    let path = path.to_path_buf();
+
+    let is_named = |path: &Path, name: &str| {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.eq_ignore_ascii_case(name))
+    };
+
+    let mut path = path.as_path();
+
+    if is_named(path, "AddOns") {
+        if let Some(parent) = path.parent().filter(|p| is_named(p, "Interface")) {
+            path = parent;
+        }
+    }
+
+    if is_named(path, "Interface") {
+        if let Some(parent) = path.parent() {
+            path = parent;
+        }
+    }
+
+    path.to_path_buf()
+}
+
+/// Canonicalizes `path` to resolve any symlinks in it. Returns `path` as it is
+/// if it doesn't exist.
+fn resolve_symlinks(path: PathBuf) -> PathBuf {
+    let canonical = match path.canonicalize() {
+        Ok(canonical) => canonical,
+        Err(_) => return path,
+    };
+
+    // Canonical paths on Windows use the `\\?\` prefix, which glob can't
+    // handle, so it's stripped from paths on a drive.
+    #[cfg(windows)]
+    {
+        let lossy = canonical.to_string_lossy();
+
+        if let Some(stripped) = lossy.strip_prefix(r"\\?\") {
+            if !stripped.starts_with(r"UNC\") {
+                return PathBuf::from(stripped);
+            }
+        }
+    }
+
+    canonical
+}
+
+/// Returns the path to the addons directory inside a flavor directory.
+fn resolve_addon_directory(dir: &Path) -> PathBuf {
+    resolve_case_insensitive_subdir(dir, "Interface/AddOns")
+}
+
+/// Deletes the files directly inside `logs_dir` last modified before
+/// `now - older_than`. Refuses to touch anything unless `logs_dir` is a `Logs`
+/// directory right inside `flavor_dir`, also once symlinks are resolved.
+fn clean_logs_dir(
+    flavor_dir: &Path,
+    logs_dir: &Path,
+    older_than: Duration,
+    now: SystemTime,
+) -> Result<usize, FilesystemError> {
+    let is_logs_dir = logs_dir
+        .file_name()
+        .is_some_and(|name| name.eq_ignore_ascii_case("Logs"));
+    let resolved = logs_dir.canonicalize()?;
+
+    if !is_logs_dir || resolved.parent() != Some(flavor_dir.canonicalize()?.as_path()) {
+        return Err(FilesystemError::NotLogsDirectory {
+            path: logs_dir.to_path_buf(),
+        });
+    }
+
+    let cutoff = match now.checked_sub(older_than) {
+        Some(cutoff) => cutoff,
+        None => return Ok(0),
+    };
+
+    let mut removed = 0;
+
+    for entry in std::fs::read_dir(&resolved)? {
+        let path = entry?.path();
+        let metadata = std::fs::symlink_metadata(&path)?;
+
+        if !metadata.is_file() {
+            continue;
+        }
+
+        if metadata.modified()? < cutoff {
+            remove_file(&path)?;
+            removed += 1;
+        }
+    }
+
+    log::debug!("removed {} log files from {:?}", removed, resolved);
+
+    Ok(removed)
+}
+
+/// Returns `base` joined with `subpath` if it exists. If not, it could have
+/// been modified by the user, so the last case-insensitive match inside `base`
+/// is used instead. Falls back to `base` joined with `subpath` if nothing
+/// matches.
+fn resolve_case_insensitive_subdir(base: &Path, subpath: &str) -> PathBuf {
+    let default = base.join(subpath);
+
+    if default.exists() {
+        return default;
+    }
+
+    let options = MatchOptions {
+        case_sensitive: false,
+        ..Default::default()
+    };
+
+    // For some reason the case insensitive pattern doesn't work unless we add
+    // an actual pattern symbol, hence the first character of every component
+    // is replaced with a `?`. Everything else is escaped, since directory
+    // names can contain glob symbols like `[`.
+    let subpattern = subpath
+        .split('/')
+        .map(|component| {
+            let mut chars = component.chars();
+
+            match chars.next() {
+                Some(_) => format!("?{}", glob::Pattern::escape(chars.as_str())),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    let pattern = format!(
+        "{}/{}",
+        glob::Pattern::escape(&base.to_string_lossy()),
+        subpattern
+    );
+
+    match glob::glob_with(&pattern, options) {
+        Ok(paths) => paths.filter_map(Result::ok).last().unwrap_or(default),
+        Err(error) => {
+            log::warn!("invalid pattern {:?}: {}", pattern, error);
+
+            default
+        }
+    }
+}
+
+/// Returns the names of the themes in `Config::theme_directory()`.
+///
+/// Only `.yml` and `.yaml` themes which can be loaded are listed, named as set
+/// in the file.
+pub fn list_themes() -> Result<Vec<String>, FilesystemError> {
+    list_themes_in(&Config::theme_directory())
+}
+
+/// Returns the name of the theme in `contents`, if it can be loaded.
+#[cfg(feature = "gui")]
+fn theme_name(contents: &str) -> Option<String> {
+    serde_yaml::from_str::<Theme>(contents)
+        .ok()
+        .map(|theme| theme.name)
+}
+
+/// Returns the name of the theme in `contents`. Without the `gui` feature
+/// themes can't be loaded, so only the name and a palette are checked for.
+#[cfg(not(feature = "gui"))]
+fn theme_name(contents: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct NamedTheme {
+        name: String,
+        #[allow(dead_code)]
+        palette: serde_yaml::Mapping,
+    }
+
+    serde_yaml::from_str::<NamedTheme>(contents)
+        .ok()
+        .map(|theme| theme.name)
+}
+
+fn list_themes_in(theme_dir: &Path) -> Result<Vec<String>, FilesystemError> {
+    let mut names = vec![];
+
+    let entries = std::fs::read_dir(theme_dir).map_err(|source| FilesystemError::Read {
+        path: theme_dir.to_path_buf(),
+        source,
+    })?;
+
+    // Only themes `load_user_themes` can load are listed.
+    for entry in entries {
+        let path = entry?.path();
+
+        if !matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml") | Some("yml")
+        ) {
+            continue;
+        }
+
+        let name = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| theme_name(&contents));
+
+        if let Some(name) = name {
+            names.push(name);
+        }
+    }
+
+    names.sort();
+
+    Ok(names)
+}
+
+/// Returns `true` if a file can be created inside `dir`.
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(".ajour_write_check");
+
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+impl PersistentData for Config {
+    /// Either `ajour.yml` or `ajour.toml`, whichever exists. If both exist, the
+    /// most recently modified one is used.
+    fn relative_path() -> PathBuf {
+        PathBuf::from(ConfigFormat::detect_in(&config_dir_path()).file_name())
+    }
+
+    /// Saves in `config_format` if set, otherwise to `Config::on_disk_path()`.
+    fn save_path(&self) -> Result<PathBuf, FilesystemError> {
+        let path = match self.config_format {
+            Some(format) => config_dir_path().join(format.file_name()),
+            None => Config::on_disk_path(),
+        };
+
+        Ok(path)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+}
+
+/// File format of the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ConfigFormat {
+    #[default]
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Returns the name of the config file in this format.
+    pub const fn file_name(self) -> &'static str {
+        match self {
+            ConfigFormat::Yaml => "ajour.yml",
+            ConfigFormat::Toml => "ajour.toml",
+        }
+    }
+
+    /// Returns the format of the config file inside `dir`. If both exist, the
+    /// most recently modified one wins, and if neither exists it's YAML.
+    fn detect_in(dir: &Path) -> ConfigFormat {
+        let modified = |format: ConfigFormat| {
+            std::fs::metadata(dir.join(format.file_name()))
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        };
+
+        match (modified(ConfigFormat::Yaml), modified(ConfigFormat::Toml)) {
+            (Some(yaml), Some(toml)) if toml > yaml => ConfigFormat::Toml,
+            (None, Some(_)) => ConfigFormat::Toml,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+}
+
+/// A setting changed by one of the `Config` setters, so callers can react to
+/// just that change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChange {
+    ThemeChanged,
+    LanguageChanged,
+    ScaleChanged,
+    DirectoryChanged(Flavor),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SelfUpdateChannel {
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl SelfUpdateChannel {
+    pub const fn all() -> [Self; 3] {
+        [
+            SelfUpdateChannel::Stable,
+            SelfUpdateChannel::Beta,
+            SelfUpdateChannel::Nightly,
+        ]
+    }
+
+    /// Returns `true` if this channel gets prereleases.
+    pub const fn is_prerelease(self) -> bool {
+        !matches!(self, SelfUpdateChannel::Stable)
+    }
+
+    /// Returns `true` if a release is offered on this channel. Stable only
+    /// takes stable releases, while Beta and Nightly take both stable
+    /// releases and prereleases.
+    pub const fn accepts_release(self, is_prerelease: bool) -> bool {
+        !is_prerelease || self.is_prerelease()
+    }
+
+    /// Returns the GitHub API url of the releases feed for this channel.
+    ///
+    /// There's no separate feed of nightly builds, so every channel uses the
+    /// releases of `ajour/ajour` and picks from them with `accepts_release`.
+    pub const fn releases_url(self) -> &'static str {
+        "https://api.github.com/repos/ajour/ajour/releases"
+    }
+
+    /// Returns the path of the releases feed for this channel, relative to
+    /// the GitHub API.
+    pub const fn releases_path(self) -> &'static str {
+        "repos/ajour/ajour/releases"
+    }
+
+    /// Returns the url of the releases feed for this channel, hosted by the
+    /// mirror at `base_url` if one is given.
+    pub fn releases_url_with_override(
+        self,
+        base_url: Option<&str>,
+    ) -> Result<String, DownloadError> {
+        let base_url = match base_url {
+            Some(base_url) => base_url,
+            None => return Ok(self.releases_url().to_owned()),
+        };
+
+        let is_http = base_url
+            .parse::<isahc::http::Uri>()
+            .ok()
+            .filter(|uri| uri.host().is_some())
+            .and_then(|uri| uri.scheme_str().map(|s| s == "http" || s == "https"))
+            .unwrap_or_default();
+
+        if is_http {
+            Ok(format!(
+                "{}/{}",
+                base_url.trim_end_matches('/'),
+                self.releases_path()
+            ))
+        } else {
+            Err(DownloadError::InvalidSelfUpdateUrl {
+                url: base_url.to_owned(),
+            })
+        }
+    }
+
+    /// Returns `true` if `asset_name` is a release asset built for the
+    /// platform Ajour is running on.
+    pub fn matches_current_platform(self, asset_name: &str) -> bool {
+        asset_matches_os(std::env::consts::OS, asset_name)
+    }
+}
+
+/// Returns `name` lowercased and with diacritics removed from latin letters,
+/// so it sorts the same regardless of locale.
+fn sort_key(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => 'a',
+            'č' | 'ç' => 'c',
+            'ď' => 'd',
+            'é' | 'è' | 'ê' | 'ë' | 'ě' => 'e',
+            'ğ' => 'g',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ľ' | 'ĺ' => 'l',
+            'ñ' | 'ň' => 'n',
+            'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'ø' => 'o',
+            'ř' => 'r',
+            'š' | 'ş' => 's',
+            'ť' => 't',
+            'ú' | 'ù' | 'û' | 'ü' | 'ů' => 'u',
+            'ý' => 'y',
+            'ž' => 'z',
+            c => c,
+        })
+        .collect()
+}
+
+/// Returns `true` if `asset_name` follows the naming of the release assets
+/// built for `os`, as found in `std::env::consts::OS`.
+fn asset_matches_os(os: &str, asset_name: &str) -> bool {
+    let name = asset_name.to_lowercase();
+
+    match os {
+        "windows" => name.ends_with(".exe") || name.ends_with(".msi"),
+        "linux" => name.ends_with(".appimage"),
+        "macos" => name.ends_with("-macos.tar.gz") || name.ends_with(".dmg"),
+        _ => false,
+    }
+}
+
+impl Default for SelfUpdateChannel {
+    fn default() -> Self {
+        SelfUpdateChannel::Stable
+    }
+}
+
+impl Display for SelfUpdateChannel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SelfUpdateChannel::Stable => "Stable",
+            SelfUpdateChannel::Beta => "Beta",
+            SelfUpdateChannel::Nightly => "Nightly",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Hash, PartialOrd, Ord)]
+pub enum Language {
+    Czech,
+    Norwegian,
+    English,
+    Danish,
+    German,
+    French,
+    Hungarian,
+    Portuguese,
+    Russian,
+    Slovak,
+    Swedish,
+    Spanish,
+    Turkish,
+    Ukrainian,
+    Italian,
+    Polish,
+    Korean,
+    SimplifiedChinese,
+    TraditionalChinese,
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Language::Czech => "Čeština",
+                Language::Danish => "Dansk",
+                Language::English => "English",
+                Language::French => "Français",
+                Language::German => "Deutsch",
+                Language::Hungarian => "Magyar",
+                Language::Norwegian => "Norsk Bokmål",
+                Language::Portuguese => "Português",
+                Language::Russian => "Pусский",
+                Language::Slovak => "Slovenčina",
+                Language::Spanish => "Español",
+                Language::Swedish => "Svenska",
+                Language::Turkish => "Türkçe",
+                Language::Ukrainian => "Yкраїнська",
+                Language::Italian => "Italiano",
+                Language::Polish => "Polski",
+                Language::Korean => "한국어",
+                Language::SimplifiedChinese => "简体中文",
+                Language::TraditionalChinese => "繁體中文",
+            }
+        )
+    }
+}
+
+impl Language {
+    // Alphabetically sorted based on their local name (@see `impl Display`).
+    // Lists shown to users should use `Language::all_sorted()`, which doesn't
+    // rely on this order.
+    pub const ALL: [Language; 19] = [
+        Language::Czech,
+        Language::Danish,
+        Language::German,
+        Language::English,
+        Language::Spanish,
+        Language::French,
+        Language::Italian,
+        Language::Hungarian,
+        Language::Norwegian,
+        Language::Polish,
+        Language::Portuguese,
+        Language::Russian,
+        Language::Slovak,
+        Language::Swedish,
+        Language::Turkish,
+        Language::Ukrainian,
+        Language::SimplifiedChinese,
+        Language::TraditionalChinese,
+        Language::Korean,
+    ];
+
+    pub const fn language_code(self) -> &'static str {
+        match self {
+            Language::Czech => "cs_CZ",
+            Language::English => "en_US",
+            Language::Danish => "da_DK",
+            Language::German => "de_DE",
+            Language::French => "fr_FR",
+            Language::Russian => "ru_RU",
+            Language::Swedish => "se_SE",
+            Language::Spanish => "es_ES",
+            Language::Hungarian => "hu_HU",
+            Language::Norwegian => "nb_NO",
+            Language::Slovak => "sk_SK",
+            Language::Turkish => "tr_TR",
+            Language::Portuguese => "pt_PT",
+            Language::Ukrainian => "uk_UA",
+            Language::Italian => "it_IT",
+            Language::Polish => "pl_PL",
+            Language::Korean => "ko_KR",
+            Language::SimplifiedChinese => "zh_CN",
+            Language::TraditionalChinese => "zh_TW",
+        }
+    }
+
+    /// Returns every language, sorted by their local name (@see `impl Display`)
+    /// ignoring case and diacritics.
+    pub fn all_sorted() -> Vec<Language> {
+        let mut all = Language::ALL.to_vec();
+        all.sort_by_cached_key(|language| sort_key(&language.to_string()));
+
+        all
+    }
+
+    /// Returns the direction text in this language is written in.
+    pub const fn text_direction(self) -> TextDirection {
+        match self {
+            Language::Czech
+            | Language::English
+            | Language::Danish
+            | Language::German
+            | Language::French
+            | Language::Russian
+            | Language::Swedish
+            | Language::Spanish
+            | Language::Hungarian
+            | Language::Norwegian
+            | Language::Slovak
+            | Language::Turkish
+            | Language::Portuguese
+            | Language::Ukrainian
+            | Language::Italian
+            | Language::Polish
+            | Language::Korean
+            | Language::SimplifiedChinese
+            | Language::TraditionalChinese => TextDirection::LeftToRight,
+        }
+    }
+
+    /// Returns `true` if this language is written right-to-left.
+    pub const fn is_rtl(self) -> bool {
+        matches!(self.text_direction(), TextDirection::RightToLeft)
+    }
+
+    /// Returns the `Language` matching a locale code such as `fr_FR`, `fr-FR`
+    /// or `fr_FR.UTF-8`. A bare language like `fr` matches on the prefix of
+    /// the code. Matching is case-insensitive.
+    pub fn from_language_code(code: &str) -> Option<Language> {
+        // Strip encoding and modifier, eg. `de_DE.UTF-8@euro`.
+        let code = code.split(['.', '@']).next()?.trim();
+        let code = code.replace('-', "_");
+
+        if code.is_empty() {
+            return None;
+        }
+
+        if let Some(language) = Language::ALL
+            .iter()
+            .find(|l| l.language_code().eq_ignore_ascii_case(&code))
+        {
+            return Some(*language);
+        }
+
+        let prefix = code.split('_').next()?;
+
+        Language::ALL.iter().copied().find(|l| {
+            l.language_code()
+                .split('_')
+                .next()
+                .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+        })
+    }
+
+    /// Returns the `Language` of the system locale, read from `LC_ALL`,
+    /// `LC_MESSAGES` and `LANG` in that order. Falls back to `English`.
+    pub fn detect_system() -> Language {
+        let codes = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok());
+
+        detect_from_codes(codes)
+    }
+}
+
+/// Direction in which text of a `Language` is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+fn detect_from_codes<I: IntoIterator<Item = String>>(codes: I) -> Language {
+    codes
+        .into_iter()
+        .find_map(|code| Language::from_language_code(&code))
+        .unwrap_or_default()
+}
+
+impl Default for Language {
+    fn default() -> Language {
+        Language::English
+    }
+}
+
+/// Returns a Config.
+///
+/// This functions handles the initialization of a Config.
+pub async fn load_config() -> Result<Config, FilesystemError> {
+    log::debug!("loading config");
+
+    let mut config: Config = Config::load_or_default_with_backup()?;
+
+    config.migrate_schema();
+
+    if let Some(scale) = config.scale {
+        let normalized = config.normalized_scale();
+
+        if (scale - normalized).abs() > f64::EPSILON {
+            log::warn!(
+                "scale {} is out of range, using {} instead",
+                scale,
+                normalized
+            );
+        }
+    }
+
+    Ok(config)
+}
+
+const fn default_true() -> bool {
     true
 }
+
+const fn current_schema() -> u32 {
+    CONFIG_SCHEMA_VERSION
+}
+
+const fn unversioned_schema() -> u32 {
+    UNVERSIONED_SCHEMA
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_theme(path: &Path, name: &str) {
+        let palette = "
+  base:
+    background: '#484793'
+    foreground: '#484793'
+  normal:
+    primary: '#484793'
+    secondary: '#484793'
+    surface: '#484793'
+    error: '#484793'
+  bright:
+    primary: '#484793'
+    secondary: '#484793'
+    surface: '#484793'
+    error: '#484793'
+";
+        std::fs::write(path, format!("name: {}\npalette:{}", name, palette)).unwrap();
+    }
+
+    #[test]
+    fn test_reset_columns() {
+        let mut mangled = ColumnConfig::default_v3();
+        mangled.move_column(ColumnSection::MyAddons, "title", usize::MAX);
+        mangled.move_column(ColumnSection::Catalog, "addon", usize::MAX);
+
+        let mut config = Config {
+            column_config: mangled.clone(),
+            ..Default::default()
+        };
+        config.reset_columns_section(ColumnSection::MyAddons);
+
+        assert_eq!(
+            config.column_config.shared_my_addons_columns(),
+            ColumnConfig::default_v3().shared_my_addons_columns()
+        );
+        assert_eq!(
+            config.column_config.catalog_columns(),
+            mangled.catalog_columns()
+        );
+
+        config.reset_columns();
+        assert_eq!(config.column_config, ColumnConfig::default_v3());
+
+        let mut config = Config {
+            column_config: ColumnConfig::V1 {
+                local_version_width: 0,
+                remote_version_width: 0,
+                status_width: 0,
+            },
+            ..Default::default()
+        };
+        config.reset_columns();
+        assert_eq!(config.column_config, ColumnConfig::default_v3());
+    }
+
+    #[test]
+    fn test_save_read_only() {
+        crate::fs::with_temp_config_dir(|dir| {
+            let path = dir.join("ajour.yml");
+            let contents = "read_only: true\ntheme: Nord\n";
+            std::fs::write(&path, contents).unwrap();
+
+            let mut config = Config::load().unwrap();
+            assert!(config.read_only);
+
+            config.theme = Some("Dracula".to_string());
+            assert!(matches!(
+                config.save(),
+                Err(FilesystemError::ReadOnly { path: error_path }) if error_path == path
+            ));
+
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+        });
+    }
+
+    #[test]
+    fn test_save_returns_path() {
+        crate::fs::with_temp_config_dir(|dir| {
+            let config = Config {
+                theme: Some("Nord".to_string()),
+                backup_wtf: true,
+                ..Default::default()
+            };
+
+            let path = config.save().unwrap();
+            assert_eq!(path, dir.join("ajour.yml"));
+
+            let saved: Config = deserialize(&path, &read_to_string(&path).unwrap()).unwrap();
+            assert_eq!(saved, config);
+        });
+    }
+
+    #[test]
+    fn test_on_disk_path() {
+        crate::fs::with_temp_config_dir(|dir| {
+            let path = Config::on_disk_path();
+
+            assert!(path.ends_with("ajour.yml"));
+            assert_eq!(path.parent(), Some(dir));
+            assert!(!path.exists());
+
+            // An existing TOML config is used.
+            std::fs::write(dir.join("ajour.toml"), "").unwrap();
+            assert_eq!(Config::on_disk_path(), dir.join("ajour.toml"));
+        });
+    }
+
+    #[test]
+    fn test_detect_config_format() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(ConfigFormat::detect_in(dir.path()), ConfigFormat::Yaml);
+
+        std::fs::write(dir.path().join("ajour.toml"), "").unwrap();
+        assert_eq!(ConfigFormat::detect_in(dir.path()), ConfigFormat::Toml);
+    }
+
+    #[test]
+    fn test_merge_directories() {
+        let mut base = Config::default();
+        base.wow
+            .directories
+            .insert(Flavor::Retail, PathBuf::from("/base/_retail_"));
+        base.wow
+            .directories
+            .insert(Flavor::Classic, PathBuf::from("/base/_classic_"));
+
+        let mut overlay = Config::default();
+        overlay
+            .wow
+            .directories
+            .insert(Flavor::Classic, PathBuf::from("/local/_classic_"));
+        overlay
+            .wow
+            .directories
+            .insert(Flavor::ClassicPTR, PathBuf::from("/local/_classic_ptr_"));
+
+        base.merge(overlay);
+
+        assert_eq!(base.wow.directories.len(), 3);
+        assert_eq!(
+            base.wow.directories[&Flavor::Retail],
+            PathBuf::from("/base/_retail_")
+        );
+        assert_eq!(
+            base.wow.directories[&Flavor::Classic],
+            PathBuf::from("/local/_classic_")
+        );
+        assert_eq!(
+            base.wow.directories[&Flavor::ClassicPTR],
+            PathBuf::from("/local/_classic_ptr_")
+        );
+    }
+
+    #[test]
+    fn test_merge_precedence() {
+        let mut base = Config {
+            theme: Some("Nord".to_string()),
+            scale: Some(1.5),
+            backup_addons: true,
+            language: Language::German,
+            telemetry_enabled: true,
+            alternating_row_colors: false,
+            ..Default::default()
+        };
+
+        let overlay = Config {
+            theme: Some("Dracula".to_string()),
+            hide_ignored_addons: true,
+            ..Default::default()
+        };
+
+        base.merge(overlay);
+
+        // Set in the overlay.
+        assert_eq!(base.theme.as_deref(), Some("Dracula"));
+        assert!(base.hide_ignored_addons);
+
+        // Unset or default in the overlay, so the base is kept.
+        assert_eq!(base.scale, Some(1.5));
+        assert!(base.backup_addons);
+        assert_eq!(base.language, Language::German);
+        assert!(!base.alternating_row_colors);
+
+        // A base config can't opt in to telemetry.
+        assert!(!base.telemetry_enabled);
+    }
+
+    #[test]
+    fn test_default_matches_serde() {
+        let config = Config::default();
+        assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION);
+        assert!(config.alternating_row_colors);
+        assert!(config.follow_symlinks);
+
+        let mut parsed: Config = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(parsed.schema_version, UNVERSIONED_SCHEMA);
+        parsed.schema_version = CONFIG_SCHEMA_VERSION;
+        assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn test_backup_compression_level_serde() {
+        let config: Config = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config.backup_compression_level, None);
+
+        let config: Config = serde_yaml::from_str("backup_compression_level: 9").unwrap();
+        assert_eq!(config.backup_compression_level, Some(9));
+    }
+
+    #[test]
+    fn test_migrate_schema() {
+        fn enable_hide_ignored(config: &mut Config) {
+            config.hide_ignored_addons = true;
+        }
+        fn bump_scale(config: &mut Config) {
+            config.scale = Some(config.scale.unwrap_or(1.0) + 0.5);
+        }
+        let migrations: &[SchemaMigration] = &[(2, enable_hide_ignored), (3, bump_scale)];
+
+        // Files without a version run every migration.
+        let mut config: Config = serde_yaml::from_str("theme: Nord").unwrap();
+        assert_eq!(config.schema_version, UNVERSIONED_SCHEMA);
+        config.migrate_schema_with(3, migrations);
+        assert_eq!(config.schema_version, 3);
+        assert!(config.hide_ignored_addons);
+        assert_eq!(config.scale, Some(1.5));
+
+        // Older configs run the migrations they haven't seen yet.
+        let mut config: Config = serde_yaml::from_str("schema_version: 2").unwrap();
+        config.migrate_schema_with(3, migrations);
+        assert_eq!(config.schema_version, 3);
+        assert!(!config.hide_ignored_addons);
+        assert_eq!(config.scale, Some(1.5));
+
+        let mut config: Config = serde_yaml::from_str("schema_version: 1").unwrap();
+        config.migrate_schema_with(3, migrations);
+        assert_eq!(config.schema_version, 3);
+        assert!(config.hide_ignored_addons);
+        assert_eq!(config.scale, Some(1.5));
+
+        // Newer configs are loaded without migrating, keeping their version.
+        let mut config: Config =
+            serde_yaml::from_str("schema_version: 9\ntheme: Nord\nfuture_setting: true").unwrap();
+        let unchanged = config.clone();
+        config.migrate_schema_with(3, migrations);
+        assert_eq!(config, unchanged);
+        assert_eq!(config.theme.as_deref(), Some("Nord"));
+    }
+
+    #[test]
+    fn test_migrate_unversioned_schema() {
+        let yaml = "
+column_config:
+  V2:
+    columns: []
+weak_auras_account:
+  Retail: FIRST
+";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.migrate_schema();
+
+        assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION);
+        assert!(matches!(config.column_config, ColumnConfig::V3 { .. }));
+        assert!(config.weak_auras_account.is_empty());
+        assert_eq!(
+            config.weak_auras_accounts.get(&Flavor::Retail),
+            Some(&vec!["FIRST".to_owned()])
+        );
+
+        // Configs on the current schema aren't migrated again.
+        let mut config: Config = serde_yaml::from_str(&format!(
+            "schema_version: {}\n{}",
+            CONFIG_SCHEMA_VERSION, yaml
+        ))
+        .unwrap();
+        config.migrate_schema();
+        assert!(matches!(config.column_config, ColumnConfig::V2 { .. }));
+    }
+
+    #[test]
+    fn test_effective_max_concurrent_downloads() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.effective_max_concurrent_downloads(),
+            DEFAULT_MAX_CONCURRENT_DOWNLOADS
+        );
+
+        config.max_concurrent_downloads = Some(0);
+        assert_eq!(config.effective_max_concurrent_downloads(), 1);
+
+        config.max_concurrent_downloads = Some(12);
+        assert_eq!(config.effective_max_concurrent_downloads(), 12);
+        assert_eq!(config.download_limiter().limit(), 12);
+    }
+
+    #[test]
+    fn test_effective_catalog_source() {
+        let mut config = Config::default();
+        assert_eq!(config.effective_catalog_source(), catalog::Source::Curse);
+
+        config.catalog_source = Some(catalog::Source::Tukui);
+        assert_eq!(config.effective_catalog_source(), catalog::Source::Tukui);
+
+        // A source which has been removed deserializes to `Other`.
+        config.catalog_source = serde_yaml::from_str("Removed").unwrap();
+        assert_eq!(config.catalog_source, Some(catalog::Source::Other));
+        assert_eq!(config.effective_catalog_source(), catalog::Source::Curse);
+    }
+
+    #[test]
+    fn test_window_bounds() {
+        let mut config = Config {
+            window_size: Some((900, 620)),
+            ..Default::default()
+        };
+        assert_eq!(config.window_bounds(), None);
+
+        config.window_position = Some((-1200, 40));
+        assert_eq!(config.window_bounds(), Some((-1200, 40, 900, 620)));
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let deserialized: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(deserialized.window_position, Some((-1200, 40)));
+    }
+
+    #[test]
+    fn test_set_window_state() {
+        let mut config = Config::default();
+
+        config.set_window_state((900, 620), Some((100, 50)), false);
+        assert_eq!(config.window_bounds(), Some((100, 50, 900, 620)));
+        assert!(!config.window_maximized);
+
+        // The restore bounds are kept while maximized.
+        config.set_window_state((2560, 1440), Some((0, 0)), true);
+        assert_eq!(config.window_bounds(), Some((100, 50, 900, 620)));
+        assert!(config.window_maximized);
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let deserialized: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(deserialized, config);
+
+        // Older configs aren't maximized.
+        let deserialized: Config = serde_yaml::from_str("window_size: [900, 620]").unwrap();
+        assert!(!deserialized.window_maximized);
+    }
+
+    #[test]
+    fn test_clamp_window_to() {
+        let displays = [(-1920, 0, 1920, 1080), (0, 0, 2560, 1440)];
+
+        let mut config = Config {
+            window_size: Some((900, 620)),
+            window_position: Some((-1200, 40)),
+            ..Default::default()
+        };
+
+        // On the secondary display to the left.
+        config.clamp_window_to(&displays);
+        assert_eq!(config.window_position, Some((-1200, 40)));
+
+        // Mostly off-screen, but enough of it is still visible.
+        config.window_position = Some((2500, 1380));
+        config.clamp_window_to(&displays);
+        assert_eq!(config.window_position, Some((2500, 1380)));
+
+        // No displays known, so nothing is changed.
+        config.window_position = Some((10_000, 10_000));
+        config.clamp_window_to(&[]);
+        assert_eq!(config.window_position, Some((10_000, 10_000)));
+
+        // The display it was on is gone.
+        config.clamp_window_to(&displays);
+        assert_eq!(config.window_position, None);
+
+        config.window_position = Some((2540, 100));
+        config.clamp_window_to(&displays);
+        assert_eq!(config.window_position, None);
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let contents = "backup_addon: true\nbackup_wtf: true\nwow:\n  flavour: Classic\n";
+
+        let (config, unknown_keys) = parse_strict(serde_yaml::from_str(contents).unwrap()).unwrap();
+
+        assert!(config.backup_wtf);
+        assert!(!config.backup_addons);
+        assert_eq!(
+            unknown_keys,
+            vec!["backup_addon".to_string(), "wow.flavour".to_string()]
+        );
+
+        let (_, unknown_keys) =
+            parse_strict(serde_yaml::to_value(Config::default()).unwrap()).unwrap();
+
+        assert!(unknown_keys.is_empty());
+    }
+
+    #[test]
+    fn test_validate_directories() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        let classic = root.path().join("_classic_");
+        create_dir_all(retail.join("Interface/AddOns")).unwrap();
+        create_dir_all(&classic).unwrap();
+
+        let mut config = Config::default();
+        config.wow.directories.insert(Flavor::Retail, retail);
+        config.wow.directories.insert(Flavor::Classic, classic);
+        config
+            .wow
+            .directories
+            .insert(Flavor::ClassicPTR, root.path().join("_classic_ptr_"));
+
+        let issues = config.validate_directories();
+
+        assert_eq!(
+            issues,
+            vec![
+                DirectoryIssue::MissingAddonDir {
+                    flavor: Flavor::Classic
+                },
+                DirectoryIssue::MissingRoot {
+                    flavor: Flavor::ClassicPTR
+                },
+            ]
+        );
+
+        // Validation must not create the missing directories.
+        assert!(!root.path().join("_classic_/Interface").exists());
+    }
+
+    #[test]
+    fn test_known_flavors() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        let classic = root.path().join("_classic_");
+        create_dir_all(retail.join("Interface/AddOns")).unwrap();
+
+        let mut config = Config::default();
+        config.wow.directories.insert(Flavor::Retail, retail);
+        config
+            .wow
+            .directories
+            .insert(Flavor::Classic, classic.clone());
+
+        assert_eq!(config.known_flavors(), vec![Flavor::Retail]);
+        assert!(!classic.exists());
+    }
+
+    #[test]
+    fn test_flavor_order_partial() {
+        let mut config = Config::default();
+        for flavor in &[Flavor::Retail, Flavor::ClassicEra, Flavor::Classic] {
+            config
+                .wow
+                .directories
+                .insert(*flavor, PathBuf::from(flavor.folder_name()));
+        }
+        let flavors = |config: &Config| -> Vec<Flavor> {
+            config
+                .sorted_directories()
+                .into_iter()
+                .map(|(flavor, _)| flavor)
+                .collect()
+        };
+
+        assert_eq!(
+            flavors(&config),
+            vec![Flavor::Retail, Flavor::Classic, Flavor::ClassicEra]
+        );
+
+        // Unlisted flavors follow in the default order, duplicates only
+        // count once.
+        config.flavor_order = vec![Flavor::ClassicEra, Flavor::ClassicEra];
+        assert_eq!(
+            flavors(&config),
+            vec![Flavor::ClassicEra, Flavor::Retail, Flavor::Classic]
+        );
+    }
+
+    #[test]
+    fn test_flavor_order_unconfigured_flavor() {
+        let yaml = "flavor_order:\n  - RetailPTR\n  - NotAFlavor\n  - Classic\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.flavor_order,
+            vec![Flavor::RetailPTR, Flavor::Classic]
+        );
+
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, PathBuf::from("_retail_"));
+        config
+            .wow
+            .directories
+            .insert(Flavor::Classic, PathBuf::from("_classic_"));
+
+        let flavors: Vec<_> = config
+            .sorted_directories()
+            .into_iter()
+            .map(|(flavor, _)| flavor)
+            .collect();
+        assert_eq!(flavors, vec![Flavor::Classic, Flavor::Retail]);
+    }
+
+    #[test]
+    fn test_addon_directory_exists() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        let classic = root.path().join("_classic_");
+        create_dir_all(retail.join("interface/addons")).unwrap();
+        create_dir_all(&classic).unwrap();
+
+        let mut config = Config::default();
+        config.wow.directories.insert(Flavor::Retail, retail);
+        config
+            .wow
+            .directories
+            .insert(Flavor::Classic, classic.clone());
+
+        assert!(config.addon_directory_exists(&Flavor::Retail));
+        assert!(!config.addon_directory_exists(&Flavor::Classic));
+        assert!(!config.addon_directory_exists(&Flavor::ClassicEra));
+        assert!(!classic.join("Interface").exists());
+    }
+
+    #[test]
+    fn test_resolve_and_ensure_addon_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        let classic = root.path().join("_classic_");
+        let missing = root.path().join("_classic_era_");
+        create_dir_all(retail.join("interface/addons")).unwrap();
+        create_dir_all(&classic).unwrap();
+
+        let mut config = Config::default();
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, retail.clone());
+        config
+            .wow
+            .directories
+            .insert(Flavor::Classic, classic.clone());
+        config
+            .wow
+            .directories
+            .insert(Flavor::ClassicEra, missing.clone());
+
+        assert_eq!(
+            config.resolve_addon_directory(&Flavor::Retail),
+            Some(retail.join("interface/addons"))
+        );
+        assert_eq!(
+            config.resolve_addon_directory(&Flavor::Classic),
+            Some(classic.join("Interface/AddOns"))
+        );
+        assert_eq!(config.resolve_addon_directory(&Flavor::RetailPTR), None);
+        assert!(!classic.join("Interface").exists());
+
+        assert_eq!(
+            config.ensure_addon_directory(&Flavor::Retail).unwrap(),
+            retail.join("interface/addons")
+        );
+        assert_eq!(
+            config.ensure_addon_directory(&Flavor::Classic).unwrap(),
+            classic.join("Interface/AddOns")
+        );
+        assert!(classic.join("Interface/AddOns").is_dir());
+
+        assert!(matches!(
+            config.ensure_addon_directory(&Flavor::ClassicEra),
+            Err(FilesystemError::FileDoesntExist { .. })
+        ));
+        assert!(!missing.exists());
+        assert!(matches!(
+            config.ensure_addon_directory(&Flavor::RetailPTR),
+            Err(FilesystemError::NoFlavorDirectory {
+                flavor: Flavor::RetailPTR
+            })
+        ));
+    }
+
+    #[test]
+    fn test_validate_directories_not_writable() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        let addon_dir = retail.join("Interface/AddOns");
+        create_dir_all(&addon_dir).unwrap();
+
+        let original = std::fs::metadata(&addon_dir).unwrap().permissions();
+        let mut permissions = original.clone();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&addon_dir, permissions).unwrap();
+
+        let mut config = Config::default();
+        config.wow.directories.insert(Flavor::Retail, retail);
+
+        let issues = config.validate_directories();
+
+        // Privileged users can write regardless of permissions.
+        let writable = is_writable(&addon_dir);
+
+        std::fs::set_permissions(&addon_dir, original).unwrap();
+
+        if writable {
+            return;
+        }
+
+        assert_eq!(
+            issues,
+            vec![DirectoryIssue::NotWritable {
+                flavor: Flavor::Retail,
+                path: addon_dir,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_from_language_code() {
+        assert_eq!(
+            Language::from_language_code("fr_FR"),
+            Some(Language::French)
+        );
+        assert_eq!(
+            Language::from_language_code("fr-fr.UTF-8"),
+            Some(Language::French)
+        );
+        assert_eq!(
+            Language::from_language_code("pt"),
+            Some(Language::Portuguese)
+        );
+        assert_eq!(
+            Language::from_language_code("PT_BR"),
+            Some(Language::Portuguese)
+        );
+        assert_eq!(Language::from_language_code("xx_XX"), None);
+        assert_eq!(Language::from_language_code(""), None);
+
+        for language in Language::ALL.iter() {
+            assert_eq!(
+                Language::from_language_code(language.language_code()),
+                Some(*language)
+            );
+        }
+    }
+
+    #[test]
+    fn test_all_languages() {
+        // Fails to compile when a variant is added without updating this match.
+        let index = |language: Language| match language {
+            Language::Czech => 0,
+            Language::Norwegian => 1,
+            Language::English => 2,
+            Language::Danish => 3,
+            Language::German => 4,
+            Language::French => 5,
+            Language::Hungarian => 6,
+            Language::Portuguese => 7,
+            Language::Russian => 8,
+            Language::Slovak => 9,
+            Language::Swedish => 10,
+            Language::Spanish => 11,
+            Language::Turkish => 12,
+            Language::Ukrainian => 13,
+            Language::Italian => 14,
+            Language::Polish => 15,
+            Language::Korean => 16,
+            Language::SimplifiedChinese => 17,
+            Language::TraditionalChinese => 18,
+        };
+
+        let mut indices: Vec<usize> = Language::ALL.iter().map(|l| index(*l)).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        assert_eq!(indices, (0..Language::ALL.len()).collect::<Vec<_>>());
+
+        assert_eq!(Language::default(), Language::English);
+        assert_eq!(
+            Language::from_language_code("zh_TW"),
+            Some(Language::TraditionalChinese)
+        );
+        assert_eq!(
+            Language::from_language_code("zh_CN"),
+            Some(Language::SimplifiedChinese)
+        );
+    }
+
+    #[test]
+    fn test_language_all_sorted() {
+        let sorted = Language::all_sorted();
+        assert_eq!(sorted.len(), Language::ALL.len());
+
+        let keys: Vec<_> = sorted.iter().map(|l| sort_key(&l.to_string())).collect();
+        assert!(keys.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        // Diacritics don't push a language to the end
+        assert_eq!(sorted[0], Language::Czech);
+    }
+
+    #[test]
+    fn test_text_direction() {
+        for language in Language::ALL.iter() {
+            assert_eq!(language.text_direction(), TextDirection::LeftToRight);
+            assert!(!language.is_rtl());
+        }
+    }
+
+    #[test]
+    fn test_language_serde() {
+        let config: Config = serde_yaml::from_str("language: French").unwrap();
+        assert_eq!(config.language, Language::French);
+
+        let config: Config = serde_yaml::from_str("language: Korean").unwrap();
+        assert_eq!(config.language, Language::Korean);
+    }
+
+    #[test]
+    fn test_cycle_scale() {
+        let mut config = Config {
+            scale: Some(1.3),
+            ..Default::default()
+        };
+
+        // Snaps to 1.25 first, then advances.
+        assert_eq!(config.cycle_scale(), 1.5);
+        assert_eq!(config.scale, Some(1.5));
+        assert_eq!(config.cycle_scale(), 2.0);
+        assert_eq!(config.cycle_scale(), 1.0);
+        assert_eq!(config.cycle_scale(), 1.25);
+
+        let mut config = Config::default();
+        assert_eq!(config.cycle_scale(), 1.25);
+
+        let presets = Config::scale_presets();
+        assert!(presets.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(presets.iter().all(|p| (MIN_SCALE..=MAX_SCALE).contains(p)));
+    }
+
+    #[test]
+    fn test_normalized_scale() {
+        let mut config = Config::default();
+        assert_eq!(config.normalized_scale(), 1.0);
+
+        config.scale = Some(0.0);
+        assert_eq!(config.normalized_scale(), MIN_SCALE);
+
+        config.scale = Some(1.5);
+        assert_eq!(config.normalized_scale(), 1.5);
+
+        config.scale = Some(100.0);
+        assert_eq!(config.normalized_scale(), MAX_SCALE);
+
+        // The stored value is only changed when clamping explicitly.
+        assert_eq!(config.scale, Some(100.0));
+        config.clamp_scale();
+        assert_eq!(config.scale, Some(MAX_SCALE));
+
+        config.scale = None;
+        config.clamp_scale();
+        assert_eq!(config.scale, None);
+    }
+
+    #[test]
+    fn test_backup_directory_or_default() {
+        let documents_dir = PathBuf::from("/home/user/Documents");
+
+        let mut config = Config::default();
+        assert_eq!(
+            config.backup_directory_or(Some(documents_dir.clone())),
+            documents_dir.join("Ajour Backups")
+        );
+
+        config.backup_directory = Some(PathBuf::from("/backups"));
+        assert_eq!(
+            config.backup_directory_or(Some(documents_dir)),
+            PathBuf::from("/backups")
+        );
+    }
+
+    #[test]
+    fn test_directories_expand_env_vars() {
+        let root = tempfile::tempdir().unwrap();
+        std::env::set_var("AJOUR_TEST_CONFIG_WOW_HOME", root.path());
+
+        let mut config = Config::default();
+        config.wow.directories.insert(
+            Flavor::Retail,
+            PathBuf::from("$AJOUR_TEST_CONFIG_WOW_HOME/_retail_"),
+        );
+
+        let retail = root.path().join("_retail_");
+        assert_eq!(
+            config.get_root_directory_for_flavor(&Flavor::Retail),
+            Some(root.path().to_path_buf())
+        );
+        assert_eq!(
+            config.get_download_directory_for_flavor(Flavor::Retail),
+            Some(retail.clone())
+        );
+        assert_eq!(
+            config.get_wtf_directory_for_flavor(&Flavor::Retail),
+            Some(retail.join("WTF"))
+        );
+    }
+
+    #[cfg(feature = "gui")]
+    #[test]
+    fn test_bundled_theme_names() {
+        let mut names: Vec<_> = Theme::bundled()
+            .into_iter()
+            .map(|theme| theme.name)
+            .collect();
+        names.sort();
+
+        assert_eq!(names, bundled_theme_names());
+    }
+
+    #[test]
+    fn test_theme_or_default() {
+        let theme_dir = tempfile::tempdir().unwrap();
+        write_theme(&theme_dir.path().join("custom.yml"), "My Theme");
+
+        assert!(bundled_theme_names().contains(&DEFAULT_THEME));
+
+        let mut config = Config::default();
+        assert_eq!(config.theme_or_default_in(theme_dir.path()), DEFAULT_THEME);
+
+        config.theme = Some("Nord".to_owned());
+        assert_eq!(config.theme_or_default_in(theme_dir.path()), "Nord");
+
+        config.theme = Some("My Theme".to_owned());
+        assert_eq!(config.theme_or_default_in(theme_dir.path()), "My Theme");
+
+        config.theme = Some("Deleted Theme".to_owned());
+        assert_eq!(config.theme_or_default_in(theme_dir.path()), DEFAULT_THEME);
+    }
+
+    #[test]
+    fn test_startup_flags_serde() {
+        let config: Config = serde_yaml::from_str("{}").unwrap();
+        assert!(!config.start_minimized);
+        assert!(!config.minimize_to_tray);
+
+        let config = Config {
+            start_minimized: true,
+            minimize_to_tray: true,
+            ..Default::default()
+        };
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let parsed: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert!(parsed.start_minimized);
+        assert!(parsed.minimize_to_tray);
+    }
+
+    #[test]
+    fn test_profile_round_trip() {
+        let mut config = Config {
+            theme: Some("Nord".to_owned()),
+            language: Language::German,
+            backup_directory: Some(PathBuf::from("/home/user/backups")),
+            start_minimized: true,
+            minimize_to_tray: true,
+            telemetry_enabled: true,
+            ..Default::default()
+        };
+        config.column_config.migrate_to_v3();
+        if let ColumnConfig::V3 {
+            my_addons_columns, ..
+        } = &mut config.column_config
+        {
+            my_addons_columns[1].width = Some(42);
+        }
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, PathBuf::from("/home/user/wow/_retail_"));
+
+        let json = config.export_profile().unwrap();
+        assert!(!json.contains("/home/user"));
+
+        let mut fresh = Config::default();
+        fresh
+            .wow
+            .directories
+            .insert(Flavor::Classic, PathBuf::from("/other/wow/_classic_"));
+        fresh.import_profile(&json).unwrap();
+
+        assert_eq!(fresh.theme, config.theme);
+        assert_eq!(fresh.language, config.language);
+        assert_eq!(fresh.column_config, config.column_config);
+        assert!(fresh.start_minimized);
+        assert!(fresh.minimize_to_tray);
+        assert!(!fresh.telemetry_enabled);
+        assert_eq!(
+            fresh.wow.directories.get(&Flavor::Classic),
+            Some(&PathBuf::from("/other/wow/_classic_"))
+        );
+        assert_eq!(fresh.wow.directories.get(&Flavor::Retail), None);
+        assert_eq!(fresh.backup_directory, None);
+    }
+
+    #[test]
+    fn test_backup_targets() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        let config_dir = root.path().join("ajour");
+        create_dir_all(retail.join("Interface/AddOns")).unwrap();
+        create_dir_all(retail.join("WTF")).unwrap();
+        create_dir_all(&config_dir).unwrap();
+
+        let mut config = Config::default();
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, retail.clone());
+        assert!(config
+            .backup_targets_in(&Flavor::Retail, &config_dir)
+            .is_empty());
+
+        config.backup_addons = true;
+        assert_eq!(
+            config.backup_targets_in(&Flavor::Retail, &config_dir),
+            vec![(BackupKind::Addons, retail.join("Interface"))]
+        );
+
+        config.backup_wtf = true;
+        config.backup_config = true;
+        assert_eq!(
+            config.backup_targets_in(&Flavor::Retail, &config_dir),
+            vec![
+                (BackupKind::Addons, retail.join("Interface")),
+                (BackupKind::Wtf, retail.join("WTF")),
+                (BackupKind::Config, config_dir.clone()),
+            ]
+        );
+
+        config.backup_addons = false;
+        assert_eq!(
+            config.backup_targets_in(&Flavor::Retail, &config_dir),
+            vec![
+                (BackupKind::Wtf, retail.join("WTF")),
+                (BackupKind::Config, config_dir.clone()),
+            ]
+        );
+
+        // Missing directories are left out
+        assert_eq!(
+            config.backup_targets_in(&Flavor::Classic, &config_dir),
+            vec![(BackupKind::Config, config_dir.clone())]
+        );
+        std::fs::remove_dir(retail.join("WTF")).unwrap();
+        config.backup_config = false;
+        assert!(config
+            .backup_targets_in(&Flavor::Retail, &config_dir)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_addon_sort() {
+        let mut config = Config::default();
+        assert_eq!(config.sort_state(), SortState::default());
+        assert_eq!(config.sort_state().column_key, "status");
+        assert_eq!(config.sort_state().direction, SortDirection::Desc);
+
+        config.set_sort("title".to_owned(), SortDirection::Asc);
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let config: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            config.sort_state(),
+            SortState {
+                column_key: "title".to_owned(),
+                direction: SortDirection::Asc,
+            }
+        );
+
+        let config: Config = serde_yaml::from_str("theme: Dark").unwrap();
+        assert_eq!(config.addon_sort, None);
+    }
+
+    #[test]
+    fn test_pinned_addons() {
+        let mut config = Config::default();
+        assert!(!config.is_pinned(&Flavor::Retail, "WeakAuras"));
+        assert!(!config.should_skip_update(&Flavor::Retail, "WeakAuras"));
+
+        config.pin(Flavor::Retail, "WeakAuras".to_owned(), "3.0.0".to_owned());
+        config.pin(Flavor::Retail, "WeakAuras".to_owned(), "3.1.0".to_owned());
+        assert!(config.is_pinned(&Flavor::Retail, "WeakAuras"));
+        assert!(!config.is_pinned(&Flavor::Classic, "WeakAuras"));
+        assert_eq!(
+            config.pinned_version(&Flavor::Retail, "WeakAuras"),
+            Some("3.1.0")
+        );
+        assert!(config.should_skip_update(&Flavor::Retail, "WeakAuras"));
+        assert!(!config.should_skip_update(&Flavor::Retail, "Details"));
+
+        config.addons.ignore(Flavor::Retail, "Details".to_owned());
+        assert!(config.should_skip_update(&Flavor::Retail, "Details"));
+
+        config.unpin(&Flavor::Retail, "WeakAuras");
+        assert!(!config.is_pinned(&Flavor::Retail, "WeakAuras"));
+        assert!(!config.should_skip_update(&Flavor::Retail, "WeakAuras"));
+        assert!(config.pinned_addons.is_empty());
+
+        let config: Config =
+            serde_yaml::from_str("pinned_addons:\n  Retail:\n    Details: 1.2.3\n").unwrap();
+        assert_eq!(
+            config.pinned_version(&Flavor::Retail, "Details"),
+            Some("1.2.3")
+        );
+    }
+
+    #[test]
+    fn test_should_auto_update() {
+        let mut config = Config::default();
+        config.auto_update_flavors.insert(Flavor::Classic, true);
+        config.auto_update_flavors.insert(Flavor::Retail, false);
+
+        assert!(config.should_auto_update(&Flavor::Classic));
+        assert!(!config.should_auto_update(&Flavor::Retail));
+        assert!(!config.should_auto_update(&Flavor::RetailPTR));
+
+        config.auto_update = true;
+        assert!(config.should_auto_update(&Flavor::Classic));
+        assert!(!config.should_auto_update(&Flavor::Retail));
+        assert!(config.should_auto_update(&Flavor::RetailPTR));
+    }
+
+    #[test]
+    fn test_last_update_check() {
+        let mut config = Config::default();
+        assert_eq!(config.last_update_check(Flavor::Retail), None);
+
+        let now = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        config.record_update_check(Flavor::Retail, now);
+        assert_eq!(config.last_update_check(Flavor::Retail), Some(now));
+        assert_eq!(config.last_update_check(Flavor::Classic), None);
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let deser: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(deser.last_update_check(Flavor::Retail), Some(now));
+
+        let old: Config = serde_yaml::from_str("hide_ignored_addons: true").unwrap();
+        assert!(old.last_update_check.is_empty());
+    }
+
+    #[test]
+    fn test_auto_update_interval() {
+        let mut config = Config::default();
+        assert_eq!(config.auto_update_interval(), None);
+
+        config.auto_update_interval_minutes = Some(1);
+        assert_eq!(
+            config.auto_update_interval(),
+            Some(Duration::from_secs(60 * 15))
+        );
+
+        config.auto_update_interval_minutes = Some(60);
+        assert_eq!(
+            config.auto_update_interval(),
+            Some(Duration::from_secs(60 * 60))
+        );
+    }
+
+    #[test]
+    fn test_self_update_channel() {
+        let all = SelfUpdateChannel::all();
+        assert_eq!(
+            all,
+            [
+                SelfUpdateChannel::Stable,
+                SelfUpdateChannel::Beta,
+                SelfUpdateChannel::Nightly
+            ]
+        );
+
+        let names: Vec<_> = all.iter().map(ToString::to_string).collect();
+        assert_eq!(names, vec!["Stable", "Beta", "Nightly"]);
+
+        let config: Config = serde_yaml::from_str("self_update_channel: Beta").unwrap();
+        assert_eq!(config.self_update_channel, SelfUpdateChannel::Beta);
+    }
+
+    #[test]
+    fn test_accepts_release() {
+        assert!(!SelfUpdateChannel::Stable.is_prerelease());
+        assert!(SelfUpdateChannel::Beta.is_prerelease());
+        assert!(SelfUpdateChannel::Nightly.is_prerelease());
+
+        assert!(SelfUpdateChannel::Stable.accepts_release(false));
+        assert!(!SelfUpdateChannel::Stable.accepts_release(true));
+        assert!(SelfUpdateChannel::Beta.accepts_release(false));
+        assert!(SelfUpdateChannel::Beta.accepts_release(true));
+        assert!(SelfUpdateChannel::Nightly.accepts_release(false));
+        assert!(SelfUpdateChannel::Nightly.accepts_release(true));
+    }
+
+    #[test]
+    fn test_releases_url_with_override() {
+        for channel in SelfUpdateChannel::all().iter() {
+            assert_eq!(
+                channel.releases_url_with_override(None).unwrap(),
+                channel.releases_url()
+            );
+            assert!(channel
+                .releases_url()
+                .ends_with(&format!("/{}", channel.releases_path())));
+        }
+
+        assert_eq!(
+            SelfUpdateChannel::Stable
+                .releases_url_with_override(Some("https://mirror.example.com/github/"))
+                .unwrap(),
+            "https://mirror.example.com/github/repos/ajour/ajour/releases"
+        );
+        assert_eq!(
+            SelfUpdateChannel::Nightly
+                .releases_url_with_override(Some("http://mirror.example.com"))
+                .unwrap(),
+            "http://mirror.example.com/repos/ajour/ajour/releases"
+        );
+
+        for invalid in &[
+            "not a url",
+            "mirror.example.com",
+            "ftp://mirror.example.com",
+            "",
+        ] {
+            assert!(matches!(
+                SelfUpdateChannel::Stable.releases_url_with_override(Some(invalid)),
+                Err(DownloadError::InvalidSelfUpdateUrl { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_weak_auras_accounts() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        let account_dir = retail.join("WTF/Account");
+        create_dir_all(account_dir.join("SECOND")).unwrap();
+        create_dir_all(account_dir.join("FIRST")).unwrap();
+        create_dir_all(account_dir.join("SavedVariables")).unwrap();
+        std::fs::write(account_dir.join("config-cache.wtf"), "").unwrap();
+
+        let mut config = Config::default();
+        config.wow.directories.insert(Flavor::Retail, retail);
+
+        assert_eq!(
+            config.list_wtf_accounts(&Flavor::Retail),
+            vec!["FIRST".to_owned(), "SECOND".to_owned()]
+        );
+        assert!(config.list_wtf_accounts(&Flavor::Classic).is_empty());
+
+        assert_eq!(config.weak_auras_account_path(&Flavor::Retail), None);
+
+        config
+            .weak_auras_account
+            .insert(Flavor::Retail, "SECOND".to_owned());
+        assert_eq!(
+            config.weak_auras_account_path(&Flavor::Retail),
+            Some(account_dir.join("SECOND"))
+        );
+
+        config
+            .weak_auras_account
+            .insert(Flavor::Retail, "REMOVED".to_owned());
+        assert_eq!(config.weak_auras_account_path(&Flavor::Retail), None);
+    }
+
+    #[test]
+    fn test_migrate_weak_auras_accounts() {
+        let mut config: Config = serde_yaml::from_str(
+            r#"
+weak_auras_account:
+  Retail: FIRST
+  Classic: FIRST
+weak_auras_accounts:
+  Retail:
+    - SECOND
+    - FIRST
+"#,
+        )
+        .unwrap();
+
+        // Not migrated yet, the singular account comes first.
+        assert_eq!(
+            config.weak_auras_accounts(&Flavor::Retail),
+            vec!["FIRST".to_owned(), "SECOND".to_owned()]
+        );
+
+        config.migrate_weak_auras_accounts();
+        assert!(config.weak_auras_account.is_empty());
+        assert_eq!(
+            config.weak_auras_accounts.get(&Flavor::Retail),
+            Some(&vec!["FIRST".to_owned(), "SECOND".to_owned()])
+        );
+        assert_eq!(
+            config.weak_auras_accounts(&Flavor::Classic),
+            vec!["FIRST".to_owned()]
+        );
+        assert!(config.weak_auras_accounts(&Flavor::ClassicEra).is_empty());
+
+        // Nothing is left to migrate the second time.
+        let migrated = config.clone();
+        config.migrate_weak_auras_accounts();
+        assert_eq!(config, migrated);
+    }
+
+    #[test]
+    fn test_select_weak_auras_account() {
+        let mut config = Config::default();
+        config
+            .weak_auras_account
+            .insert(Flavor::Retail, "FIRST".to_owned());
+
+        config.select_weak_auras_account(Flavor::Retail, "SECOND".to_owned());
+        config.select_weak_auras_account(Flavor::Retail, "THIRD".to_owned());
+        config.select_weak_auras_account(Flavor::Retail, "SECOND".to_owned());
+
+        assert!(config.weak_auras_account.is_empty());
+        assert_eq!(
+            config.weak_auras_accounts(&Flavor::Retail),
+            vec!["SECOND".to_owned(), "THIRD".to_owned(), "FIRST".to_owned()]
+        );
+
+        config.remove_weak_auras_account(&Flavor::Retail, "THIRD");
+        config.remove_weak_auras_account(&Flavor::Retail, "UNKNOWN");
+        assert_eq!(
+            config.weak_auras_accounts(&Flavor::Retail),
+            vec!["SECOND".to_owned(), "FIRST".to_owned()]
+        );
+
+        config.remove_weak_auras_account(&Flavor::Retail, "SECOND");
+        config.remove_weak_auras_account(&Flavor::Retail, "FIRST");
+        assert!(config.weak_auras_accounts.is_empty());
+
+        // The singular account can be removed before it's migrated.
+        config
+            .weak_auras_account
+            .insert(Flavor::Retail, "FIRST".to_owned());
+        config.remove_weak_auras_account(&Flavor::Retail, "FIRST");
+        assert!(config.weak_auras_accounts(&Flavor::Retail).is_empty());
+    }
+
+    #[test]
+    fn test_rename_flavor_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let old = root.path().join("old/_retail_");
+        let new = root.path().join("new/_retail_");
+        let missing = root.path().join("missing/_retail_");
+        create_dir_all(old.join("WTF/Account/FIRST")).unwrap();
+        create_dir_all(new.join("WTF/Account/FIRST")).unwrap();
+        create_dir_all(missing.join("WTF/Account/OTHER")).unwrap();
+
+        let mut config = Config::default();
+        config.set_flavor_directory(Flavor::Retail, old).unwrap();
+        config
+            .weak_auras_account
+            .insert(Flavor::Retail, "FIRST".to_owned());
+
+        config
+            .rename_flavor_directory(Flavor::Retail, new.join("Interface/AddOns"))
+            .unwrap();
+        assert_eq!(
+            config.weak_auras_account_path(&Flavor::Retail),
+            Some(new.canonicalize().unwrap().join("WTF/Account/FIRST"))
+        );
+
+        config
+            .rename_flavor_directory(Flavor::Retail, missing.clone())
+            .unwrap();
+        assert_eq!(
+            config.wow.directories.get(&Flavor::Retail),
+            Some(&missing.canonicalize().unwrap())
+        );
+        assert!(!config.weak_auras_account.contains_key(&Flavor::Retail));
+    }
+
+    #[test]
+    fn test_asset_matches_os() {
+        assert!(asset_matches_os("windows", "ajour.exe"));
+        assert!(asset_matches_os("windows", "ajour-opengl.exe"));
+        assert!(asset_matches_os("linux", "ajour.AppImage"));
+        assert!(asset_matches_os("linux", "ajour-opengl.AppImage"));
+        assert!(asset_matches_os("macos", "ajour-macos.tar.gz"));
+        assert!(asset_matches_os("macos", "ajour.dmg"));
+
+        assert!(!asset_matches_os("windows", "ajour.AppImage"));
+        assert!(!asset_matches_os("linux", "ajour.exe"));
+        assert!(!asset_matches_os("linux", "ajour-macos.tar.gz"));
+        assert!(!asset_matches_os("macos", "ajour.exe"));
+        assert!(!asset_matches_os("macos", "ajour.tar.gz"));
+        assert!(!asset_matches_os("freebsd", "ajour.AppImage"));
+    }
+
+    #[test]
+    fn test_config_change() {
+        let mut config = Config::default();
+
+        let theme_dir = tempfile::tempdir().unwrap();
+
+        assert_eq!(
+            config.set_theme_in(Some("Nord".to_owned()), theme_dir.path()),
+            Ok(Some(ConfigChange::ThemeChanged))
+        );
+        assert_eq!(
+            config.set_theme_in(Some("Nord".to_owned()), theme_dir.path()),
+            Ok(None)
+        );
+        assert_eq!(config.theme.as_deref(), Some("Nord"));
+
+        assert_eq!(
+            config.set_language(Language::German),
+            Some(ConfigChange::LanguageChanged)
+        );
+        assert_eq!(config.set_language(Language::German), None);
+
+        assert_eq!(
+            config.set_scale(Some(1.5)),
+            Some(ConfigChange::ScaleChanged)
+        );
+        assert_eq!(config.set_scale(Some(1.5)), None);
+
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        create_dir_all(&retail).unwrap();
+
+        assert_eq!(
+            config.set_flavor_directory(Flavor::Retail, retail.clone()),
+            Ok(Some(ConfigChange::DirectoryChanged(Flavor::Retail)))
+        );
+        assert_eq!(
+            config.set_flavor_directory(Flavor::Retail, retail),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_clear_flavor() {
+        let mut config = Config::default();
+        for flavor in [Flavor::Retail, Flavor::Classic].iter() {
+            config
+                .wow
+                .directories
+                .insert(*flavor, PathBuf::from(flavor.folder_name()));
+            config
+                .weak_auras_account
+                .insert(*flavor, "ACCOUNT".to_owned());
+            config.select_weak_auras_account(*flavor, "OTHER".to_owned());
+            config.auto_update_flavors.insert(*flavor, true);
+            config.record_update_check(*flavor, SystemTime::now());
+            config.pin(*flavor, "WeakAuras".to_owned(), "3.0.0".to_owned());
+            config.addons.ignore(*flavor, "Details".to_owned());
+            config
+                .addons
+                .release_channels
+                .entry(*flavor)
+                .or_default()
+                .insert(
+                    "Details".to_owned(),
+                    crate::repository::ReleaseChannel::Beta,
+                );
+        }
+
+        config.wow.flavor = Flavor::Classic;
+        config.default_flavor = Some(Flavor::Classic);
+
+        let before = config.clone();
+        config.clear_flavor(&Flavor::ClassicEra);
+        assert_eq!(config, before);
+
+        config.clear_flavor(&Flavor::Classic);
+        assert_eq!(config.wow.flavor, Flavor::Retail);
+        assert_eq!(config.default_flavor, Some(Flavor::Retail));
+        assert!(!config.wow.directories.contains_key(&Flavor::Classic));
+        assert!(!config.weak_auras_account.contains_key(&Flavor::Classic));
+        assert!(!config.weak_auras_accounts.contains_key(&Flavor::Classic));
+        assert!(!config.auto_update_flavors.contains_key(&Flavor::Classic));
+        assert!(!config.addons.ignored.contains_key(&Flavor::Classic));
+        assert!(!config
+            .addons
+            .release_channels
+            .contains_key(&Flavor::Classic));
+        assert!(!config.pinned_addons.contains_key(&Flavor::Classic));
+        assert_eq!(config.last_update_check(Flavor::Classic), None);
+
+        assert!(config.wow.directories.contains_key(&Flavor::Retail));
+        assert!(config.weak_auras_accounts.contains_key(&Flavor::Retail));
+        assert!(config.auto_update_flavors.contains_key(&Flavor::Retail));
+        assert!(config.addons.is_ignored(&Flavor::Retail, "Details"));
+        assert!(config.addons.release_channels.contains_key(&Flavor::Retail));
+        assert!(config.last_update_check(Flavor::Retail).is_some());
+
+        config.clear_flavor(&Flavor::Retail);
+        assert_eq!(config.wow.flavor, Flavor::default());
+        assert_eq!(config.default_flavor, None);
+    }
+
+    #[test]
+    fn test_cached_addon_directory() {
+        use std::cell::Cell;
+
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        create_dir_all(retail.join("interface/addons")).unwrap();
+
+        let calls = Cell::new(0);
+        let resolve = |dir: &Path| {
+            calls.set(calls.get() + 1);
+            resolve_addon_directory(dir)
+        };
+
+        let first = cached_addon_directory(&retail, resolve);
+        let second = cached_addon_directory(&retail, resolve);
+        assert_eq!(first, retail.join("interface/addons"));
+        assert_eq!(first, second);
+        assert_eq!(calls.get(), 1);
+
+        ADDON_DIRECTORY_CACHE.write().unwrap().remove(&retail);
+        cached_addon_directory(&retail, resolve);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_root_directory_of_root_path() {
+        #[cfg(not(windows))]
+        let root = PathBuf::from("/");
+        #[cfg(windows)]
+        let root = PathBuf::from(r"C:\");
+
+        let mut config = Config::default();
+        config.wow.directories.insert(Flavor::Retail, root);
+        assert_eq!(config.get_root_directory_for_flavor(&Flavor::Retail), None);
+
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, PathBuf::from("/wow/_retail_"));
+        assert_eq!(
+            config.get_root_directory_for_flavor(&Flavor::Retail),
+            Some(PathBuf::from("/wow"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_with_glob_symbols() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("World of Warcraft [old/_retail_");
+        create_dir_all(retail.join("interface/addons")).unwrap();
+        create_dir_all(retail.join("wtf")).unwrap();
+
+        assert_eq!(
+            resolve_addon_directory(&retail),
+            retail.join("interface/addons")
+        );
+
+        let mut config = Config::default();
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, retail.clone());
+        assert_eq!(
+            config.get_wtf_directory_for_flavor(&Flavor::Retail),
+            Some(retail.join("wtf"))
+        );
+    }
+
+    #[test]
+    fn test_get_screenshots_directory_for_flavor() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.get_screenshots_directory_for_flavor(&Flavor::Retail),
+            None
+        );
+
+        let root = tempfile::tempdir().unwrap();
+        for folder in &["Screenshots", "SCREENSHOTS", "screenshots"] {
+            let retail = root.path().join(folder).join("_retail_");
+            create_dir_all(retail.join(folder)).unwrap();
+            config
+                .wow
+                .directories
+                .insert(Flavor::Retail, retail.clone());
+
+            assert_eq!(
+                config.get_screenshots_directory_for_flavor(&Flavor::Retail),
+                Some(retail.join(folder))
+            );
+        }
+
+        // Missing directories aren't created.
+        let retail = root.path().join("_retail_");
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, retail.clone());
+        let screenshots = config.get_screenshots_directory_for_flavor(&Flavor::Retail);
+        assert_eq!(screenshots, Some(retail.join("Screenshots")));
+        assert!(!retail.join("Screenshots").exists());
+    }
+
+    #[test]
+    fn test_clean_logs() {
+        let mut config = Config::default();
+        assert!(matches!(
+            config.clean_logs(&Flavor::Retail, Duration::from_secs(0)),
+            Err(FilesystemError::NoFlavorDirectory { .. })
+        ));
+
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, retail.clone());
+
+        // Nothing to clean without a Logs directory.
+        create_dir_all(&retail).unwrap();
+        assert_eq!(
+            config.get_logs_directory_for_flavor(&Flavor::Retail),
+            Some(retail.join("Logs"))
+        );
+        assert_eq!(
+            config
+                .clean_logs(&Flavor::Retail, Duration::from_secs(0))
+                .unwrap(),
+            0
+        );
+
+        let logs = retail.join("LOGS");
+        create_dir_all(logs.join("Archive")).unwrap();
+        assert_eq!(
+            config.get_logs_directory_for_flavor(&Flavor::Retail),
+            Some(logs.clone())
+        );
+
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        let aged = SystemTime::now() - 2 * week;
+        for name in &["WoWCombatLog.txt", "FrameXML.log", "Archive/old.log"] {
+            let file = std::fs::File::create(logs.join(name)).unwrap();
+            if *name != "FrameXML.log" {
+                file.set_modified(aged).unwrap();
+            }
+        }
+
+        assert_eq!(config.clean_logs(&Flavor::Retail, week).unwrap(), 1);
+        assert!(!logs.join("WoWCombatLog.txt").exists());
+        assert!(logs.join("FrameXML.log").exists());
+        assert!(logs.join("Archive/old.log").exists());
+    }
+
+    #[test]
+    fn test_clean_logs_refuses_other_directories() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        let other = root.path().join("Logs");
+        create_dir_all(&retail).unwrap();
+        create_dir_all(&other).unwrap();
+        std::fs::write(other.join("keep.txt"), "").unwrap();
+
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        let later = SystemTime::now() + 2 * week;
+
+        for dir in &[root.path().to_path_buf(), other.clone()] {
+            assert!(matches!(
+                clean_logs_dir(&retail, dir, week, later),
+                Err(FilesystemError::NotLogsDirectory { .. })
+            ));
+        }
+
+        // A Logs symlink pointing out of the flavor directory is refused too.
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&other, retail.join("Logs")).unwrap();
+            assert!(matches!(
+                clean_logs_dir(&retail, &retail.join("Logs"), week, later),
+                Err(FilesystemError::NotLogsDirectory { .. })
+            ));
+        }
+
+        assert!(other.join("keep.txt").exists());
+    }
+
+    #[test]
+    fn test_fonts_directory() {
+        let mut config = Config::default();
+        assert_eq!(config.get_fonts_directory_for_flavor(&Flavor::Retail), None);
+        assert!(matches!(
+            config.ensure_fonts_directory(&Flavor::Retail),
+            Err(FilesystemError::NoFlavorDirectory { .. })
+        ));
+
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("_retail_");
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, retail.clone());
+
+        // The flavor directory has to exist for the Fonts directory to be
+        // created in it.
+        assert!(matches!(
+            config.ensure_fonts_directory(&Flavor::Retail),
+            Err(FilesystemError::FileDoesntExist { .. })
+        ));
+
+        create_dir_all(&retail).unwrap();
+        assert_eq!(
+            config.get_fonts_directory_for_flavor(&Flavor::Retail),
+            Some(retail.join("Fonts"))
+        );
+        assert!(!retail.join("Fonts").exists());
+
+        assert_eq!(
+            config.ensure_fonts_directory(&Flavor::Retail).unwrap(),
+            retail.join("Fonts")
+        );
+        assert!(retail.join("Fonts").is_dir());
+
+        // An existing directory is found regardless of case.
+        let classic = root.path().join("_classic_");
+        create_dir_all(classic.join("FONTS")).unwrap();
+        config
+            .wow
+            .directories
+            .insert(Flavor::Classic, classic.clone());
+        assert_eq!(
+            config.get_fonts_directory_for_flavor(&Flavor::Classic),
+            Some(classic.join("FONTS"))
+        );
+        assert_eq!(
+            config.ensure_fonts_directory(&Flavor::Classic).unwrap(),
+            classic.join("FONTS")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_symlinks() {
+        let root = tempfile::tempdir().unwrap();
+        let retail = root.path().join("wow/_retail_");
+        let drive = root.path().join("drive/Interface");
+        create_dir_all(drive.join("AddOns")).unwrap();
+        create_dir_all(&retail).unwrap();
+        std::os::unix::fs::symlink(&drive, retail.join("Interface")).unwrap();
+
+        let mut config: Config = serde_yaml::from_str("{}").unwrap();
+        assert!(config.follow_symlinks);
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, retail.clone());
+
+        let real = drive.canonicalize().unwrap().join("AddOns");
+        assert_eq!(
+            config.resolve_addon_directory(&Flavor::Retail),
+            Some(real.clone())
+        );
+        assert_eq!(
+            config.ensure_addon_directory(&Flavor::Retail).unwrap(),
+            real
+        );
+
+        config.follow_symlinks = false;
+        let link = retail.join("Interface/AddOns");
+        assert_eq!(
+            config.resolve_addon_directory(&Flavor::Retail),
+            Some(link.clone())
+        );
+        assert_eq!(
+            config.ensure_addon_directory(&Flavor::Retail).unwrap(),
+            link
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_backup_symlinked_interface() {
+        use crate::backup::BackupFolder;
+        use crate::fs::backup::{Backup, ZipBackup};
+
+        let root = tempfile::tempdir().unwrap();
+        let wow = root.path().join("wow");
+        let retail = wow.join("_retail_");
+        let drive = root.path().join("drive/Interface");
+        create_dir_all(drive.join("AddOns/Details")).unwrap();
+        std::fs::write(drive.join("AddOns/Details/Details.toc"), "").unwrap();
+        create_dir_all(&retail).unwrap();
+        std::os::unix::fs::symlink(&drive, retail.join("Interface")).unwrap();
+
+        let mut config = Config {
+            backup_addons: true,
+            ..Default::default()
+        };
+        assert!(config.follow_symlinks);
+        config.wow.directories.insert(Flavor::Retail, retail);
+
+        let src_folders = config
+            .backup_targets_in(&Flavor::Retail, root.path())
+            .into_iter()
+            .map(|(_, path)| BackupFolder::new(path, &wow))
+            .collect();
+
+        let dest = root.path().join("backup.zip");
+        ZipBackup::new(src_folders, &dest).backup().unwrap();
+
+        let zip = zip::ZipArchive::new(std::fs::File::open(&dest).unwrap()).unwrap();
+        let names: Vec<_> = zip.file_names().collect();
+        assert!(names.contains(&"_retail_/Interface/AddOns/Details/Details.toc"));
+    }
+
+    #[test]
+    fn test_resolve_case_insensitive_subdir() {
+        for subpath in &["Interface/AddOns", "INTERFACE/ADDONS", "interface/addons"] {
+            let base = tempfile::tempdir().unwrap();
+            create_dir_all(base.path().join(subpath)).unwrap();
+
+            assert_eq!(
+                resolve_case_insensitive_subdir(base.path(), "Interface/AddOns"),
+                base.path().join(subpath)
+            );
+        }
+
+        // Nothing matching falls back to the joined path.
+        let base = tempfile::tempdir().unwrap();
+        assert_eq!(
+            resolve_case_insensitive_subdir(base.path(), "Interface/AddOns"),
+            base.path().join("Interface/AddOns")
+        );
+
+        // Glob symbols in the subpath are matched literally.
+        create_dir_all(base.path().join("wtf[1]")).unwrap();
+        assert_eq!(
+            resolve_case_insensitive_subdir(base.path(), "WTF[1]"),
+            base.path().join("wtf[1]")
+        );
+    }
+
+    #[test]
+    fn test_set_flavor_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let root_path = root.path().canonicalize().unwrap();
+        let retail = root_path.join("_retail_");
+        create_dir_all(retail.join("Interface/AddOns")).unwrap();
+
+        let mut config = Config::default();
+
+        // Picking the AddOns folder is corrected to the flavor folder.
+        config
+            .set_flavor_directory(Flavor::Retail, retail.join("Interface/AddOns"))
+            .unwrap();
+        assert_eq!(config.wow.directories.get(&Flavor::Retail), Some(&retail));
+
+        config
+            .set_flavor_directory(Flavor::Retail, retail.join("Interface"))
+            .unwrap();
+        assert_eq!(config.wow.directories.get(&Flavor::Retail), Some(&retail));
+
+        // A custom folder name is fine, as long as it has WoW folders.
+        let custom = root_path.join("retail copy");
+        create_dir_all(custom.join("WTF")).unwrap();
+        config
+            .set_flavor_directory(Flavor::RetailPTR, custom.clone())
+            .unwrap();
+        assert_eq!(
+            config.wow.directories.get(&Flavor::RetailPTR),
+            Some(&custom)
+        );
+
+        let bogus = root_path.join("Documents");
+        create_dir_all(&bogus).unwrap();
+        assert_eq!(
+            config.set_flavor_directory(Flavor::Classic, bogus.clone()),
+            Err(DirectoryError::NotFlavorDirectory {
+                flavor: Flavor::Classic,
+                path: bogus,
+            })
+        );
+
+        let missing = root_path.join("missing");
+        assert_eq!(
+            config.set_flavor_directory(Flavor::Classic, missing.clone()),
+            Err(DirectoryError::DoesntExist { path: missing })
+        );
+        assert_eq!(config.wow.directories.get(&Flavor::Classic), None);
+    }
+
+    #[test]
+    fn test_addon_cache_directory() {
+        let root = tempfile::tempdir().unwrap();
+
+        let retail = addon_cache_directory_in(root.path(), &Flavor::Retail).unwrap();
+        let classic_era = addon_cache_directory_in(root.path(), &Flavor::ClassicEra).unwrap();
+
+        assert_eq!(retail, root.path().join("cache/addons/retail"));
+        assert_eq!(classic_era, root.path().join("cache/addons/classic_era"));
+        assert!(retail.is_dir());
+        assert!(classic_era.is_dir());
+    }
+
+    #[test]
+    fn test_set_theme() {
+        let theme_dir = tempfile::tempdir().unwrap();
+        write_theme(&theme_dir.path().join("custom.yml"), "My Theme");
+
+        let mut config = Config::default();
+
+        // Custom
+        assert_eq!(
+            config.set_theme_in(Some("My Theme".to_owned()), theme_dir.path()),
+            Ok(Some(ConfigChange::ThemeChanged))
+        );
+        assert_eq!(config.theme.as_deref(), Some("My Theme"));
+
+        // Bundled
+        assert_eq!(
+            config.set_theme_in(Some("Dracula".to_owned()), theme_dir.path()),
+            Ok(Some(ConfigChange::ThemeChanged))
+        );
+        assert_eq!(config.theme.as_deref(), Some("Dracula"));
+
+        // Unknown themes are rejected and leave the theme as is
+        assert_eq!(
+            config.set_theme_in(Some("custom".to_owned()), theme_dir.path()),
+            Err(ThemeError::UnknownTheme {
+                name: "custom".to_owned()
+            })
+        );
+        assert_eq!(config.theme.as_deref(), Some("Dracula"));
+
+        // None resets to the default
+        assert_eq!(
+            config.set_theme_in(None, theme_dir.path()),
+            Ok(Some(ConfigChange::ThemeChanged))
+        );
+        assert_eq!(config.theme, None);
+        assert_eq!(config.theme_or_default_in(theme_dir.path()), DEFAULT_THEME);
+    }
+
+    #[test]
+    fn test_list_themes() {
+        let theme_dir = tempfile::tempdir().unwrap();
+        write_theme(&theme_dir.path().join("custom.yml"), "My Theme");
+        write_theme(&theme_dir.path().join("other.yaml"), "Other");
+        std::fs::write(theme_dir.path().join("broken.yml"), "name: Broken\n").unwrap();
+        std::fs::write(theme_dir.path().join("other.toml"), "").unwrap();
+        std::fs::write(theme_dir.path().join("notes.txt"), "").unwrap();
+
+        assert_eq!(
+            list_themes_in(theme_dir.path()).unwrap(),
+            vec!["My Theme".to_owned(), "Other".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_detect_from_codes() {
+        let codes = vec!["C".to_owned(), "de_DE.UTF-8".to_owned()];
+        assert_eq!(detect_from_codes(codes), Language::German);
+
+        let codes = vec!["xx_XX".to_owned()];
+        assert_eq!(detect_from_codes(codes), Language::English);
+    }
+}
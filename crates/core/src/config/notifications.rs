@@ -0,0 +1,96 @@
+use super::default_true;
+use serde::{Deserialize, Serialize};
+
+/// Events the user can be notified about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationEvent {
+    UpdateSuccess,
+    UpdateFailure,
+    NewCatalog,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 3] = [
+        NotificationEvent::UpdateSuccess,
+        NotificationEvent::UpdateFailure,
+        NotificationEvent::NewCatalog,
+    ];
+}
+
+/// Struct for which events desktop notifications are shown. Only failures are
+/// notified by default.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotificationConfig {
+    #[serde(default)]
+    pub on_update_success: bool,
+
+    #[serde(default = "default_true")]
+    pub on_update_failure: bool,
+
+    #[serde(default)]
+    pub on_new_catalog: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        NotificationConfig {
+            on_update_success: false,
+            on_update_failure: true,
+            on_new_catalog: false,
+        }
+    }
+}
+
+impl NotificationConfig {
+    /// Returns `true` if a notification should be shown for `event`.
+    pub fn should_notify(&self, event: NotificationEvent) -> bool {
+        match event {
+            NotificationEvent::UpdateSuccess => self.on_update_success,
+            NotificationEvent::UpdateFailure => self.on_update_failure,
+            NotificationEvent::NewCatalog => self.on_new_catalog,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_config_serde() {
+        let config: NotificationConfig = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(config, NotificationConfig::default());
+
+        let config = NotificationConfig {
+            on_update_success: true,
+            on_update_failure: false,
+            on_new_catalog: true,
+        };
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert_eq!(
+            serde_yaml::from_str::<NotificationConfig>(&yaml).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn test_should_notify() {
+        let default = NotificationConfig::default();
+        assert!(!default.should_notify(NotificationEvent::UpdateSuccess));
+        assert!(default.should_notify(NotificationEvent::UpdateFailure));
+        assert!(!default.should_notify(NotificationEvent::NewCatalog));
+
+        // Every event only depends on its own flag.
+        for enabled in 0..NotificationEvent::ALL.len() {
+            let config = NotificationConfig {
+                on_update_success: enabled == 0,
+                on_update_failure: enabled == 1,
+                on_new_catalog: enabled == 2,
+            };
+
+            for (i, event) in NotificationEvent::ALL.iter().enumerate() {
+                assert_eq!(config.should_notify(*event), i == enabled, "{:?}", event);
+            }
+        }
+    }
+}
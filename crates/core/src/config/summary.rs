@@ -0,0 +1,117 @@
+use crate::config::{Config, Flavor, Language, SelfUpdateChannel};
+use crate::utility::expand_path;
+
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+/// Sanitized overview of a `Config` which is safe to attach to bug reports.
+///
+/// Paths are reduced to their final component, so usernames in home
+/// directories aren't leaked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigSummary {
+    pub flavors: Vec<FlavorSummary>,
+    pub theme: Option<String>,
+    pub language: Language,
+    pub self_update_channel: SelfUpdateChannel,
+    pub backup_addons: bool,
+    pub backup_wtf: bool,
+    pub backup_config: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlavorSummary {
+    pub flavor: Flavor,
+    pub directory: String,
+    pub exists: bool,
+}
+
+impl ConfigSummary {
+    pub(crate) fn new(config: &Config) -> Self {
+        let flavors = config
+            .wow
+            .sorted_directories()
+            .into_iter()
+            .map(|(flavor, dir)| {
+                let dir = expand_path(dir);
+
+                FlavorSummary {
+                    flavor,
+                    directory: redact_path(&dir),
+                    exists: dir.is_dir(),
+                }
+            })
+            .collect();
+
+        ConfigSummary {
+            flavors,
+            theme: config.theme.clone(),
+            language: config.language,
+            self_update_channel: config.self_update_channel,
+            backup_addons: config.backup_addons,
+            backup_wtf: config.backup_wtf,
+            backup_config: config.backup_config,
+        }
+    }
+}
+
+impl Display for ConfigSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Flavors:")?;
+        if self.flavors.is_empty() {
+            writeln!(f, "  none")?;
+        }
+        for flavor in &self.flavors {
+            writeln!(
+                f,
+                "  {}: {} ({})",
+                flavor.flavor,
+                flavor.directory,
+                if flavor.exists { "exists" } else { "missing" }
+            )?;
+        }
+        writeln!(f, "Theme: {}", self.theme.as_deref().unwrap_or("default"))?;
+        writeln!(f, "Language: {:?}", self.language)?;
+        writeln!(f, "Self update channel: {}", self.self_update_channel)?;
+        write!(
+            f,
+            "Backup: addons={}, wtf={}, config={}",
+            self.backup_addons, self.backup_wtf, self.backup_config
+        )
+    }
+}
+
+/// Returns only the final component of `path`.
+fn redact_path(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "<root>".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_summary_redacts_paths() {
+        let mut config = Config::default();
+        config.wow.directories.insert(
+            Flavor::Retail,
+            PathBuf::from("/home/secret-user/Games/World of Warcraft/_retail_"),
+        );
+        config.backup_directory = Some(PathBuf::from("/home/secret-user/Backups"));
+        config.backup_wtf = true;
+
+        let summary = config.summary();
+        assert_eq!(summary.flavors[0].directory, "_retail_");
+        assert!(!summary.flavors[0].exists);
+
+        let output = summary.to_string();
+        assert!(output.contains("Retail: _retail_ (missing)"));
+        assert!(output.contains("Backup: addons=false, wtf=true, config=false"));
+        assert!(!output.contains("secret-user"));
+        assert!(!output.contains('/'));
+    }
+}
@@ -1,6 +1,26 @@
-use serde::{Deserialize, Serialize};
+use crate::config::Language;
+use crate::error::FilesystemError;
+use crate::utility::expand_path;
+
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Flavor names which differ from the English name, per language.
+static LOCALIZED_FLAVOR_NAMES: &[(Language, Flavor, &str)] = &[
+    (Language::German, Flavor::RetailPTR, "Retail-PTR"),
+    (Language::German, Flavor::RetailBeta, "Retail-Beta"),
+    (Language::German, Flavor::ClassicPTR, "Classic-PTR"),
+    (Language::German, Flavor::ClassicEra, "Classic-Ära"),
+    (Language::French, Flavor::RetailPTR, "Retail RTP"),
+    (Language::French, Flavor::RetailBeta, "Retail Bêta"),
+    (Language::French, Flavor::ClassicPTR, "Classic RTP"),
+    (Language::Spanish, Flavor::RetailPTR, "Retail RPP"),
+    (Language::Spanish, Flavor::ClassicPTR, "Classic RPP"),
+    (Language::Portuguese, Flavor::RetailPTR, "Retail RTP"),
+    (Language::Portuguese, Flavor::ClassicPTR, "Classic RTP"),
+];
 
 /// Struct for settings related to World of Warcraft.
 #[serde(default)]
@@ -17,6 +37,269 @@ pub struct Wow {
     pub flavor: Flavor,
 }
 
+impl Wow {
+    /// Returns the directory of `flavor` with environment variables expanded.
+    pub fn directory(&self, flavor: &Flavor) -> Option<PathBuf> {
+        self.directories.get(flavor).map(|dir| expand_path(dir))
+    }
+
+    /// Returns the flavor directories sorted by flavor, so the order is stable.
+    pub fn sorted_directories(&self) -> Vec<(Flavor, &Path)> {
+        self.ordered_directories(&[])
+    }
+
+    /// Returns the flavor directories in the order of `order`, followed by the
+    /// flavors which aren't in `order`, sorted by flavor. Flavors in `order`
+    /// without a directory are skipped.
+    pub fn ordered_directories(&self, order: &[Flavor]) -> Vec<(Flavor, &Path)> {
+        let mut directories: Vec<_> = self
+            .directories
+            .iter()
+            .map(|(flavor, dir)| (*flavor, dir.as_path()))
+            .collect();
+
+        // Only the first occurrence of a flavor in `order` counts.
+        directories.sort_by_key(|(flavor, _)| {
+            let position = order.iter().position(|f| f == flavor);
+            (position.unwrap_or(usize::MAX), *flavor)
+        });
+        directories
+    }
+
+    /// Returns the flavor to show first: `default_flavor` if it has a
+    /// directory set, else the first flavor with a directory.
+    /// This will return `None` if no flavor has a directory.
+    pub fn primary_flavor(&self, default_flavor: Option<Flavor>) -> Option<Flavor> {
+        default_flavor
+            .filter(|flavor| self.directories.contains_key(flavor))
+            .or_else(|| self.sorted_directories().first().map(|(flavor, _)| *flavor))
+    }
+
+    /// Scans a World of Warcraft root directory for flavor folders.
+    ///
+    /// A folder is only accepted if it contains an `Interface` directory,
+    /// matched case-insensitively.
+    pub fn detect_flavors(root: &Path) -> HashMap<Flavor, PathBuf> {
+        let mut flavors = HashMap::new();
+
+        let entries = match fs::read_dir(root) {
+            Ok(entries) => entries,
+            Err(_) => return flavors,
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+
+            let flavor = match path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(Flavor::from_folder_name)
+            {
+                Some(flavor) => flavor,
+                None => continue,
+            };
+
+            if path.is_dir() && has_interface_dir(&path) {
+                flavors.insert(flavor, path);
+            }
+        }
+
+        flavors
+    }
+
+    /// Adds the flavors detected in `root` to `directories` and returns the
+    /// flavors which were added.
+    ///
+    /// Directories which are already set are only replaced if `force` is `true`.
+    pub fn add_detected_flavors(&mut self, root: &Path, force: bool) -> Vec<Flavor> {
+        let mut added = vec![];
+
+        for (flavor, path) in Wow::detect_flavors(root) {
+            if force || !self.directories.contains_key(&flavor) {
+                self.directories.insert(flavor, path);
+                added.push(flavor);
+            }
+        }
+
+        added.sort();
+        added
+    }
+
+    /// Reads the flavor directories of the games installed through Battle.net
+    /// from its `product.db`, found in `C:\ProgramData\Battle.net\Agent` on
+    /// Windows and `/Users/Shared/Battle.net/Agent` on macOS.
+    ///
+    /// The install path is the World of Warcraft root directory, unless it
+    /// already is a flavor folder. Installs without a path and products which
+    /// aren't a flavor are skipped. A missing file gives no directories, and a
+    /// damaged one the installs read before the damage. Nothing is checked on
+    /// disk.
+    pub fn import_from_battlenet(
+        product_db: &Path,
+    ) -> Result<HashMap<Flavor, PathBuf>, FilesystemError> {
+        let mut flavors = HashMap::new();
+
+        let contents = match fs::read(product_db) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(flavors),
+            Err(source) => {
+                return Err(FilesystemError::Read {
+                    path: product_db.to_path_buf(),
+                    source,
+                })
+            }
+        };
+
+        // `product.db` is a protobuf `Database`, whose field 1 holds each
+        // `ProductInstall`.
+        for (number, install) in length_delimited_fields(&contents) {
+            if number != 1 {
+                continue;
+            }
+
+            let mut product_code = None;
+            let mut install_path = None;
+
+            for (number, value) in length_delimited_fields(install) {
+                match number {
+                    2 => product_code = std::str::from_utf8(value).ok(),
+                    // `UserSettings`, whose field 1 is the install path.
+                    3 => {
+                        install_path = length_delimited_fields(value)
+                            .into_iter()
+                            .find(|(number, _)| *number == 1)
+                            .and_then(|(_, path)| std::str::from_utf8(path).ok())
+                    }
+                    _ => {}
+                }
+            }
+
+            let flavor = match product_code.and_then(Flavor::from_product_code) {
+                Some(flavor) => flavor,
+                None => continue,
+            };
+
+            let install_path = match install_path {
+                Some(path) if !path.trim().is_empty() => PathBuf::from(path.trim()),
+                _ => continue,
+            };
+
+            let is_flavor_folder = install_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(Flavor::from_folder_name)
+                == Some(flavor);
+
+            let dir = if is_flavor_folder {
+                install_path
+            } else {
+                install_path.join(flavor.folder_name())
+            };
+
+            flavors.insert(flavor, dir);
+        }
+
+        Ok(flavors)
+    }
+}
+
+/// Returns the field numbers and contents of the length delimited fields of a
+/// protobuf message, skipping fields of other wire types. Stops at the first
+/// field which can't be read.
+fn length_delimited_fields(message: &[u8]) -> Vec<(u64, &[u8])> {
+    let mut fields = vec![];
+    let mut pos = 0;
+
+    while pos < message.len() {
+        let key = match read_varint(message, &mut pos) {
+            Some(key) => key,
+            None => break,
+        };
+
+        match key & 0x7 {
+            0 => {
+                if read_varint(message, &mut pos).is_none() {
+                    break;
+                }
+            }
+            1 => pos += 8,
+            2 => {
+                let end = match read_varint(message, &mut pos)
+                    .and_then(|len| pos.checked_add(len as usize))
+                {
+                    Some(end) if end <= message.len() => end,
+                    _ => break,
+                };
+
+                fields.push((key >> 3, &message[pos..end]));
+                pos = end;
+            }
+            5 => pos += 4,
+            _ => break,
+        }
+    }
+
+    fields
+}
+
+/// Reads the varint at `pos`, moving `pos` past it.
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0;
+
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+/// Deserializes a list of flavors, skipping entries which aren't a flavor.
+pub(crate) fn de_flavor_order<'de, D>(deserializer: D) -> Result<Vec<Flavor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Flavor(Flavor),
+        Unknown(IgnoredAny),
+    }
+
+    let entries = Vec::<Entry>::deserialize(deserializer)?;
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Flavor(flavor) => Some(flavor),
+            Entry::Unknown(_) => {
+                log::warn!("ignoring unknown flavor in flavor_order");
+                None
+            }
+        })
+        .collect())
+}
+
+/// Returns `true` if `dir` contains an `Interface` directory, ignoring case.
+fn has_interface_dir(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries.filter_map(Result::ok).any(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case("interface")
+                    && entry.path().is_dir()
+            })
+        })
+        .unwrap_or_default()
+}
+
 impl Default for Wow {
     fn default() -> Self {
         Wow {
@@ -33,25 +316,28 @@ pub enum Flavor {
     Retail,
     RetailPTR,
     RetailBeta,
-    #[serde(alias = "classic", alias = "wow_classic")]
+    #[serde(alias = "classic", alias = "wow_classic", alias = "ClassicTbc")]
     Classic,
     ClassicPTR,
+    #[serde(alias = "classic_era", alias = "wow_classic_era")]
+    ClassicEra,
 }
 
 impl Flavor {
-    pub const ALL: [Flavor; 5] = [
+    pub const ALL: [Flavor; 6] = [
         Flavor::Retail,
         Flavor::RetailPTR,
         Flavor::RetailBeta,
         Flavor::Classic,
         Flavor::ClassicPTR,
+        Flavor::ClassicEra,
     ];
 
     /// Returns flavor `String` in CurseForge format
     pub(crate) fn curse_format(self) -> String {
         match self {
             Flavor::Retail | Flavor::RetailPTR | Flavor::RetailBeta => "wow_retail".to_owned(),
-            Flavor::Classic | Flavor::ClassicPTR => "wow_classic".to_owned(),
+            Flavor::Classic | Flavor::ClassicPTR | Flavor::ClassicEra => "wow_classic".to_owned(),
         }
     }
 
@@ -59,18 +345,60 @@ impl Flavor {
     pub(crate) fn hub_format(self) -> String {
         match self {
             Flavor::Retail | Flavor::RetailPTR | Flavor::RetailBeta => "retail".to_owned(),
-            Flavor::Classic | Flavor::ClassicPTR => "classic".to_owned(),
+            Flavor::Classic | Flavor::ClassicPTR | Flavor::ClassicEra => "classic".to_owned(),
+        }
+    }
+
+    /// Returns `BaseFlavor` which self relates to.
+    ///
+    /// Classic Era has its own folder on disk, but shares remote sources with
+    /// Classic, so it resolves to `BaseFlavor::Classic`.
+    pub const fn base_flavor(self) -> BaseFlavor {
+        match self {
+            Flavor::Retail | Flavor::RetailPTR | Flavor::RetailBeta => BaseFlavor::Retail,
+            Flavor::Classic | Flavor::ClassicPTR | Flavor::ClassicEra => BaseFlavor::Classic,
+        }
+    }
+
+    /// Returns `true` if self is one of the Classic flavors.
+    pub const fn is_classic(self) -> bool {
+        matches!(self.base_flavor(), BaseFlavor::Classic)
+    }
+
+    /// Returns the expected major game version for self, which is the
+    /// `## Interface:` number in a TOC file divided by `10000`.
+    ///
+    /// PTR and Beta share the prefix of the flavor they test. Bump these when
+    /// a new expansion is released.
+    pub const fn interface_version_prefix(self) -> u32 {
+        match self {
+            Flavor::Retail | Flavor::RetailPTR | Flavor::RetailBeta => 10,
+            Flavor::Classic | Flavor::ClassicPTR => 3,
+            Flavor::ClassicEra => 1,
         }
     }
 
-    /// Returns `Flavor` which self relates to.
-    pub fn base_flavor(self) -> Flavor {
+    /// Returns the English display name of the flavor.
+    pub const fn english_name(self) -> &'static str {
         match self {
-            Flavor::Retail | Flavor::RetailPTR | Flavor::RetailBeta => Flavor::Retail,
-            Flavor::Classic | Flavor::ClassicPTR => Flavor::Classic,
+            Flavor::Retail => "Retail",
+            Flavor::RetailPTR => "Retail PTR",
+            Flavor::RetailBeta => "Retail Beta",
+            Flavor::Classic => "Classic",
+            Flavor::ClassicPTR => "Classic PTR",
+            Flavor::ClassicEra => "Classic Era",
         }
     }
 
+    /// Returns the display name of the flavor in `lang`, falling back to the
+    /// English name when there is no translation.
+    pub fn localized_name(&self, lang: Language) -> &'static str {
+        LOCALIZED_FLAVOR_NAMES
+            .iter()
+            .find(|(l, f, _)| *l == lang && f == self)
+            .map_or_else(|| self.english_name(), |(_, _, name)| name)
+    }
+
     /// Returns `String` which correlate to the folder on disk.
     pub(crate) fn folder_name(self) -> String {
         match self {
@@ -79,8 +407,52 @@ impl Flavor {
             Flavor::RetailBeta => "_beta_".to_owned(),
             Flavor::Classic => "_classic_".to_owned(),
             Flavor::ClassicPTR => "_classic_ptr_".to_owned(),
+            Flavor::ClassicEra => "_classic_era_".to_owned(),
+        }
+    }
+
+    /// Returns the `Flavor` which correlates to the folder on disk, ignoring
+    /// case and surrounding whitespace.
+    /// This will return `None` if the folder doesn't match any flavor.
+    pub fn from_folder_name(name: &str) -> Option<Flavor> {
+        let name = name.trim();
+
+        Flavor::ALL
+            .iter()
+            .find(|flavor| flavor.folder_name().eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    /// Returns the `Battle.net` product code of the flavor, eg. `wowt`.
+    pub const fn product_code(self) -> &'static str {
+        match self {
+            Flavor::Retail => "wow",
+            Flavor::RetailPTR => "wowt",
+            Flavor::RetailBeta => "wow_beta",
+            Flavor::Classic => "wow_classic",
+            Flavor::ClassicPTR => "wow_classic_ptr",
+            Flavor::ClassicEra => "wow_classic_era",
         }
     }
+
+    /// Returns the `Flavor` of a `Battle.net` product code, ignoring case and
+    /// surrounding whitespace.
+    /// This will return `None` for products which aren't a supported flavor.
+    pub fn from_product_code(code: &str) -> Option<Flavor> {
+        let code = code.trim();
+
+        Flavor::ALL
+            .iter()
+            .find(|flavor| flavor.product_code().eq_ignore_ascii_case(code))
+            .copied()
+    }
+}
+
+/// The game family a `Flavor` belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BaseFlavor {
+    Retail,
+    Classic,
 }
 
 impl Default for Flavor {
@@ -91,16 +463,302 @@ impl Default for Flavor {
 
 impl std::fmt::Display for Flavor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Flavor::Retail => "Retail",
-                Flavor::RetailPTR => "Retail PTR",
-                Flavor::RetailBeta => "Retail Beta",
-                Flavor::Classic => "Classic",
-                Flavor::ClassicPTR => "Classic PTR",
+        write!(f, "{}", self.english_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localized_name() {
+        assert_eq!(
+            Flavor::ClassicEra.localized_name(Language::German),
+            "Classic-Ära"
+        );
+        assert_eq!(
+            Flavor::RetailPTR.localized_name(Language::German),
+            "Retail-PTR"
+        );
+
+        // Falls back to English
+        assert_eq!(Flavor::Retail.localized_name(Language::German), "Retail");
+        assert_eq!(
+            Flavor::ClassicEra.localized_name(Language::Korean),
+            "Classic Era"
+        );
+
+        for flavor in Flavor::ALL.iter() {
+            assert_eq!(flavor.localized_name(Language::English), flavor.to_string());
+        }
+    }
+
+    #[test]
+    fn test_base_flavor() {
+        for flavor in Flavor::ALL.iter() {
+            let expected = match flavor {
+                Flavor::Retail | Flavor::RetailPTR | Flavor::RetailBeta => BaseFlavor::Retail,
+                Flavor::Classic | Flavor::ClassicPTR | Flavor::ClassicEra => BaseFlavor::Classic,
+            };
+
+            assert_eq!(flavor.base_flavor(), expected);
+            assert_eq!(flavor.is_classic(), expected == BaseFlavor::Classic);
+        }
+
+        assert!(Flavor::ClassicEra.is_classic());
+        assert!(!Flavor::RetailBeta.is_classic());
+    }
+
+    #[test]
+    fn test_interface_version_prefix() {
+        assert_eq!(100_207 / 10_000, Flavor::Retail.interface_version_prefix());
+        assert_eq!(30_402 / 10_000, Flavor::Classic.interface_version_prefix());
+        assert_eq!(
+            11_403 / 10_000,
+            Flavor::ClassicEra.interface_version_prefix()
+        );
+
+        for flavor in Flavor::ALL.iter() {
+            let prefix = flavor.interface_version_prefix();
+            assert!((1..100).contains(&prefix));
+
+            if flavor.base_flavor() == BaseFlavor::Retail {
+                assert_eq!(prefix, Flavor::Retail.interface_version_prefix());
             }
-        )
+        }
+
+        assert_eq!(
+            Flavor::ClassicPTR.interface_version_prefix(),
+            Flavor::Classic.interface_version_prefix()
+        );
+
+        let mut prefixes = vec![
+            Flavor::Retail.interface_version_prefix(),
+            Flavor::Classic.interface_version_prefix(),
+            Flavor::ClassicEra.interface_version_prefix(),
+        ];
+        prefixes.sort_unstable();
+        prefixes.dedup();
+        assert_eq!(prefixes.len(), 3);
+    }
+
+    #[test]
+    fn test_primary_flavor() {
+        let mut wow = Wow::default();
+        assert_eq!(wow.primary_flavor(Some(Flavor::Classic)), None);
+
+        wow.directories
+            .insert(Flavor::Classic, PathBuf::from("_classic_"));
+        wow.directories
+            .insert(Flavor::ClassicEra, PathBuf::from("_classic_era_"));
+
+        assert_eq!(
+            wow.primary_flavor(Some(Flavor::ClassicEra)),
+            Some(Flavor::ClassicEra)
+        );
+        assert_eq!(
+            wow.primary_flavor(Some(Flavor::Retail)),
+            Some(Flavor::Classic)
+        );
+        assert_eq!(wow.primary_flavor(None), Some(Flavor::Classic));
+    }
+
+    #[test]
+    fn test_sorted_directories() {
+        let mut wow = Wow::default();
+        for flavor in Flavor::ALL.iter().rev() {
+            wow.directories
+                .insert(*flavor, PathBuf::from(flavor.folder_name()));
+        }
+
+        let sorted = wow.sorted_directories();
+        let flavors: Vec<_> = sorted.iter().map(|(flavor, _)| *flavor).collect();
+        assert_eq!(flavors, Flavor::ALL.to_vec());
+        assert_eq!(sorted[0].1, Path::new("_retail_"));
+
+        for _ in 0..10 {
+            assert_eq!(wow.sorted_directories(), sorted);
+        }
+    }
+
+    #[test]
+    fn test_classic_era_round_trip() {
+        let mut accounts = HashMap::new();
+        accounts.insert(Flavor::Classic, "classic".to_owned());
+        accounts.insert(Flavor::ClassicEra, "era".to_owned());
+
+        let yaml = serde_yaml::to_string(&accounts).unwrap();
+        let deser: HashMap<Flavor, String> = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(accounts, deser);
+    }
+
+    #[test]
+    fn test_legacy_classic_keys() {
+        let yaml = "---\nwow_classic: a\nClassicTbc: b\nclassic_era: c\n";
+        let deser: HashMap<Flavor, String> = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            deser.get(&Flavor::ClassicEra).map(String::as_str),
+            Some("c")
+        );
+        assert!(deser.contains_key(&Flavor::Classic));
+        assert_eq!(deser.len(), 2);
+    }
+
+    #[test]
+    fn test_from_folder_name() {
+        assert_eq!(Flavor::from_folder_name("_RETAIL_"), Some(Flavor::Retail));
+        assert_eq!(Flavor::from_folder_name("_classic_"), Some(Flavor::Classic));
+        assert_eq!(
+            Flavor::from_folder_name(" _Classic_Era_\n"),
+            Some(Flavor::ClassicEra)
+        );
+        assert_eq!(Flavor::from_folder_name("Interface"), None);
+        assert_eq!(Flavor::from_folder_name("classic"), None);
+
+        for flavor in Flavor::ALL.iter() {
+            assert_eq!(
+                Flavor::from_folder_name(&flavor.folder_name()),
+                Some(*flavor)
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_product_code() {
+        let codes = [
+            ("wow", Flavor::Retail),
+            ("wowt", Flavor::RetailPTR),
+            ("wow_beta", Flavor::RetailBeta),
+            ("wow_classic", Flavor::Classic),
+            ("wow_classic_ptr", Flavor::ClassicPTR),
+            ("wow_classic_era", Flavor::ClassicEra),
+        ];
+        for (code, flavor) in codes.iter() {
+            assert_eq!(Flavor::from_product_code(code), Some(*flavor));
+            assert_eq!(flavor.product_code(), *code);
+        }
+
+        assert_eq!(
+            Flavor::from_product_code(" WoW_Classic\n"),
+            Some(Flavor::Classic)
+        );
+        assert_eq!(Flavor::from_product_code("wow_classic_era_ptr"), None);
+        assert_eq!(Flavor::from_product_code("d3"), None);
+        assert_eq!(Flavor::from_product_code(""), None);
+        assert_eq!(Flavor::from_product_code("_retail_"), None);
+    }
+
+    /// Encodes a length delimited protobuf field.
+    fn field(number: u8, value: &[u8]) -> Vec<u8> {
+        assert!(value.len() < 0x80);
+
+        let mut field = vec![number << 3 | 2, value.len() as u8];
+        field.extend_from_slice(value);
+        field
+    }
+
+    /// Encodes a `ProductInstall` of `product.db`.
+    fn product_install(code: &str, install_path: Option<&str>) -> Vec<u8> {
+        // Field 1 is the uid, which matches the product code for games.
+        let mut install = field(1, code.as_bytes());
+        install.extend(field(2, code.as_bytes()));
+
+        let mut settings = vec![];
+        if let Some(path) = install_path {
+            settings.extend(field(1, path.as_bytes()));
+        }
+        settings.extend(field(2, b"EU"));
+        // A varint field, as for the desktop shortcut option.
+        settings.extend(&[3 << 3, 1]);
+        install.extend(field(3, &settings));
+
+        field(1, &install)
+    }
+
+    #[test]
+    fn test_import_from_battlenet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("product.db");
+
+        // A missing file gives no directories.
+        assert!(Wow::import_from_battlenet(&path).unwrap().is_empty());
+
+        let root = "/games/World of Warcraft";
+        let installs = [
+            product_install("agent", Some("/games/Battle.net")),
+            product_install("wow", Some(root)),
+            product_install("wowt", Some("/games/World of Warcraft/_ptr_")),
+            product_install("wow_classic", Some(root)),
+            product_install("wow_classic_era", None),
+            product_install("d3", Some("/games/Diablo III")),
+        ];
+        let mut db = installs.concat();
+        // Other `Database` fields, eg. the active installs.
+        db.extend(field(2, &field(1, b"wow")));
+        fs::write(&path, &db).unwrap();
+
+        let root = PathBuf::from(root);
+        let mut expected = HashMap::new();
+        expected.insert(Flavor::Retail, root.join("_retail_"));
+        expected.insert(Flavor::RetailPTR, root.join("_ptr_"));
+        expected.insert(Flavor::Classic, root.join("_classic_"));
+        assert_eq!(Wow::import_from_battlenet(&path).unwrap(), expected);
+
+        // A truncated file gives the installs before the cut.
+        let cut = installs[0].len() + installs[1].len() + 5;
+        fs::write(&path, &db[..cut]).unwrap();
+        let flavors = Wow::import_from_battlenet(&path).unwrap();
+        assert_eq!(flavors.len(), 1);
+        assert_eq!(flavors[&Flavor::Retail], root.join("_retail_"));
+
+        fs::write(&path, b"not a product db").unwrap();
+        assert!(Wow::import_from_battlenet(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detect_flavors() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("_retail_/Interface/AddOns")).unwrap();
+        fs::create_dir_all(root.path().join("_classic_era_/interface")).unwrap();
+        fs::create_dir_all(root.path().join("_classic_/WTF")).unwrap();
+        fs::create_dir_all(root.path().join("Data")).unwrap();
+
+        let flavors = Wow::detect_flavors(root.path());
+
+        assert_eq!(flavors.len(), 2);
+        assert_eq!(flavors[&Flavor::Retail], root.path().join("_retail_"));
+        assert_eq!(
+            flavors[&Flavor::ClassicEra],
+            root.path().join("_classic_era_")
+        );
+    }
+
+    #[test]
+    fn test_add_detected_flavors() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("_retail_/Interface")).unwrap();
+        fs::create_dir_all(root.path().join("_classic_/Interface")).unwrap();
+
+        let custom = PathBuf::from("/custom/_retail_");
+        let mut wow = Wow::default();
+        wow.directories.insert(Flavor::Retail, custom.clone());
+
+        assert_eq!(
+            wow.add_detected_flavors(root.path(), false),
+            vec![Flavor::Classic]
+        );
+        assert_eq!(wow.directories[&Flavor::Retail], custom);
+
+        assert_eq!(
+            wow.add_detected_flavors(root.path(), true),
+            vec![Flavor::Retail, Flavor::Classic]
+        );
+        assert_eq!(
+            wow.directories[&Flavor::Retail],
+            root.path().join("_retail_")
+        );
     }
 }
@@ -1,3 +1,5 @@
+use crate::backup::BackupKind;
+use crate::config::Flavor;
 use crate::repository::ReleaseChannel;
 
 use std::path::PathBuf;
@@ -9,11 +11,27 @@ pub enum FilesystemError {
     #[error(transparent)]
     SerdeYaml(#[from] serde_yaml::Error),
     #[error(transparent)]
+    TomlSer(#[from] toml::ser::Error),
+    #[error(transparent)]
+    TomlDe(#[from] toml::de::Error),
+    #[error(transparent)]
     Zip(#[from] zip::result::ZipError),
     #[error(transparent)]
     WalkDir(#[from] walkdir::Error),
     #[error("File doesn't exist: {path:?}")]
     FileDoesntExist { path: PathBuf },
+    #[error("No directory set for {flavor}")]
+    NoFlavorDirectory { flavor: Flavor },
+    #[error("Failed to create directory {path:?}: {source}")]
+    CreateDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to read {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
     #[cfg(target_os = "macos")]
     #[error("Could not file bin name {bin_name} in archive")]
     BinMissingFromTar { bin_name: String },
@@ -21,6 +39,36 @@ pub enum FilesystemError {
     NormalizingPathSlash { path: PathBuf },
     #[error("Could not strip prefix {prefix:?} from {from:?}")]
     StripPrefix { prefix: String, from: String },
+    #[error("Can't restore a {kind:?} backup into {target:?}")]
+    RestoreKindMismatch { kind: BackupKind, target: PathBuf },
+    #[error("Refusing to clean {path:?}, it isn't a Logs directory of a flavor")]
+    NotLogsDirectory { path: PathBuf },
+    #[error("Not saving {path:?}, as it's read-only")]
+    ReadOnly { path: PathBuf },
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum DirectoryIssue {
+    #[error("Directory for {flavor} doesn't exist")]
+    MissingRoot { flavor: Flavor },
+    #[error("AddOns directory for {flavor} doesn't exist")]
+    MissingAddonDir { flavor: Flavor },
+    #[error("Directory for {flavor} is not writable: {path:?}")]
+    NotWritable { flavor: Flavor, path: PathBuf },
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum DirectoryError {
+    #[error("Directory doesn't exist: {path:?}")]
+    DoesntExist { path: PathBuf },
+    #[error("Directory is not a {flavor} directory: {path:?}")]
+    NotFlavorDirectory { flavor: Flavor, path: PathBuf },
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ThemeError {
+    #[error("Theme {name} doesn't exist")]
+    UnknownTheme { name: String },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -47,6 +95,10 @@ pub enum DownloadError {
     MissingSelfUpdateRelease { bin_name: String },
     #[error("Catalog failed to download")]
     CatalogFailed,
+    #[error("Invalid catalog url {url}")]
+    InvalidCatalogUrl { url: String },
+    #[error("Invalid self update url {url}")]
+    InvalidSelfUpdateUrl { url: String },
     #[error("Self update for linux only works from AppImage")]
     SelfUpdateLinuxNonAppImage,
     #[error(transparent)]
@@ -61,6 +113,19 @@ pub enum DownloadError {
     Filesystem(#[from] FilesystemError),
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum CatalogError {
+    #[error("Invalid catalog url {url}")]
+    InvalidUrl { url: String },
+    #[error("Invalid status code {code} for catalog {url}")]
+    InvalidStatusCode {
+        code: isahc::http::StatusCode,
+        url: String,
+    },
+    #[error(transparent)]
+    Download(#[from] DownloadError),
+}
+
 impl From<std::io::Error> for DownloadError {
     fn from(e: std::io::Error) -> Self {
         DownloadError::Filesystem(FilesystemError::IO(e))
@@ -1,6 +1,7 @@
 use super::Result;
 use crate::{
     addon::{Addon, AddonFolder},
+    cache::unmark_in_flight,
     parse::parse_toc_path,
 };
 use std::collections::HashSet;
@@ -61,7 +62,16 @@ pub async fn install_addon(
     to_directory: &Path,
 ) -> Result<Vec<AddonFolder>> {
     let zip_path = from_directory.join(&addon.primary_folder_id);
-    let mut zip_file = std::fs::File::open(&zip_path)?;
+    let installed = unzip_addon(addon, &zip_path, to_directory);
+
+    // The archive is done with, whether it could be installed or not.
+    unmark_in_flight(&zip_path);
+
+    installed
+}
+
+fn unzip_addon(addon: &Addon, zip_path: &Path, to_directory: &Path) -> Result<Vec<AddonFolder>> {
+    let mut zip_file = std::fs::File::open(zip_path)?;
     let mut archive = zip::ZipArchive::new(&mut zip_file)?;
 
     // Remove all existing top level addon folders.
@@ -116,7 +126,7 @@ pub async fn install_addon(
     }
 
     // Cleanup
-    std::fs::remove_file(&zip_path)?;
+    std::fs::remove_file(zip_path)?;
 
     let mut addon_folders: Vec<_> = toc_files
         .iter()
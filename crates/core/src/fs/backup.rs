@@ -1,14 +1,41 @@
 use super::Result;
 use crate::backup::BackupFolder;
 use crate::error::FilesystemError;
+use crate::sha256::copy_sha256_hex;
 
+use glob::{MatchOptions, Pattern};
 use path_slash::PathExt;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{BufWriter, Read, Write};
+use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 use zip::{write::FileOptions, CompressionMethod, ZipWriter};
 
+/// Name of the manifest inside each archive.
+pub(crate) const MANIFEST_NAME: &str = "manifest.json";
+
+/// Deflate level used if `backup_compression_level` isn't set.
+pub(crate) const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+
+/// Highest deflate level, which gives the smallest archives.
+pub(crate) const MAX_COMPRESSION_LEVEL: u32 = 9;
+
+/// Lists the files inside an archive together with their checksum, so the
+/// archive can be verified later on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Manifest {
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ManifestEntry {
+    /// Name of the file inside the archive.
+    pub path: String,
+    /// Lowercase hex SHA-256 of the file contents.
+    pub sha256: String,
+}
+
 /// A trait defining a way to back things up to the fs
 pub trait Backup {
     fn backup(&self) -> Result<()>;
@@ -18,6 +45,25 @@ pub trait Backup {
 pub struct ZipBackup {
     src: Vec<BackupFolder>,
     dest: PathBuf,
+    compression_level: Option<u32>,
+    exclude_patterns: Vec<Pattern>,
+}
+
+/// Options for the entries of an archive, which are turned into the
+/// `FileOptions` of the zip writer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EntryOptions {
+    /// Deflate level, from 0 to `MAX_COMPRESSION_LEVEL`.
+    compression_level: u32,
+}
+
+impl EntryOptions {
+    fn file_options(self) -> FileOptions {
+        FileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .compression_level(Some(self.compression_level as i32))
+            .unix_permissions(0o755)
+    }
 }
 
 impl ZipBackup {
@@ -25,51 +71,157 @@ impl ZipBackup {
         ZipBackup {
             src,
             dest: dest.as_ref().to_owned(),
+            compression_level: None,
+            exclude_patterns: vec![],
         }
     }
-}
 
-impl Backup for ZipBackup {
-    fn backup(&self) -> Result<()> {
+    /// Skips entries matching any of the glob `patterns`, relative to the
+    /// prefix of their `BackupFolder`. Invalid patterns are logged and ignored.
+    pub(crate) fn exclude_patterns(mut self, patterns: &[String]) -> ZipBackup {
+        self.exclude_patterns = patterns
+            .iter()
+            .filter_map(|pattern| match Pattern::new(pattern) {
+                Ok(pattern) => Some(pattern),
+                Err(error) => {
+                    log::warn!("ignoring backup exclude pattern {:?}: {}", pattern, error);
+                    None
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Sets the deflate level of archive entries, from 0 to
+    /// `MAX_COMPRESSION_LEVEL`. If `None`, `DEFAULT_COMPRESSION_LEVEL` is used.
+    pub(crate) fn compression_level(mut self, level: Option<u32>) -> ZipBackup {
+        self.compression_level = level;
+        self
+    }
+
+    /// Returns the options for archive entries. An out of range compression
+    /// level is clamped.
+    fn entry_options(&self) -> EntryOptions {
+        let level = self.compression_level.unwrap_or(DEFAULT_COMPRESSION_LEVEL);
+
+        if level > MAX_COMPRESSION_LEVEL {
+            log::warn!(
+                "backup compression level {} is out of range, using {} instead",
+                level,
+                MAX_COMPRESSION_LEVEL
+            );
+        }
+
+        EntryOptions {
+            compression_level: level.min(MAX_COMPRESSION_LEVEL),
+        }
+    }
+
+    /// Like `Backup::backup()`, but turns the entry options into `FileOptions`
+    /// with `file_options`.
+    fn backup_with(&self, file_options: impl FnOnce(EntryOptions) -> FileOptions) -> Result<()> {
         let output = BufWriter::new(File::create(&self.dest)?);
 
         let mut zip_writer = ZipWriter::new(output);
-        let options = FileOptions::default()
-            .compression_method(CompressionMethod::Deflated)
-            .unix_permissions(0o755);
+        let options = file_options(self.entry_options());
+
+        let mut manifest = Manifest::default();
+
+        for (folder, path) in self.entries() {
+            let entry = zip_write(
+                &path,
+                &folder.prefix,
+                folder.archive_dir.as_deref(),
+                &mut zip_writer,
+                options,
+            )?;
+
+            manifest.files.extend(entry);
+        }
+
+        zip_writer.start_file(MANIFEST_NAME, options)?;
+        serde_json::to_writer_pretty(&mut zip_writer, &manifest).map_err(std::io::Error::from)?;
+
+        zip_writer.finish()?;
+
+        Ok(())
+    }
+
+    /// Returns the paths of all files which `backup` writes to the archive.
+    pub(crate) fn files(&self) -> Vec<PathBuf> {
+        self.entries()
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| !path.is_dir())
+            .collect()
+    }
 
-        let mut buffer = vec![];
+    /// Returns every path to write to the archive, together with the folder
+    /// it belongs to.
+    fn entries(&self) -> Vec<(&BackupFolder, PathBuf)> {
+        let mut entries = vec![];
 
         for folder in &self.src {
             let prefix = &folder.prefix;
-            let path = &folder.path;
 
-            zip_write(path, prefix, &mut buffer, &mut zip_writer, options)?;
+            // Root is written first. This also lets `path` be a single file.
+            entries.push((folder, folder.path.clone()));
 
-            for entry in WalkDir::new(path)
+            let is_excluded = |path: &Path| {
+                path.strip_prefix(prefix)
+                    .is_ok_and(|path| should_exclude(path, &self.exclude_patterns))
+            };
+
+            // Excluded directories aren't descended into.
+            for entry in WalkDir::new(&folder.path)
+                .min_depth(1)
                 .into_iter()
+                .filter_entry(|entry| !is_excluded(entry.path()))
                 .filter_map(std::result::Result::ok)
             {
-                let path = entry.path();
-
-                zip_write(path, prefix, &mut buffer, &mut zip_writer, options)?;
+                entries.push((folder, entry.into_path()));
             }
         }
 
-        zip_writer.finish()?;
+        entries
+    }
+}
 
-        Ok(())
+impl Backup for ZipBackup {
+    fn backup(&self) -> Result<()> {
+        self.backup_with(EntryOptions::file_options)
     }
 }
 
-/// Write each path to the zip archive
+/// Returns `true` if `path` matches any of the glob `patterns`. `path` should
+/// be relative to the root of the backup, eg. `WTF/Account/config-cache.wtf`.
+///
+/// `*` doesn't match across directories, `**` does.
+pub fn should_exclude(path: &Path, patterns: &[Pattern]) -> bool {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+
+    let path = match path.to_slash() {
+        Some(path) => path,
+        None => return false,
+    };
+
+    patterns
+        .iter()
+        .any(|pattern| pattern.matches_with(&path, options))
+}
+
+/// Write each path to the zip archive. Returns the manifest entry if `path` is
+/// a file.
 fn zip_write(
     path: &Path,
     prefix: &Path,
-    buffer: &mut Vec<u8>,
+    archive_dir: Option<&Path>,
     writer: &mut ZipWriter<BufWriter<File>>,
     options: FileOptions,
-) -> Result<()> {
+) -> Result<Option<ManifestEntry>> {
     if !path.exists() {
         return Err(FilesystemError::FileDoesntExist {
             path: path.to_owned(),
@@ -97,17 +249,106 @@ fn zip_write(
         })?
         .trim_start_matches('/');
 
+    // Place entry under `archive_dir` inside the archive, if set
+    let name = match archive_dir {
+        Some(dir) => {
+            let normalized_dir = dir
+                .to_slash()
+                .ok_or(FilesystemError::NormalizingPathSlash {
+                    path: dir.to_path_buf(),
+                })?;
+
+            format!("{}/{}", normalized_dir.trim_end_matches('/'), name)
+        }
+        None => name.to_owned(),
+    };
+
     if path.is_dir() {
-        writer.add_directory(name, options)?;
-    } else {
-        writer.start_file(name, options)?;
+        writer.add_directory(&name, options)?;
 
-        let mut file = File::open(path)?;
-        file.read_to_end(buffer)?;
+        return Ok(None);
+    }
+
+    writer.start_file(&name, options)?;
+
+    let file = File::open(path)?;
+    let sha256 = copy_sha256_hex(file, writer)?;
+
+    Ok(Some(ManifestEntry { path: name, sha256 }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_exclude() {
+        let patterns = vec![
+            Pattern::new("**/*.log").unwrap(),
+            Pattern::new("**/Cache/**").unwrap(),
+        ];
+
+        assert!(should_exclude(Path::new("WTF/Logs/combat.log"), &patterns));
+        assert!(should_exclude(Path::new("debug.log"), &patterns));
+        assert!(should_exclude(Path::new("WTF/Cache/items.wdb"), &patterns));
+        assert!(!should_exclude(
+            Path::new("WTF/Account/FIRST/SavedVariables/Details.lua"),
+            &patterns
+        ));
+        assert!(!should_exclude(Path::new("WTF/Config.wtf"), &[]));
+    }
 
-        writer.write_all(buffer)?;
-        buffer.clear();
+    #[test]
+    fn test_exclude_patterns() {
+        let root = tempfile::tempdir().unwrap();
+        let src = root.path().join("WTF");
+        std::fs::create_dir_all(src.join("Logs")).unwrap();
+        std::fs::write(src.join("Logs/combat.log"), "").unwrap();
+        std::fs::write(src.join("Details.lua"), "").unwrap();
+
+        let dest = root.path().join("backup.zip");
+        ZipBackup::new(vec![BackupFolder::new(&src, root.path())], &dest)
+            .exclude_patterns(&["**/*.log".to_owned(), "[".to_owned()])
+            .backup()
+            .unwrap();
+
+        let zip = zip::ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+        let names: Vec<_> = zip.file_names().collect();
+
+        assert!(names.contains(&"WTF/Details.lua"));
+        assert!(!names.contains(&"WTF/Logs/combat.log"));
     }
 
-    Ok(())
+    #[test]
+    fn test_compression_level() {
+        let root = tempfile::tempdir().unwrap();
+        let src = root.path().join("WTF");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("Config.wtf"), "SET foo \"bar\"\n".repeat(100)).unwrap();
+
+        let level = |level| {
+            let dest = root.path().join("backup.zip");
+            let mut options = None;
+
+            ZipBackup::new(vec![BackupFolder::new(&src, root.path())], &dest)
+                .compression_level(level)
+                .backup_with(|entry_options| {
+                    options = Some(entry_options);
+                    entry_options.file_options()
+                })
+                .unwrap();
+
+            options.unwrap().compression_level
+        };
+
+        assert_eq!(level(None), DEFAULT_COMPRESSION_LEVEL);
+        assert_eq!(level(Some(0)), 0);
+        assert_eq!(level(Some(9)), 9);
+        assert_eq!(level(Some(42)), MAX_COMPRESSION_LEVEL);
+
+        let mut zip =
+            zip::ZipArchive::new(File::open(root.path().join("backup.zip")).unwrap()).unwrap();
+        let entry = zip.by_name("WTF/Config.wtf").unwrap();
+        assert_eq!(entry.compression(), CompressionMethod::Deflated);
+    }
 }
@@ -4,7 +4,7 @@ use once_cell::sync::Lazy;
 #[cfg(not(windows))]
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 mod addon;
@@ -12,11 +12,15 @@ pub mod backup;
 mod save;
 #[cfg(feature = "gui")]
 mod theme;
+mod watch;
 
 pub use addon::{delete_addons, delete_saved_variables, install_addon};
-pub use save::PersistentData;
+pub(crate) use save::deserialize;
+pub use save::{PersistentData, Tracked};
 #[cfg(feature = "gui")]
 pub use theme::load_user_themes;
+pub(crate) use watch::record_own_write;
+pub use watch::{watch_file, FileWatcher};
 
 pub static CONFIG_DIR: Lazy<Mutex<PathBuf>> = Lazy::new(|| {
     // Returns the location of the config directory. Will create if it doesn't
@@ -45,8 +49,13 @@ pub static CONFIG_DIR: Lazy<Mutex<PathBuf>> = Lazy::new(|| {
     }
 });
 
+/// Returns the config directory, without creating it.
+pub(crate) fn config_dir_path() -> PathBuf {
+    CONFIG_DIR.lock().unwrap().clone()
+}
+
 pub fn config_dir() -> PathBuf {
-    let config_dir = CONFIG_DIR.lock().unwrap().clone();
+    let config_dir = config_dir_path();
 
     if !config_dir.exists() {
         let _ = fs::create_dir_all(&config_dir);
@@ -55,4 +64,99 @@ pub fn config_dir() -> PathBuf {
     config_dir
 }
 
+/// Creates `path` and all of its missing parents.
+pub(crate) fn create_dir_all(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+
+    fs::create_dir_all(path).map_err(|source| FilesystemError::CreateDir {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Reads the file at `path` into a string.
+pub(crate) fn read_to_string(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+
+    fs::read_to_string(path).map_err(|source| FilesystemError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Runs `f` with `config_dir()` pointing at a new temporary directory, which is
+/// passed to `f`. Tests using this run one at a time.
+#[cfg(test)]
+pub(crate) fn with_temp_config_dir<T>(f: impl FnOnce(&Path) -> T) -> T {
+    static LOCK: Lazy<Mutex<()>> = Lazy::new(Default::default);
+
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    let previous = std::mem::replace(
+        &mut *CONFIG_DIR.lock().unwrap(),
+        temp_dir.path().to_path_buf(),
+    );
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(temp_dir.path())));
+    *CONFIG_DIR.lock().unwrap() = previous;
+
+    match result {
+        Ok(result) => result,
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
+
 type Result<T, E = FilesystemError> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_dir_all_read_only_parent() {
+        let root = tempfile::tempdir().unwrap();
+        let parent = root.path().join("parent");
+        fs::create_dir(&parent).unwrap();
+
+        let original = fs::metadata(&parent).unwrap().permissions();
+        let mut permissions = original.clone();
+        permissions.set_readonly(true);
+        fs::set_permissions(&parent, permissions).unwrap();
+
+        let path = parent.join("child");
+        let result = create_dir_all(&path);
+
+        fs::set_permissions(&parent, original).unwrap();
+
+        // Privileged users can write regardless of permissions.
+        if result.is_ok() {
+            return;
+        }
+
+        match result {
+            Err(FilesystemError::CreateDir {
+                path: error_path, ..
+            }) => {
+                assert_eq!(error_path, path)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_to_string_missing() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("missing.yml");
+
+        match read_to_string(&path) {
+            Err(FilesystemError::Read {
+                path: error_path,
+                source,
+            }) => {
+                assert_eq!(error_path, path);
+                assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
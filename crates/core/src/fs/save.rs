@@ -1,8 +1,14 @@
-use super::{config_dir, FilesystemError, Result};
+use super::{
+    config_dir, config_dir_path, create_dir_all, record_own_write, FilesystemError, Result,
+};
+use crate::utility::rename;
+use chrono::Local;
 use serde::{de::DeserializeOwned, Serialize};
 
+use std::ffi::OsStr;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 /// Defines a serializable struct that should persist on the filesystem inside the
 /// Ajour config directory.
@@ -28,9 +34,7 @@ pub trait PersistentData: DeserializeOwned + Serialize {
         let path = Self::path()?;
 
         if path.exists() {
-            let file = fs::File::open(&path)?;
-
-            Ok(serde_yaml::from_reader(&file)?)
+            deserialize(&path, &fs::read_to_string(&path)?)
         } else {
             Err(FilesystemError::FileDoesntExist { path })
         }
@@ -47,13 +51,233 @@ pub trait PersistentData: DeserializeOwned + Serialize {
         }
     }
 
-    /// Save to `PersistentData::path()`
-    fn save(&self) -> Result<()> {
-        let contents = serde_yaml::to_string(&self)?;
+    /// Load from `PersistentData::path()`. If the file can't be parsed, it's renamed to
+    /// `<file>.corrupt-<timestamp>` so it isn't lost, and `Default` is saved and returned.
+    fn load_or_default_with_backup<T: PersistentData + Default>() -> Result<T> {
+        let path = <T as PersistentData>::path()?;
+
+        match load_or_backup_corrupt(&path)? {
+            Some(deser) => Ok(deser),
+            None => Ok(get_default_and_save()?),
+        }
+    }
+
+    /// Returns the full file path to save to, without creating any directories.
+    /// Defaults to `PersistentData::relative_path()` inside the config folder.
+    fn save_path(&self) -> Result<PathBuf> {
+        Ok(config_dir_path().join(Self::relative_path()))
+    }
+
+    /// Returns `true` if saving is disabled, in which case `PersistentData::save()`
+    /// fails with `FilesystemError::ReadOnly`. Defaults to `false`.
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
+    /// Save to `PersistentData::save_path()` and return that path. The file is
+    /// replaced atomically, so an interrupted save never leaves a partially
+    /// written file behind.
+    ///
+    /// If `PersistentData::is_read_only()`, nothing is written or created and
+    /// `FilesystemError::ReadOnly` is returned.
+    fn save(&self) -> Result<PathBuf> {
+        let path = self.save_path()?;
+
+        if self.is_read_only() {
+            return Err(FilesystemError::ReadOnly { path });
+        }
+
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        let contents = serialize(&path, self)?;
+        write_atomic(&path, contents.as_bytes())?;
+        record_own_write(&path, contents.as_bytes());
+
+        Ok(path)
+    }
+}
+
+/// Wraps a `PersistentData` value together with the state it last had on disk,
+/// so saving can be skipped when nothing changed.
+#[derive(Debug, Clone, Default)]
+pub struct Tracked<T> {
+    value: T,
+    saved: Option<T>,
+}
+
+impl<T: PersistentData + PartialEq + Clone> Tracked<T> {
+    /// Wraps `value`, which hasn't been saved yet and is therefore dirty.
+    pub fn new(value: T) -> Self {
+        Tracked { value, saved: None }
+    }
+
+    /// Wraps `value`, which is identical to what's on disk, eg. because it was
+    /// just loaded.
+    pub fn from_saved(value: T) -> Self {
+        Tracked {
+            saved: Some(value.clone()),
+            value,
+        }
+    }
+
+    /// Returns `true` if the value differs from what was last saved.
+    pub fn is_dirty(&self) -> bool {
+        self.saved.as_ref() != Some(&self.value)
+    }
+
+    /// Marks the value as dirty, so the next `Tracked::save_if_dirty()` writes
+    /// it regardless.
+    pub fn touch(&mut self) {
+        self.saved = None;
+    }
+
+    /// Saves the value with `PersistentData::save()` if it's dirty. Returns the
+    /// written path, or `None` if nothing was written, eg. because the value is
+    /// read-only.
+    pub fn save_if_dirty(&mut self) -> Result<Option<PathBuf>> {
+        self.save_if_dirty_with(PersistentData::save)
+    }
+
+    /// Like `Tracked::save_if_dirty()`, but writes with `write`.
+    fn save_if_dirty_with(
+        &mut self,
+        write: impl FnOnce(&T) -> Result<PathBuf>,
+    ) -> Result<Option<PathBuf>> {
+        if !self.is_dirty() {
+            return Ok(None);
+        }
+
+        let path = match write(&self.value) {
+            Ok(path) => path,
+            Err(FilesystemError::ReadOnly { path }) => {
+                log::debug!("not saving {:?}, as it's read-only", path);
+
+                return Ok(None);
+            }
+            Err(error) => return Err(error),
+        };
+        self.saved = Some(self.value.clone());
+
+        Ok(Some(path))
+    }
+}
+
+impl<T> std::ops::Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> std::ops::DerefMut for Tracked<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+/// Serializes `data` as TOML if `path` has a `.toml` extension, otherwise as YAML.
+pub(crate) fn serialize<T: Serialize + ?Sized>(path: &Path, data: &T) -> Result<String> {
+    if !is_toml(path) {
+        return Ok(serde_yaml::to_string(data)?);
+    }
+
+    // TOML has no null and doesn't support every shape serde can produce, eg.
+    // struct variants, so the YAML representation is converted instead.
+    let value = without_nulls(serde_yaml::to_value(data)?);
+
+    Ok(toml::to_string(&toml::Value::try_from(value)?)?)
+}
+
+/// Deserializes `contents` as TOML if `path` has a `.toml` extension, otherwise as
+/// YAML.
+pub(crate) fn deserialize<T: DeserializeOwned>(path: &Path, contents: &str) -> Result<T> {
+    if !is_toml(path) {
+        return Ok(serde_yaml::from_str(contents)?);
+    }
+
+    let value: toml::Value = toml::from_str(contents)?;
+
+    Ok(serde_yaml::from_value(serde_yaml::to_value(value)?)?)
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension() == Some(OsStr::new("toml"))
+}
+
+/// Removes null values from all mappings in `value`.
+fn without_nulls(value: serde_yaml::Value) -> serde_yaml::Value {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => serde_yaml::Value::Mapping(
+            mapping
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        serde_yaml::Value::Sequence(sequence) => {
+            serde_yaml::Value::Sequence(sequence.into_iter().map(without_nulls).collect())
+        }
+        value => value,
+    }
+}
+
+/// Writes `contents` to a `.tmp` sibling of `path`, flushes it to disk and then
+/// renames it over `path`.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()
+    };
+
+    // `rename` replaces an existing file on Windows as well, and retries if the
+    // file is briefly locked by eg. a virus scanner.
+    let result = write_tmp().and_then(|_| rename(&tmp_path, path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    Ok(result?)
+}
+
+/// Load from `path`. Returns `None` if the file doesn't exist, or if it couldn't be
+/// parsed, in which case it's moved aside first.
+fn load_or_backup_corrupt<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
 
-        fs::write(Self::path()?, contents)?;
+    let contents = fs::read_to_string(path)?;
 
-        Ok(())
+    match deserialize(path, &contents) {
+        Ok(deser) => Ok(Some(deser)),
+        Err(error) => {
+            let mut backup_name = path.file_name().unwrap_or_default().to_os_string();
+            backup_name.push(format!(
+                ".corrupt-{}",
+                Local::now().format("%Y-%m-%d_%H-%M-%S")
+            ));
+            let backup_path = path.with_file_name(backup_name);
+
+            fs::rename(path, &backup_path)?;
+
+            log::error!(
+                "failed to parse {:?}: {}. Moved it to {:?}",
+                path,
+                error,
+                backup_path
+            );
+
+            Ok(None)
+        }
     }
 }
 
@@ -65,3 +289,127 @@ fn get_default_and_save<T: PersistentData + Default>() -> Result<T> {
 
     Ok(data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, ConfigFormat, Flavor};
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_tracked_save_if_dirty() {
+        let mut writes = 0;
+        let mut write = |_: &Config| {
+            writes += 1;
+            Ok(PathBuf::from("ajour.yml"))
+        };
+
+        let mut config = Tracked::from_saved(Config::default());
+        assert!(!config.is_dirty());
+        assert_eq!(config.save_if_dirty_with(&mut write).unwrap(), None);
+
+        config.hide_ignored_addons = !config.hide_ignored_addons;
+        assert!(config.is_dirty());
+        assert_eq!(
+            config.save_if_dirty_with(&mut write).unwrap(),
+            Some(PathBuf::from("ajour.yml"))
+        );
+        assert!(!config.is_dirty());
+        assert_eq!(config.save_if_dirty_with(&mut write).unwrap(), None);
+
+        config.touch();
+        assert!(config.is_dirty());
+        config.save_if_dirty_with(&mut write).unwrap();
+
+        let mut fresh = Tracked::new(Config::default());
+        assert!(fresh.is_dirty());
+        fresh.save_if_dirty_with(&mut write).unwrap();
+
+        assert_eq!(writes, 3);
+
+        // A read-only value isn't written and stays dirty.
+        let mut read_only = Tracked::new(Config::default());
+        let read_only_write = |_: &Config| {
+            Err(FilesystemError::ReadOnly {
+                path: PathBuf::from("ajour.yml"),
+            })
+        };
+        assert_eq!(read_only.save_if_dirty_with(read_only_write).unwrap(), None);
+        assert!(read_only.is_dirty());
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ajour.yml");
+
+        fs::write(&path, "old: contents\nwhich: are longer\n").unwrap();
+        write_atomic(&path, b"new: contents\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new: contents\n");
+        assert!(!dir.path().join("ajour.yml.tmp").exists());
+
+        write_atomic(&dir.path().join("fresh.yml"), b"fresh: true\n").unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("fresh.yml")).unwrap(),
+            "fresh: true\n"
+        );
+    }
+
+    #[test]
+    fn test_load_or_backup_corrupt() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ajour.yml");
+
+        let missing: Option<HashMap<String, String>> = load_or_backup_corrupt(&path).unwrap();
+        assert!(missing.is_none());
+
+        fs::write(&path, "key: value\n").unwrap();
+        let valid: Option<HashMap<String, String>> = load_or_backup_corrupt(&path).unwrap();
+        assert_eq!(valid.unwrap()["key"], "value");
+
+        fs::write(&path, "key: [value\n").unwrap();
+        let corrupt: Option<HashMap<String, String>> = load_or_backup_corrupt(&path).unwrap();
+        assert!(corrupt.is_none());
+        assert!(!path.exists());
+
+        let backups: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with("ajour.yml.corrupt-"));
+        assert_eq!(
+            fs::read_to_string(dir.path().join(&backups[0])).unwrap(),
+            "key: [value\n"
+        );
+    }
+
+    #[test]
+    fn test_serialize_formats_round_trip() {
+        let mut config = Config::default();
+        config
+            .wow
+            .directories
+            .insert(Flavor::Retail, PathBuf::from("/games/wow/_retail_"));
+        config.theme = Some("Nord".to_string());
+        config.scale = Some(1.5);
+        config.window_size = Some((900, 600));
+        config.config_format = Some(ConfigFormat::Toml);
+        config
+            .addons
+            .ignored
+            .insert(Flavor::Classic, vec!["Foo".to_string()]);
+        config.column_config.migrate_to_v3();
+
+        for format in [ConfigFormat::Yaml, ConfigFormat::Toml].iter() {
+            let path = Path::new(format.file_name());
+            let contents = serialize(path, &config).unwrap();
+
+            let deserialized: Config = deserialize(path, &contents).unwrap();
+            assert_eq!(deserialized, config);
+        }
+    }
+}
@@ -1,7 +1,7 @@
-use super::config_dir;
+use crate::config::Config;
 use crate::theme::Theme;
 
-use async_std::fs::{create_dir_all, read_dir, read_to_string};
+use async_std::fs::{read_dir, read_to_string};
 use async_std::stream::StreamExt;
 
 /// Loads all user defined `.yml` files from the themes
@@ -10,11 +10,7 @@ use async_std::stream::StreamExt;
 pub async fn load_user_themes() -> Vec<Theme> {
     let mut themes = vec![];
 
-    let theme_dir = config_dir().join("themes");
-
-    if !theme_dir.exists() {
-        let _ = create_dir_all(&theme_dir).await;
-    }
+    let theme_dir = Config::theme_directory();
 
     if let Ok(mut dir_entries) = read_dir(theme_dir).await {
         while let Some(entry) = dir_entries.next().await {
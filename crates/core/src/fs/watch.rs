@@ -0,0 +1,143 @@
+use crate::sha256::sha256;
+
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Hash of the contents Ajour itself last wrote, per path.
+static OWN_WRITES: Lazy<Mutex<HashMap<PathBuf, [u8; 32]>>> = Lazy::new(Default::default);
+
+/// Longest time between two checks of a watched file.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Remembers that Ajour wrote `contents` to `path`, so watchers of `path`
+/// don't report it as an external change.
+pub(crate) fn record_own_write(path: &Path, contents: &[u8]) {
+    OWN_WRITES
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), Sha256::digest(contents).into());
+}
+
+fn is_own_write(path: &Path, hash: Option<[u8; 32]>) -> bool {
+    hash.is_some() && OWN_WRITES.lock().unwrap().get(path).copied() == hash
+}
+
+fn content_hash(path: &Path) -> Option<[u8; 32]> {
+    File::open(path).ok().and_then(|file| sha256(file).ok())
+}
+
+/// Watches a file for changes made outside of Ajour. The watch stops when
+/// this is dropped.
+#[derive(Debug)]
+pub struct FileWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Calls `callback` from a background thread whenever the contents of `path`
+/// change on disk.
+///
+/// Changes are reported once the contents have been stable for `debounce`, so
+/// a burst of writes only fires once. Contents written by Ajour through
+/// `PersistentData::save()` aren't reported.
+pub fn watch_file(
+    path: impl AsRef<Path>,
+    debounce: Duration,
+    mut callback: impl FnMut() + Send + 'static,
+) -> FileWatcher {
+    let path = path.as_ref().to_path_buf();
+    let stop = Arc::new(AtomicBool::new(false));
+    let poll_interval = POLL_INTERVAL.min(debounce / 4);
+
+    let handle = {
+        let stop = stop.clone();
+
+        thread::spawn(move || {
+            let mut reported = content_hash(&path);
+            let mut current = reported;
+            let mut changed_at = Instant::now();
+
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(poll_interval);
+
+                let hash = content_hash(&path);
+                if hash != current {
+                    current = hash;
+                    changed_at = Instant::now();
+                }
+
+                if current != reported && changed_at.elapsed() >= debounce {
+                    reported = current;
+
+                    if is_own_write(&path, current) {
+                        log::debug!("ignoring our own write to {:?}", path);
+                    } else {
+                        log::debug!("{:?} changed on disk", path);
+                        callback();
+                    }
+                }
+            }
+        })
+    };
+
+    FileWatcher {
+        stop,
+        handle: Some(handle),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_watch_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ajour.yml");
+        fs::write(&path, "theme: Dark").unwrap();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let watcher = {
+            let calls = calls.clone();
+            watch_file(&path, Duration::from_millis(200), move || {
+                calls.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+
+        // A burst of edits only fires once.
+        for theme in &["Light", "Outrun", "Ferra"] {
+            fs::write(&path, format!("theme: {}", theme)).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        }
+        thread::sleep(Duration::from_millis(600));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Our own writes are ignored.
+        record_own_write(&path, b"theme: Dark");
+        fs::write(&path, "theme: Dark").unwrap();
+        thread::sleep(Duration::from_millis(600));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        drop(watcher);
+    }
+}
@@ -9,6 +9,8 @@ pub mod murmur2;
 pub mod network;
 pub mod parse;
 pub mod repository;
+pub mod sha256;
+pub mod telemetry;
 #[cfg(feature = "gui")]
 pub mod theme;
 pub mod utility;
@@ -1,7 +1,9 @@
 use crate::addon::Addon;
+use crate::cache::{mark_in_flight, unmark_in_flight};
 use crate::error::DownloadError;
 use crate::repository::GlobalReleaseChannel;
 use async_std::{
+    channel::{bounded, Receiver, Sender},
     fs::{create_dir_all, File},
     io::copy,
 };
@@ -51,6 +53,24 @@ pub async fn request_async<T: ToString>(
     Ok(HTTP_CLIENT.send_async(request.body(())?).await?)
 }
 
+/// Sends a HEAD request to `url`, which only returns the headers of the
+/// response.
+pub(crate) async fn head_async<T: ToString>(
+    url: T,
+    timeout: Option<u64>,
+) -> Result<Response<isahc::AsyncBody>, DownloadError> {
+    let mut request = Request::builder()
+        .method("HEAD")
+        .uri(url.to_string())
+        .header("user-agent", &user_agent());
+
+    if let Some(timeout) = timeout {
+        request = request.timeout(std::time::Duration::from_secs(timeout));
+    }
+
+    Ok(HTTP_CLIENT.send_async(request.body(())?).await?)
+}
+
 // Generic function for posting Json data
 pub(crate) async fn post_json_async<T: ToString, D: Serialize>(
     url: T,
@@ -78,6 +98,57 @@ pub(crate) async fn post_json_async<T: ToString, D: Serialize>(
         .await?)
 }
 
+/// Limits how many downloads run at the same time. Clones share the same
+/// limit.
+#[derive(Debug, Clone)]
+pub struct DownloadLimiter {
+    limit: usize,
+    sender: Sender<()>,
+    receiver: Receiver<()>,
+}
+
+impl DownloadLimiter {
+    /// Returns a limiter which allows `limit` downloads at the same time, but
+    /// at least one.
+    pub fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        let (sender, receiver) = bounded(limit);
+
+        DownloadLimiter {
+            limit,
+            sender,
+            receiver,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Waits until fewer than `limit` downloads are running. The download
+    /// counts as running until the returned `DownloadPermit` is dropped.
+    pub async fn acquire(&self) -> DownloadPermit {
+        // The channel is never closed, as we hold both ends.
+        let _ = self.sender.send(()).await;
+
+        DownloadPermit {
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+/// A running download of a `DownloadLimiter`.
+#[derive(Debug)]
+pub struct DownloadPermit {
+    receiver: Receiver<()>,
+}
+
+impl Drop for DownloadPermit {
+    fn drop(&mut self) {
+        let _ = self.receiver.try_recv();
+    }
+}
+
 /// Function to download a zip archive for a `Addon`.
 /// Note: Addon needs to have a `remote_url` to the file.
 pub async fn download_addon(
@@ -128,10 +199,21 @@ pub async fn download_addon(
             create_dir_all(to_directory).await?;
         }
 
+        // Stays in flight until `install_addon` is done with it, so clearing
+        // the cache in between doesn't remove it.
         let zip_path = to_directory.join(&addon.primary_folder_id);
-        let mut file = File::create(&zip_path).await?;
+        mark_in_flight(&zip_path);
+
+        let written = async {
+            let mut file = File::create(&zip_path).await?;
+            copy(&mut body, &mut file).await
+        }
+        .await;
 
-        copy(&mut body, &mut file).await?;
+        if let Err(error) = written {
+            unmark_in_flight(&zip_path);
+            return Err(error.into());
+        }
     }
 
     Ok(())
@@ -178,3 +260,27 @@ pub(crate) async fn download_file<T: ToString>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::FutureExt;
+
+    #[test]
+    fn test_download_limiter() {
+        assert_eq!(DownloadLimiter::new(0).limit(), 1);
+
+        let limiter = DownloadLimiter::new(2);
+        assert_eq!(limiter.limit(), 2);
+
+        let first = limiter.acquire().now_or_never().unwrap();
+        let _second = limiter.clone().acquire().now_or_never().unwrap();
+
+        // The limit is reached until a download finishes.
+        assert!(limiter.acquire().now_or_never().is_none());
+
+        drop(first);
+        assert!(limiter.acquire().now_or_never().is_some());
+    }
+}
@@ -36,11 +36,39 @@ pub struct ParsingPatterns {
     pub file_parsing_regex: HashMap<String, (regex::Regex, Regex)>,
 }
 
+/// Progress of scanning the addon folders of a flavor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanProgress {
+    pub flavor: Flavor,
+    /// Number of folders scanned so far.
+    pub scanned: usize,
+    /// Number of folders to scan.
+    pub total: usize,
+}
+
 pub async fn read_addon_directory<P: AsRef<Path>>(
     addon_cache: Option<Arc<Mutex<AddonCache>>>,
     fingerprint_cache: Option<Arc<Mutex<FingerprintCache>>>,
     root_dir: P,
     flavor: Flavor,
+) -> Result<Vec<Addon>, ParseError> {
+    read_addon_directory_with_progress(addon_cache, fingerprint_cache, root_dir, flavor, |_| {})
+        .await
+}
+
+/// Same as `read_addon_directory`, but calls `on_progress` once for every addon
+/// folder after it has been scanned.
+///
+/// `on_progress` is called from the task awaiting the returned future, never
+/// from another thread, so it doesn't have to be `Send`. The future is only
+/// `Send` if `on_progress` is, which is required to run it on a multi-threaded
+/// executor.
+pub async fn read_addon_directory_with_progress<P: AsRef<Path>>(
+    addon_cache: Option<Arc<Mutex<AddonCache>>>,
+    fingerprint_cache: Option<Arc<Mutex<FingerprintCache>>>,
+    root_dir: P,
+    flavor: Flavor,
+    on_progress: impl FnMut(ScanProgress),
 ) -> Result<Vec<Addon>, ParseError> {
     log::debug!("{} - parsing addons folder", flavor);
 
@@ -111,7 +139,8 @@ pub async fn read_addon_directory<P: AsRef<Path>>(
     }
 
     // Get from cache / calculate fingerprints for all directories
-    let fingerprints = fingerprint_all_dirs(root_dir, flavor, &all_dirs, fingerprint_cache).await?;
+    let fingerprints =
+        fingerprint_all_dirs(root_dir, flavor, &all_dirs, fingerprint_cache, on_progress).await?;
 
     // Parse all addon folders from `.toc` file in each directory and assign it's
     // respective fingerprint
@@ -174,6 +203,7 @@ async fn fingerprint_all_dirs(
     flavor: Flavor,
     all_dirs: &[String],
     fingerprint_cache: Option<Arc<Mutex<FingerprintCache>>>,
+    mut on_progress: impl FnMut(ScanProgress),
 ) -> Result<Vec<Fingerprint>, ParseError> {
     let mut fingerprint_cache = if let Some(fingerprint_cache) = fingerprint_cache {
         Some(fingerprint_cache.lock_arc().await)
@@ -184,11 +214,14 @@ async fn fingerprint_all_dirs(
         .as_mut()
         .map(|c| c.get_mut_for_flavor(flavor));
 
-    // Each addon dir mapped to fingerprint struct.
+    // Each addon dir mapped to fingerprint struct. The dirs are fingerprinted in
+    // parallel, one chunk at a time so progress can be reported in between.
     let num_cached = AtomicUsize::new(0);
-    let new_fingerprints: Vec<_> = all_dirs
-        .par_iter() // Easy parallelization
-        .map(|dir_name| {
+    let chunk_size = rayon::current_num_threads().max(1) * 4;
+    let mut new_fingerprints = Vec::with_capacity(all_dirs.len());
+
+    for (idx, chunk) in all_dirs.chunks(chunk_size).enumerate() {
+        let chunk_fingerprints = chunk.par_iter().map(|dir_name| {
             let addon_dir = root_dir.join(dir_name);
             let modified = if let Ok(metadata) = addon_dir.metadata() {
                 metadata.modified().unwrap_or_else(|_| SystemTime::now())
@@ -222,10 +255,24 @@ async fn fingerprint_all_dirs(
                     modified,
                 }
             }
-        })
+        });
+
         // Note: we filter out cases where hashing has failed.
-        .filter(|f| f.hash.is_some())
-        .collect();
+        new_fingerprints.extend(
+            chunk_fingerprints
+                .filter(|f| f.hash.is_some())
+                .collect::<Vec<_>>(),
+        );
+
+        let scanned_before = idx * chunk_size;
+        for scanned in scanned_before + 1..=scanned_before + chunk.len() {
+            on_progress(ScanProgress {
+                flavor,
+                scanned,
+                total: all_dirs.len(),
+            });
+        }
+    }
 
     {
         let num_cached = num_cached.load(Ordering::Relaxed);
@@ -1120,6 +1167,36 @@ fn split_dependencies_into_vec(value: &str) -> Vec<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint_progress() {
+        let root = tempfile::tempdir().unwrap();
+        let all_dirs = (0..50)
+            .map(|idx| format!("Addon{}", idx))
+            .collect::<Vec<_>>();
+        for dir in all_dirs.iter() {
+            let addon_dir = root.path().join(dir);
+            std::fs::create_dir(&addon_dir).unwrap();
+            std::fs::write(addon_dir.join(format!("{}.toc", dir)), "## Title: Test\n").unwrap();
+        }
+
+        let mut progress = vec![];
+        async_std::task::block_on(fingerprint_all_dirs(
+            root.path(),
+            Flavor::Retail,
+            &all_dirs,
+            None,
+            |p| progress.push(p),
+        ))
+        .unwrap();
+
+        assert_eq!(progress.len(), all_dirs.len());
+        for (idx, p) in progress.iter().enumerate() {
+            assert_eq!(p.flavor, Flavor::Retail);
+            assert_eq!(p.scanned, idx + 1);
+            assert_eq!(p.total, all_dirs.len());
+        }
+    }
+
     #[test]
     fn test_toc_title() {
         let title = RE_TOC_TITLE.replace_all("Atlas |cFF0099FF[Foobar]|r", "$1");
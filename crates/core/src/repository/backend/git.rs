@@ -54,7 +54,7 @@ pub(crate) async fn batch_fetch_repo_packages(
 }
 
 mod github {
-    use crate::config::Flavor;
+    use crate::config::{BaseFlavor, Flavor};
     use crate::error::RepositoryError;
     use crate::network::request_async;
     use crate::repository::{Backend, ReleaseChannel, RemotePackage, RepositoryMetadata};
@@ -214,14 +214,16 @@ mod github {
             .filter(|a| a.name.to_lowercase().contains("classic"))
             .count();
 
-        if flavor.base_flavor() == Flavor::Retail && num_non_classic > 1
-            || flavor.base_flavor() == Flavor::Classic && num_classic == 0 && num_non_classic > 1
+        if flavor.base_flavor() == BaseFlavor::Retail && num_non_classic > 1
+            || flavor.base_flavor() == BaseFlavor::Classic
+                && num_classic == 0
+                && num_non_classic > 1
         {
             return Err(RepositoryError::GitIndeterminableZip {
                 count: num_non_classic,
                 url: url.to_string(),
             });
-        } else if flavor.base_flavor() == Flavor::Classic && num_classic > 1 {
+        } else if flavor.base_flavor() == BaseFlavor::Classic && num_classic > 1 {
             return Err(RepositoryError::GitIndeterminableZipClassic {
                 count: num_classic,
                 url: url.to_string(),
@@ -232,7 +234,7 @@ mod github {
             .assets
             .iter()
             .find(|a| {
-                if flavor.base_flavor() == Flavor::Retail {
+                if flavor.base_flavor() == BaseFlavor::Retail {
                     a.name.ends_with("zip") && !a.name.to_lowercase().contains("classic")
                 } else if num_classic > 0 {
                     a.name.ends_with("zip") && a.name.to_lowercase().contains("classic")
@@ -279,7 +281,7 @@ mod github {
 }
 
 mod gitlab {
-    use crate::config::Flavor;
+    use crate::config::{BaseFlavor, Flavor};
     use crate::error::RepositoryError;
     use crate::network::request_async;
     use crate::repository::{Backend, ReleaseChannel, RemotePackage, RepositoryMetadata};
@@ -344,8 +346,8 @@ mod gitlab {
                 .filter(|a| a.name.to_lowercase().contains("classic"))
                 .count();
 
-            if self.flavor.base_flavor() == Flavor::Retail && num_non_classic > 1
-                || self.flavor.base_flavor() == Flavor::Classic
+            if self.flavor.base_flavor() == BaseFlavor::Retail && num_non_classic > 1
+                || self.flavor.base_flavor() == BaseFlavor::Classic
                     && num_classic == 0
                     && num_non_classic > 1
             {
@@ -353,7 +355,7 @@ mod gitlab {
                     count: num_non_classic,
                     url: url.clone(),
                 });
-            } else if self.flavor.base_flavor() == Flavor::Classic && num_classic > 1 {
+            } else if self.flavor.base_flavor() == BaseFlavor::Classic && num_classic > 1 {
                 return Err(RepositoryError::GitIndeterminableZipClassic {
                     count: num_classic,
                     url,
@@ -365,7 +367,7 @@ mod gitlab {
                 .links
                 .iter()
                 .find(|a| {
-                    if self.flavor.base_flavor() == Flavor::Retail {
+                    if self.flavor.base_flavor() == BaseFlavor::Retail {
                         a.name.ends_with("zip") && !a.name.to_lowercase().contains("classic")
                     } else if num_classic > 0 {
                         a.name.ends_with("zip") && a.name.to_lowercase().contains("classic")
@@ -1,5 +1,5 @@
 use super::*;
-use crate::config::Flavor;
+use crate::config::{BaseFlavor, Flavor};
 use crate::error::{DownloadError, RepositoryError};
 use crate::network::request_async;
 use crate::repository::{ReleaseChannel, RemotePackage, RepositoryKind, RepositoryPackage};
@@ -56,7 +56,7 @@ impl Backend for Tukui {
                     }
                 }
             }
-            Flavor::Classic | Flavor::ClassicPTR => {}
+            Flavor::Classic | Flavor::ClassicPTR | Flavor::ClassicEra => {}
         }
 
         Ok(None)
@@ -110,11 +110,9 @@ pub(crate) fn metadata_from_tukui_package(package: TukuiPackage) -> RepositoryMe
 
 /// Returns flavor `String` in Tukui format
 fn format_flavor(flavor: &Flavor) -> String {
-    let base_flavor = flavor.base_flavor();
-    match base_flavor {
-        Flavor::Retail => "retail".to_owned(),
-        Flavor::Classic => "classic".to_owned(),
-        _ => panic!(format!("Unknown base flavor {}", base_flavor)),
+    match flavor.base_flavor() {
+        BaseFlavor::Retail => "retail".to_owned(),
+        BaseFlavor::Classic => "classic".to_owned(),
     }
 }
 
@@ -134,7 +132,7 @@ fn changelog_endpoint(id: &str, flavor: &Flavor) -> String {
             "-2" => "https://www.tukui.org/ui/elvui/changelog".to_owned(),
             _ => format!("https://www.tukui.org/addons.php?id={}&changelog", id),
         },
-        Flavor::Classic | Flavor::ClassicPTR => format!(
+        Flavor::Classic | Flavor::ClassicPTR | Flavor::ClassicEra => format!(
             "https://www.tukui.org/classic-addons.php?id={}&changelog",
             id
         ),
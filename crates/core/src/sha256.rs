@@ -0,0 +1,79 @@
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Write};
+
+/// Returns the SHA-256 digest of everything read from `reader` as a
+/// lowercase hex string.
+pub(crate) fn sha256_hex<R: Read>(reader: R) -> io::Result<String> {
+    Ok(to_hex(&sha256(reader)?))
+}
+
+/// Returns the SHA-256 digest of everything read from `reader`.
+pub(crate) fn sha256<R: Read>(mut reader: R) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+
+    Ok(hasher.finalize().into())
+}
+
+/// Copies `reader` into `writer` and returns the SHA-256 digest of the copied
+/// bytes as a lowercase hex string.
+pub(crate) fn copy_sha256_hex<R: Read, W: Write>(
+    mut reader: R,
+    writer: &mut W,
+) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 8 * 1024];
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+
+        writer.write_all(&buffer[..read])?;
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(to_hex(&hasher.finalize()))
+}
+
+fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash() {
+        // Tests known results
+        assert_eq!(
+            sha256_hex(&b""[..]).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256_hex(&b"abc"[..]).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            sha256_hex(&b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"[..]).unwrap(),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn test_copy_hash() {
+        // Longer than the copy buffer, so it's hashed over several reads.
+        let data = vec![b'a'; 20 * 1024];
+        let mut copy = vec![];
+
+        assert_eq!(
+            copy_sha256_hex(&data[..], &mut copy).unwrap(),
+            sha256_hex(&data[..]).unwrap()
+        );
+        assert_eq!(copy, data);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::catalog::Source;
+use crate::config::{Config, Flavor};
+use crate::repository::RepositoryKind;
+
+use serde::Serialize;
+
+/// Anonymous usage events, which are only recorded if the user opted in with
+/// `Config::telemetry_enabled`.
+///
+/// Events only hold enums and counts, never strings from the user's machine,
+/// so paths and usernames can't end up in a payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemetryEvent {
+    AppStarted,
+    AddonsUpdated {
+        flavor: Flavor,
+        count: usize,
+    },
+    AddonUpdateFailed {
+        flavor: Flavor,
+        repository: Option<RepositoryKind>,
+    },
+    CatalogDownloaded {
+        source: CatalogSourceKind,
+    },
+}
+
+/// Catalog source of a `TelemetryEvent`, which leaves out the url of custom
+/// catalogs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogSourceKind {
+    Curse,
+    Tukui,
+    WowI,
+    TownlongYak,
+    Custom,
+    Other,
+}
+
+impl From<&Source> for CatalogSourceKind {
+    fn from(source: &Source) -> Self {
+        match source {
+            Source::Curse => CatalogSourceKind::Curse,
+            Source::Tukui => CatalogSourceKind::Tukui,
+            Source::WowI => CatalogSourceKind::WowI,
+            Source::TownlongYak => CatalogSourceKind::TownlongYak,
+            Source::Custom { .. } => CatalogSourceKind::Custom,
+            Source::Other => CatalogSourceKind::Other,
+        }
+    }
+}
+
+/// What is sent for a `TelemetryEvent`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TelemetryPayload {
+    pub event: &'static str,
+    pub version: &'static str,
+    pub properties: Vec<(&'static str, String)>,
+}
+
+impl TelemetryEvent {
+    pub fn payload(&self) -> TelemetryPayload {
+        let (event, properties) = match self {
+            TelemetryEvent::AppStarted => ("app_started", vec![]),
+            TelemetryEvent::AddonsUpdated { flavor, count } => (
+                "addons_updated",
+                vec![("flavor", flavor.to_string()), ("count", count.to_string())],
+            ),
+            TelemetryEvent::AddonUpdateFailed { flavor, repository } => (
+                "addon_update_failed",
+                vec![
+                    ("flavor", flavor.to_string()),
+                    (
+                        "repository",
+                        repository.map_or_else(|| "none".to_owned(), |kind| kind.to_string()),
+                    ),
+                ],
+            ),
+            TelemetryEvent::CatalogDownloaded { source } => (
+                "catalog_downloaded",
+                vec![("source", format!("{:?}", source))],
+            ),
+        };
+
+        TelemetryPayload {
+            event,
+            version: env!("CARGO_PKG_VERSION"),
+            properties,
+        }
+    }
+}
+
+/// Collects the payloads of recorded events until they're sent.
+#[derive(Debug, Default)]
+pub struct Telemetry {
+    pending: Vec<TelemetryPayload>,
+}
+
+impl Telemetry {
+    /// Records `event`, but only if `Config::telemetry_enabled` is set.
+    /// Returns `true` if the event was recorded.
+    pub fn record_event(&mut self, config: &Config, event: TelemetryEvent) -> bool {
+        if !config.telemetry_enabled {
+            return false;
+        }
+
+        self.pending.push(event.payload());
+        true
+    }
+
+    /// Returns the recorded payloads, leaving none behind.
+    pub fn take_pending(&mut self) -> Vec<TelemetryPayload> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::GitKind;
+
+    fn all_events() -> Vec<TelemetryEvent> {
+        let mut events = vec![TelemetryEvent::AppStarted];
+
+        for flavor in Flavor::ALL.iter().copied() {
+            events.push(TelemetryEvent::AddonsUpdated { flavor, count: 12 });
+
+            for repository in [
+                None,
+                Some(RepositoryKind::Curse),
+                Some(RepositoryKind::Git(GitKind::Github)),
+            ]
+            .iter()
+            .copied()
+            {
+                events.push(TelemetryEvent::AddonUpdateFailed { flavor, repository });
+            }
+        }
+
+        let custom = Source::Custom {
+            url: "file:///home/user/catalog".to_owned(),
+        };
+        for source in [Source::Curse, Source::Tukui, custom].iter() {
+            events.push(TelemetryEvent::CatalogDownloaded {
+                source: source.into(),
+            });
+        }
+
+        events
+    }
+
+    #[test]
+    fn test_no_events_when_disabled() {
+        let mut config = Config::default();
+        let mut telemetry = Telemetry::default();
+
+        for event in all_events() {
+            assert!(!telemetry.record_event(&config, event));
+        }
+        assert!(telemetry.take_pending().is_empty());
+
+        config.telemetry_enabled = true;
+        assert!(telemetry.record_event(&config, TelemetryEvent::AppStarted));
+        assert_eq!(telemetry.take_pending().len(), 1);
+        assert!(telemetry.take_pending().is_empty());
+    }
+
+    #[test]
+    fn test_payloads_have_no_paths() {
+        for event in all_events() {
+            let json = serde_json::to_string(&event.payload()).unwrap();
+
+            assert!(!json.contains('/'), "{}", json);
+            assert!(!json.contains('\\'), "{}", json);
+            assert!(!json.contains("user"), "{}", json);
+        }
+    }
+}
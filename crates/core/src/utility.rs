@@ -58,28 +58,36 @@ pub struct ReleaseAsset {
     pub download_url: String,
 }
 
-pub async fn get_latest_release(channel: SelfUpdateChannel) -> Option<Release> {
+pub async fn get_latest_release(
+    channel: SelfUpdateChannel,
+    url_override: Option<String>,
+) -> Option<Release> {
     log::debug!("checking for application update");
 
-    let mut resp = request_async(
-        "https://api.github.com/repos/ajour/ajour/releases",
-        vec![],
-        None,
-    )
-    .await
-    .ok()?;
+    let url = match channel.releases_url_with_override(url_override.as_deref()) {
+        Ok(url) => url,
+        Err(e) => {
+            log::error!("{}", e);
+            return None;
+        }
+    };
+    if url_override.is_some() {
+        log::info!("checking for application update using mirror {}", url);
+    }
+
+    let mut resp = request_async(url, vec![], None).await.ok()?;
 
     let releases: Vec<Release> = resp.json().await.ok()?;
 
-    releases.into_iter().find(|r| {
-        if channel == SelfUpdateChannel::Beta {
-            // If beta, always want latest release
-            true
-        } else {
-            // Otherwise ONLY non-prereleases
-            !r.prerelease
-        }
-    })
+    releases
+        .into_iter()
+        .filter(|r| channel.accepts_release(r.prerelease))
+        // Skip releases which haven't got an asset for this platform yet
+        .find(|r| {
+            r.assets
+                .iter()
+                .any(|a| channel.matches_current_platform(&a.name))
+        })
 }
 
 /// Downloads the latest release file that matches `bin_name`, renames the current
@@ -230,6 +238,73 @@ pub fn wow_path_resolution(path: Option<PathBuf>) -> Option<PathBuf> {
     None
 }
 
+/// Expands `$VAR` and `${VAR}` tokens in `raw` using the process environment.
+///
+/// Variables which aren't set are left in place and a warning is logged.
+pub fn expand_path(raw: &Path) -> PathBuf {
+    expand_path_with(raw, |name| {
+        log::warn!("environment variable {} in {:?} is not set", name, raw);
+    })
+}
+
+/// Returns the names of the variables in `raw` which `expand_path` can't
+/// expand, as they aren't set.
+pub fn unset_path_variables(raw: &Path) -> Vec<String> {
+    let mut unset = vec![];
+    expand_path_with(raw, |name| unset.push(name.to_owned()));
+
+    unset
+}
+
+/// Expands `raw` like `expand_path`, calling `on_unset` with the name of each
+/// variable which isn't set.
+fn expand_path_with(raw: &Path, mut on_unset: impl FnMut(&str)) -> PathBuf {
+    let raw_str = match raw.to_str() {
+        Some(raw_str) if raw_str.contains('$') => raw_str,
+        _ => return raw.to_path_buf(),
+    };
+
+    let mut expanded = String::with_capacity(raw_str.len());
+    let mut rest = raw_str;
+
+    while let Some(idx) = rest.find('$') {
+        expanded.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let (name, token_len) = if let Some(braced) = rest.strip_prefix("${") {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 3),
+                None => ("", 1),
+            }
+        } else {
+            let len = rest[1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len() - 1);
+            (&rest[1..=len], len + 1)
+        };
+
+        let token = &rest[..token_len];
+
+        if name.is_empty() {
+            expanded.push_str(token);
+        } else {
+            match std::env::var(name) {
+                Ok(value) => expanded.push_str(&value),
+                Err(_) => {
+                    on_unset(name);
+                    expanded.push_str(token);
+                }
+            }
+        }
+
+        rest = &rest[token_len..];
+    }
+
+    expanded.push_str(rest);
+
+    PathBuf::from(expanded)
+}
+
 /// Rename a file or directory to a new name, retrying if the operation fails because of permissions
 ///
 /// Will retry for ~30 seconds with longer and longer delays between each, to allow for virus scan
@@ -345,6 +420,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expand_path() {
+        std::env::set_var("AJOUR_TEST_WOW_HOME", "/games/wow");
+
+        assert_eq!(
+            expand_path(Path::new("$AJOUR_TEST_WOW_HOME/_retail_")),
+            PathBuf::from("/games/wow/_retail_")
+        );
+        assert_eq!(
+            expand_path(Path::new("${AJOUR_TEST_WOW_HOME}_backup/_retail_")),
+            PathBuf::from("/games/wow_backup/_retail_")
+        );
+        assert_eq!(
+            expand_path(Path::new("/games/wow/_retail_")),
+            PathBuf::from("/games/wow/_retail_")
+        );
+        assert_eq!(
+            expand_path(Path::new("/price/$5")),
+            PathBuf::from("/price/$5")
+        );
+        assert_eq!(expand_path(Path::new("/$/${}")), PathBuf::from("/$/${}"));
+    }
+
+    #[test]
+    fn test_expand_path_unset_var() {
+        std::env::remove_var("AJOUR_TEST_UNSET");
+
+        assert_eq!(
+            expand_path(Path::new("$AJOUR_TEST_UNSET/_retail_")),
+            PathBuf::from("$AJOUR_TEST_UNSET/_retail_")
+        );
+        assert_eq!(
+            expand_path(Path::new("${AJOUR_TEST_UNSET}/_retail_")),
+            PathBuf::from("${AJOUR_TEST_UNSET}/_retail_")
+        );
+        assert_eq!(
+            unset_path_variables(Path::new("${AJOUR_TEST_UNSET}/_retail_")),
+            vec!["AJOUR_TEST_UNSET".to_owned()]
+        );
+        assert!(unset_path_variables(Path::new("/games/wow/_retail_")).is_empty());
+        assert_eq!(
+            expand_path(Path::new("${AJOUR_TEST_UNSET/_retail_")),
+            PathBuf::from("${AJOUR_TEST_UNSET/_retail_")
+        );
+    }
+
     #[test]
     fn test_interface() {
         let interface = "90001";
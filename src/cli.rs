@@ -90,7 +90,7 @@ pub enum Command {
     UpdateWeakauras,
     /// Install an addon from the command line
     Install {
-        #[structopt(parse(try_from_str = str_to_flavor), possible_values = &["retail","ptr","beta","classic","classic_ptr"])]
+        #[structopt(parse(try_from_str = str_to_flavor), possible_values = &["retail","ptr","beta","classic","classic_ptr","classic_era"])]
         /// flavor to install addon under
         flavor: Flavor,
         #[structopt()]
@@ -102,12 +102,15 @@ pub enum Command {
         #[structopt(short, long, default_value = "both", parse(try_from_str = str_to_backup_folder), possible_values = &["both","wtf","addons"])]
         /// folder to backup
         backup_folder: BackupFolder,
-        #[structopt(short, long, parse(try_from_str = str_to_flavor), possible_values = &["retail","ptr","beta","classic","classic_ptr"])]
+        #[structopt(short, long, parse(try_from_str = str_to_flavor), possible_values = &["retail","ptr","beta","classic","classic_ptr","classic_era"])]
         /// space separated list of flavors to include in backup. If ommited, all flavors will be included.
         flavors: Vec<Flavor>,
         #[structopt()]
         /// folder to save backups to
         destination: PathBuf,
+        #[structopt(long)]
+        /// list the files which would be backed up, without writing anything
+        dry_run: bool,
     },
 }
 
@@ -118,7 +121,8 @@ fn str_to_flavor(s: &str) -> Result<Flavor, &'static str> {
         "ptr" => Ok(Flavor::RetailPTR),
         "classic" => Ok(Flavor::Classic),
         "classic_ptr" => Ok(Flavor::ClassicPTR),
-        _ => Err("valid values are ['retail','ptr','beta','classic','classic_ptr']"),
+        "classic_era" => Ok(Flavor::ClassicEra),
+        _ => Err("valid values are ['retail','ptr','beta','classic','classic_ptr','classic_era']"),
     }
 }
 
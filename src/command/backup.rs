@@ -1,8 +1,9 @@
 use crate::cli::BackupFolder;
 use crate::Result;
 
-use ajour_core::backup::{self, backup_folders};
+use ajour_core::backup::{self, backup_archives, config_backup_folders, BackupKind, BackupTag};
 use ajour_core::config::{load_config, Flavor};
+use ajour_core::fs::config_dir;
 use anyhow::format_err;
 
 use async_std::task;
@@ -13,6 +14,7 @@ pub fn backup(
     backup_folder: BackupFolder,
     destination: PathBuf,
     flavors: Vec<Flavor>,
+    dry_run: bool,
 ) -> Result<()> {
     task::block_on(async {
         let config = load_config().await?;
@@ -23,11 +25,11 @@ pub fn backup(
             flavors
         };
 
-        if !destination.exists() {
+        if !destination.exists() && !dry_run {
             create_dir(destination.clone())?;
         }
 
-        if !destination.is_dir() {
+        if destination.exists() && !destination.is_dir() {
             return Err(format_err!("destination must be a folder, not a file"));
         }
 
@@ -42,16 +44,24 @@ pub fn backup(
             destination
         );
 
-        let mut src_folders = vec![];
+        let kind = match backup_folder {
+            BackupFolder::Both => None,
+            BackupFolder::AddOns => Some(BackupKind::Addons),
+            BackupFolder::WTF => Some(BackupKind::Wtf),
+        };
+
+        let mut archives = vec![];
 
         for flavor in flavors {
             let wow_dir = config.get_root_directory_for_flavor(&flavor).ok_or_else(|| format_err!("No WoW directories set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
-            let addon_directory = config.get_addon_directory_for_flavor(&flavor).ok_or_else(|| format_err!("No WoW directories set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
+            let addon_directory = config.addon_directory_in_flavor(&flavor).ok_or_else(|| format_err!("No WoW directories set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
             let wtf_directory = config.get_wtf_directory_for_flavor(&flavor).ok_or_else(|| format_err!("No WoW directories set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
 
             let addons_folder = backup::BackupFolder::new(&addon_directory, &wow_dir);
             let wtf_folder = backup::BackupFolder::new(&wtf_directory, &wow_dir);
 
+            let mut src_folders = vec![];
+
             match backup_folder {
                 BackupFolder::Both => {
                     if addon_directory.exists() && wtf_directory.exists() {
@@ -70,9 +80,48 @@ pub fn backup(
                     }
                 }
             }
+
+            let tag = BackupTag {
+                flavor: Some(flavor),
+                kind,
+            };
+            archives.push((tag, src_folders));
+        }
+
+        // Backup Ajour config and themes
+        if config.backup_config {
+            let tag = BackupTag {
+                flavor: None,
+                kind: Some(BackupKind::Config),
+            };
+            archives.push((tag, config_backup_folders(&config_dir())));
+        }
+
+        if dry_run {
+            let plan = backup::plan_backup(
+                archives
+                    .into_iter()
+                    .flat_map(|(_, folders)| folders)
+                    .collect(),
+                &destination,
+                &config.backup_exclude_patterns,
+                None,
+                config.backup_filename_pattern.as_deref(),
+            );
+
+            log::info!("Dry run, nothing was written:\n{}", plan);
+
+            return Ok(());
         }
 
-        backup_folders(src_folders, destination).await?;
+        backup_archives(
+            archives,
+            destination,
+            config.backup_compression_level,
+            config.backup_exclude_patterns.clone(),
+            config.backup_filename_pattern.clone(),
+        )
+        .await?;
 
         log::info!("Backup complete!");
 
@@ -10,7 +10,7 @@ use ajour_core::cache::{
 };
 use ajour_core::config::{load_config, Flavor};
 use ajour_core::fs::install_addon;
-use ajour_core::network::download_addon;
+use ajour_core::network::{download_addon, DownloadLimiter};
 use ajour_core::parse::{read_addon_directory, update_addon_fingerprint};
 use ajour_core::repository::{GlobalReleaseChannel, RepositoryKind};
 
@@ -40,7 +40,7 @@ pub fn update_all_addons() -> Result<()> {
         // Update addons for both flavors
         for flavor in Flavor::ALL.iter() {
             // Only returns None if the path isn't set in the config
-            let addon_directory = config.get_addon_directory_for_flavor(flavor).ok_or_else(|| format_err!("No WoW directory set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
+            let addon_directory = config.resolve_addon_directory(flavor).ok_or_else(|| format_err!("No WoW directory set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
 
             if let Ok(addons) = read_addon_directory(
                 Some(addon_cache.clone()),
@@ -58,19 +58,14 @@ pub fn update_all_addons() -> Result<()> {
                     .cloned()
                     .unwrap_or_default();
 
-                // Get any ingnored addons from the config
-                let ignored_ids = config
-                    .addons
-                    .ignored
-                    .get(flavor)
-                    .cloned()
-                    .unwrap_or_default();
+                // Filter out any ignored and pinned addons
+                for mut addon in addons.into_iter().filter(|a| {
+                    if let Some(version) = config.pinned_version(flavor, &a.primary_folder_id) {
+                        log::info!("Skipping {}, pinned at {}", a.primary_folder_id, version);
+                    }
 
-                // Filter out any ignored addons
-                for mut addon in addons
-                    .into_iter()
-                    .filter(|a| !ignored_ids.iter().any(|i| i == &a.primary_folder_id))
-                {
+                    !config.should_skip_update(flavor, &a.primary_folder_id)
+                }) {
                     // Apply release channel preference
                     if let Some(channel) = release_channels.get(&addon.primary_folder_id) {
                         addon.release_channel = *channel;
@@ -127,7 +122,14 @@ pub fn update_all_addons() -> Result<()> {
         }
 
         // Call `update_addon` on each addon concurrently
-        for result in join_all(addons_to_update.into_iter().map(update_addon)).await {
+        let download_limiter = config.download_limiter();
+        for result in join_all(
+            addons_to_update
+                .into_iter()
+                .map(|args| update_addon(args, download_limiter.clone())),
+        )
+        .await
+        {
             // Log any errors updating an addon
             if let Err(e) = result {
                 log_error(&e);
@@ -169,9 +171,13 @@ async fn update_addon(
         PathBuf,
         PathBuf,
     ),
+    download_limiter: DownloadLimiter,
 ) -> Result<()> {
     // Download the update to the temp directory
-    download_addon(&addon, global_release_channel, &temp_directory).await?;
+    {
+        let _permit = download_limiter.acquire().await;
+        download_addon(&addon, global_release_channel, &temp_directory).await?;
+    }
 
     // Extracts addon from the downloaded archive to the addon directory and removes the archive
     let installed_folders = install_addon(&addon, &temp_directory, &addon_directory).await?;
@@ -15,13 +15,16 @@ pub fn update_all_weakauras() -> Result<()> {
         let mut flavors_setup = 0usize;
 
         for flavor in &Flavor::ALL[..] {
-            if let Some(account) = config.weak_auras_account.get(flavor).cloned() {
+            let accounts = config.weak_auras_accounts(flavor);
+            if !accounts.is_empty() {
                 flavors_setup += 1;
+            }
 
+            for account in accounts {
                 log::info!("{} - Parsing WeakAuras for account {}", flavor, &account);
 
                 let wtf_path = config.get_wtf_directory_for_flavor(flavor).ok_or_else(|| format_err!("No WoW directory set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
-                let addon_dir = config.get_addon_directory_for_flavor(flavor).ok_or_else(|| format_err!("No WoW directory set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
+                let addon_dir = config.resolve_addon_directory(flavor).ok_or_else(|| format_err!("No WoW directory set. Launch Ajour and make sure a WoW directory is set before using the command line."))?;
 
                 let auras = parse_auras(wtf_path, account.clone())
                     .await
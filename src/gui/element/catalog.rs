@@ -119,7 +119,7 @@ pub fn data_row_container<'a, 'b>(
     let flavor_exists_for_addon = addon_data
         .game_versions
         .iter()
-        .any(|gc| gc.flavor == config.wow.flavor.base_flavor());
+        .any(|gc| gc.flavor.base_flavor() == config.wow.flavor.base_flavor());
 
     if let Some((idx, width)) = column_config
         .iter()
@@ -168,7 +168,7 @@ pub fn data_row_container<'a, 'b>(
                 config.wow.flavor,
                 addon_data.id.to_string(),
                 InstallKind::Catalog {
-                    source: addon_data.source,
+                    source: addon_data.source.clone(),
                 },
             ));
         }
@@ -278,7 +278,7 @@ pub fn data_row_container<'a, 'b>(
         let game_version_text = addon_data
             .game_versions
             .iter()
-            .find(|gv| gv.flavor == config.wow.flavor.base_flavor())
+            .find(|gv| gv.flavor.base_flavor() == config.wow.flavor.base_flavor())
             .map(|gv| match addon_data.source {
                 Source::TownlongYak => format_interface_into_game_version(&gv.game_version[..]),
                 _ => gv.game_version.clone(),
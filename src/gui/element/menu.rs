@@ -33,14 +33,7 @@ pub fn data_container<'a>(
     weak_auras_is_installed: bool,
 ) -> Container<'a, Message> {
     let flavor = config.wow.flavor;
-    let mut valid_flavors = config
-        .wow
-        .directories
-        .keys()
-        .copied()
-        .collect::<Vec<Flavor>>();
-
-    valid_flavors.sort();
+    let valid_flavors = config.known_flavors();
 
     // State.
     let myaddons_state = state
@@ -826,16 +826,15 @@ pub fn menu_container<'a>(
 
     // Displays text depending on the state of the app.
     let flavor = config.wow.flavor;
-    let ignored_addons = config.addons.ignored.get(&flavor);
 
     let status_text = match state {
         State::Ready => {
-            let flavor = flavor.to_string().to_lowercase();
             let addons_count = addons
                 .iter()
-                .filter(|a| !a.is_ignored(ignored_addons))
+                .filter(|a| !config.addons.is_ignored(&flavor, &a.primary_folder_id))
                 .count()
                 .to_string();
+            let flavor = flavor.to_string().to_lowercase();
             let mut vars = HashMap::new();
             vars.insert("flavor".to_string(), &flavor);
             vars.insert("number".to_string(), &addons_count);
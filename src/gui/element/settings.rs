@@ -51,7 +51,7 @@ pub fn data_container<'a, 'b>(
                 .map(|p| p.to_str())
                 .flatten()
                 .unwrap_or("-");
-            let flavor_text = Text::new(flavor.to_string())
+            let flavor_text = Text::new(flavor.localized_name(config.language))
                 .size(14)
                 .vertical_alignment(VerticalAlignment::Center);
             let flavor_text_container = Container::new(flavor_text)
@@ -158,13 +158,19 @@ pub fn data_container<'a, 'b>(
             .push(scale_buttons_row)
     };
 
-    let (backup_title_row, backup_directory_row, backup_now_row) = {
+    let (backup_title_row, backup_description_row, backup_directory_row, backup_now_row) = {
         // Title for the Backup section.
         let backup_title_text =
             Text::new(localized_string("backup")).size(DEFAULT_HEADER_FONT_SIZE);
         let backup_title_text_container = Container::new(backup_title_text)
             .style(style::BrightBackgroundContainer(color_palette));
 
+        // Short description of what gets backed up.
+        let backup_description_text =
+            Text::new(localized_string("backup-description")).size(DEFAULT_FONT_SIZE);
+        let backup_description_container = Container::new(backup_description_text)
+            .style(style::NormalBackgroundContainer(color_palette));
+
         let checkbox_title = &localized_string("addons")[..];
         let addon_folder_checkbox: Element<_> = Container::new(
             Checkbox::new(config.backup_addons, checkbox_title, move |is_checked| {
@@ -189,6 +195,18 @@ pub fn data_container<'a, 'b>(
         .style(style::BrightBackgroundContainer(color_palette))
         .into();
 
+        let checkbox_title = &localized_string("config")[..];
+        let config_checkbox: Element<_> = Container::new(
+            Checkbox::new(config.backup_config, checkbox_title, move |is_checked| {
+                Interaction::ToggleBackupFolder(is_checked, BackupFolderKind::Config)
+            })
+            .text_size(DEFAULT_FONT_SIZE)
+            .spacing(5)
+            .style(style::DefaultCheckbox(color_palette)),
+        )
+        .style(style::BrightBackgroundContainer(color_palette))
+        .into();
+
         // Directory button for Backup directory selection.
         let directory_button_title_container =
             Container::new(Text::new(localized_string("select-directory")).size(DEFAULT_FONT_SIZE))
@@ -205,13 +223,8 @@ pub fn data_container<'a, 'b>(
 
         // Directory text, written next to directory button to let the user
         // know what has been selected.
-        let no_directory_str = &localized_string("no-directory")[..];
-        let path_str = config
-            .backup_directory
-            .as_ref()
-            .and_then(|p| p.to_str())
-            .unwrap_or(no_directory_str);
-        let directory_data_text = Text::new(path_str)
+        let backup_directory = config.backup_directory_or_default();
+        let directory_data_text = Text::new(backup_directory.to_string_lossy())
             .size(DEFAULT_FONT_SIZE)
             .vertical_alignment(VerticalAlignment::Center);
         let directory_data_text_container = Container::new(directory_data_text)
@@ -225,83 +238,69 @@ pub fn data_container<'a, 'b>(
             .push(Space::new(Length::Units(DEFAULT_PADDING), Length::Units(0)))
             .push(wtf_folder_checkbox.map(Message::Interaction))
             .push(Space::new(Length::Units(DEFAULT_PADDING), Length::Units(0)))
+            .push(config_checkbox.map(Message::Interaction))
+            .push(Space::new(Length::Units(DEFAULT_PADDING), Length::Units(0)))
             .push(directory_button.map(Message::Interaction))
             .push(Space::new(Length::Units(DEFAULT_PADDING), Length::Units(0)))
             .push(directory_data_text_container);
 
-        // Row to show actual backup button along with info about the latest
-        // backup date/time. Will give a description of what Backup is when no
-        // directory is chosen
-        let mut backup_now_row = Row::new();
-
-        // Show button / last backup info if directory is shown, otherwise
-        // show description about the backup process
-        if config.backup_directory.is_some() {
-            let backup_button_title_container =
-                Container::new(Text::new(localized_string("backup-now")).size(DEFAULT_FONT_SIZE))
-                    .width(Length::FillPortion(1))
-                    .center_x()
-                    .align_x(Align::Center);
-            let mut backup_button = Button::new(
-                &mut backup_state.backup_now_btn_state,
-                backup_button_title_container,
-            )
-            .style(style::DefaultBoxedButton(color_palette));
-
-            // Only show button as clickable if it's not currently backing up and
-            // the wow folder is chosen and at least one of the folders is selected
-            // for backup
-            if !backup_state.backing_up
-                && config.wow.directories.keys().next().is_some()
-                && (config.backup_addons || config.backup_wtf)
-            {
-                backup_button = backup_button.on_press(Interaction::Backup);
-            }
-
-            let backup_status_text = if backup_state.backing_up {
-                Text::new(localized_string("backup-progress"))
-                    .size(DEFAULT_FONT_SIZE)
-                    .vertical_alignment(VerticalAlignment::Center)
-            } else {
-                let as_of = backup_state
-                    .last_backup
-                    .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
-                    .unwrap_or_else(|| localized_string("backup-never"));
-
-                let mut vars = HashMap::new();
-                vars.insert("time".to_string(), &as_of);
-                let fmt = localized_string("backup-latest");
-
-                Text::new(strfmt(&fmt, &vars).unwrap())
-                    .size(DEFAULT_FONT_SIZE)
-                    .vertical_alignment(VerticalAlignment::Center)
-            };
+        let backup_button_title_container =
+            Container::new(Text::new(localized_string("backup-now")).size(DEFAULT_FONT_SIZE))
+                .width(Length::FillPortion(1))
+                .center_x()
+                .align_x(Align::Center);
+        let mut backup_button = Button::new(
+            &mut backup_state.backup_now_btn_state,
+            backup_button_title_container,
+        )
+        .style(style::DefaultBoxedButton(color_palette));
+
+        // Only show button as clickable if it's not currently backing up and
+        // the wow folder is chosen and at least one of the folders is selected
+        // for backup
+        if !backup_state.backing_up
+            && config.wow.directories.keys().next().is_some()
+            && (config.backup_addons || config.backup_wtf || config.backup_config)
+        {
+            backup_button = backup_button.on_press(Interaction::Backup);
+        }
 
-            let backup_status_text_container = Container::new(backup_status_text)
-                .center_y()
-                .height(Length::Units(25))
-                .style(style::NormalBackgroundContainer(color_palette));
+        let backup_status_text = if backup_state.backing_up {
+            Text::new(localized_string("backup-progress"))
+                .size(DEFAULT_FONT_SIZE)
+                .vertical_alignment(VerticalAlignment::Center)
+        } else {
+            let as_of = backup_state
+                .last_backup
+                .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| localized_string("backup-never"));
 
-            let backup_button: Element<Interaction> = backup_button.into();
+            let mut vars = HashMap::new();
+            vars.insert("time".to_string(), &as_of);
+            let fmt = localized_string("backup-latest");
 
-            backup_now_row = backup_now_row
-                .push(backup_button.map(Message::Interaction))
-                .push(Space::new(Length::Units(DEFAULT_PADDING), Length::Units(0)))
-                .push(backup_status_text_container);
-        } else {
-            let backup_status_text = Text::new(localized_string("backup-description"))
+            Text::new(strfmt(&fmt, &vars).unwrap())
                 .size(DEFAULT_FONT_SIZE)
-                .vertical_alignment(VerticalAlignment::Center);
+                .vertical_alignment(VerticalAlignment::Center)
+        };
 
-            let backup_status_text_container = Container::new(backup_status_text)
-                .center_y()
-                .style(style::NormalBackgroundContainer(color_palette));
+        let backup_status_text_container = Container::new(backup_status_text)
+            .center_y()
+            .height(Length::Units(25))
+            .style(style::NormalBackgroundContainer(color_palette));
 
-            backup_now_row = backup_now_row.push(backup_status_text_container);
-        }
+        let backup_button: Element<Interaction> = backup_button.into();
+
+        // Row to show actual backup button along with info about the latest
+        // backup date/time.
+        let backup_now_row = Row::new()
+            .push(backup_button.map(Message::Interaction))
+            .push(Space::new(Length::Units(DEFAULT_PADDING), Length::Units(0)))
+            .push(backup_status_text_container);
 
         (
             backup_title_text_container,
+            backup_description_container,
             backup_directory_row,
             backup_now_row,
         )
@@ -483,7 +482,7 @@ pub fn data_container<'a, 'b>(
             .style(style::NormalBackgroundContainer(color_palette));
         let pick_list: Element<_> = PickList::new(
             localization_picklist_state,
-            &Language::ALL[..],
+            Language::all_sorted(),
             Some(config.language),
             Interaction::PickLocalizationLanguage,
         )
@@ -528,6 +527,8 @@ pub fn data_container<'a, 'b>(
     scrollable = scrollable
         .push(backup_title_row)
         .push(Space::new(Length::Units(0), Length::Units(5)))
+        .push(backup_description_row)
+        .push(Space::new(Length::Units(0), Length::Units(5)))
         .push(backup_now_row)
         .push(Space::new(Length::Units(0), Length::Units(5)))
         .push(backup_directory_row)
@@ -6,15 +6,16 @@ use crate::cli::Opts;
 use crate::localization::{localized_string, LANG};
 use crate::Result;
 use ajour_core::{
-    addon::{Addon, AddonFolder, AddonState},
+    addon::{is_addon_visible, Addon, AddonFolder},
     cache::catalog_download_latest_or_use_cache,
     cache::{
         load_addon_cache, load_fingerprint_cache, AddonCache, AddonCacheEntry, FingerprintCache,
     },
     catalog::{self, Catalog, CatalogAddon},
-    config::{ColumnConfig, ColumnConfigV2, Config, Flavor, Language, SelfUpdateChannel},
+    config::{self, ColumnConfigV2, Config, Flavor, Language, SelfUpdateChannel},
     error::*,
-    fs::PersistentData,
+    fs::{PersistentData, Tracked},
+    network::DownloadLimiter,
     repository::{Changelog, GlobalReleaseChannel, ReleaseChannel, RepositoryPackage},
     theme::{load_user_themes, Theme},
     utility::{self, get_latest_release},
@@ -183,7 +184,7 @@ pub struct Ajour {
     weakauras_scrollable_state: scrollable::State,
     settings_scrollable_state: scrollable::State,
     about_scrollable_state: scrollable::State,
-    config: Config,
+    config: Tracked<Config>,
     expanded_type: ExpandType,
     self_update_state: SelfUpdateState,
     refresh_btn_state: button::State,
@@ -223,6 +224,7 @@ pub struct Ajour {
     flavor_picklist_state: pick_list::State<Flavor>,
     addons_search_state: AddonsSearchState,
     wow_directories: Vec<WowDirectoryState>,
+    download_limiter: DownloadLimiter,
 }
 
 impl Default for Ajour {
@@ -236,7 +238,7 @@ impl Default for Ajour {
             weakauras_scrollable_state: Default::default(),
             settings_scrollable_state: Default::default(),
             about_scrollable_state: Default::default(),
-            config: Config::default(),
+            config: Tracked::default(),
             expanded_type: ExpandType::None,
             self_update_state: Default::default(),
             refresh_btn_state: Default::default(),
@@ -285,6 +287,7 @@ impl Default for Ajour {
                     button_state: Default::default(),
                 })
                 .collect::<Vec<WowDirectoryState>>(),
+            download_limiter: DownloadLimiter::new(config::DEFAULT_MAX_CONCURRENT_DOWNLOADS),
         }
     }
 }
@@ -298,12 +301,15 @@ impl Application for Ajour {
         let init_commands = vec![
             Command::perform(load_caches(), Message::CachesLoaded),
             Command::perform(
-                get_latest_release(config.self_update_channel),
+                get_latest_release(
+                    config.self_update_channel,
+                    config.self_update_url_override.clone(),
+                ),
                 Message::LatestRelease,
             ),
             Command::perform(load_user_themes(), Message::ThemesLoaded),
             Command::perform(
-                catalog_download_latest_or_use_cache(),
+                catalog_download_latest_or_use_cache(Some(config.effective_catalog_source())),
                 Message::CatalogDownloaded,
             ),
         ];
@@ -329,8 +335,18 @@ impl Application for Ajour {
             iced_futures::time::every(Duration::from_secs(60 * 5)).map(Message::RefreshCatalog);
         let new_release_subscription = iced_futures::time::every(Duration::from_secs(60 * 60))
             .map(Message::CheckLatestRelease);
-        let check_updates_subscription = iced_futures::time::every(Duration::from_secs(60 * 30))
-            .map(Message::CheckRepositoryUpdates);
+        // Check for updates at the interval chosen for auto updates, if any.
+        let check_updates_interval = self
+            .config
+            .auto_update_interval()
+            .filter(|_| {
+                Flavor::ALL
+                    .iter()
+                    .any(|flavor| self.config.should_auto_update(flavor))
+            })
+            .unwrap_or_else(|| Duration::from_secs(60 * 30));
+        let check_updates_subscription =
+            iced_futures::time::every(check_updates_interval).map(Message::CheckRepositoryUpdates);
 
         iced::Subscription::batch(vec![
             runtime_subscription,
@@ -455,7 +471,7 @@ impl Application for Ajour {
                 // Loops though the addons.
                 for (idx, addon) in addons.iter_mut().enumerate() {
                     // If hiding ignored addons, we will skip it.
-                    if addon.state == AddonState::Ignored && self.config.hide_ignored_addons {
+                    if !is_addon_visible(addon, &self.config, &flavor) {
                         continue;
                     }
 
@@ -761,6 +777,7 @@ impl Application for Ajour {
                     let catalog_source = self
                         .config
                         .catalog_source
+                        .clone()
                         .map(CatalogSource::Choice)
                         .unwrap_or(CatalogSource::None);
 
@@ -1079,7 +1096,16 @@ impl Application for Ajour {
 /// Starts the GUI.
 /// This function does not return.
 pub fn run(opts: Opts) {
-    let config: Config = Config::load_or_default().expect("loading config on application startup");
+    let is_fresh_config = Config::path().is_ok_and(|path| !path.exists());
+
+    let mut config: Config = async_std::task::block_on(config::load_config())
+        .expect("loading config on application startup");
+
+    // Default a fresh config to the system language
+    if is_fresh_config {
+        config.language = Language::detect_system();
+        let _ = config.save();
+    }
 
     // Set LANG using config (defaults to "en_US")
     LANG.set(RwLock::new(config.language.language_code()))
@@ -1088,6 +1114,8 @@ pub fn run(opts: Opts) {
     log::debug!("config loaded:\n{:#?}", &config);
 
     let mut settings = Settings::default();
+    // iced can't position or maximize the window, so only the saved size is
+    // used. It's the size a maximized window is restored to.
     settings.window.size = config.window_size.unwrap_or((900, 620));
 
     #[cfg(not(target_os = "linux"))]
@@ -1255,6 +1283,24 @@ impl SortDirection {
     }
 }
 
+impl From<SortDirection> for config::SortDirection {
+    fn from(direction: SortDirection) -> Self {
+        match direction {
+            SortDirection::Asc => config::SortDirection::Asc,
+            SortDirection::Desc => config::SortDirection::Desc,
+        }
+    }
+}
+
+impl From<config::SortDirection> for SortDirection {
+    fn from(direction: config::SortDirection) -> Self {
+        match direction {
+            config::SortDirection::Asc => SortDirection::Asc,
+            config::SortDirection::Desc => SortDirection::Desc,
+        }
+    }
+}
+
 pub struct AddonsSearchState {
     pub query: Option<String>,
     pub query_state: text_input::State,
@@ -1780,7 +1826,7 @@ pub enum InstallStatus {
     Error(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum InstallKind {
     Catalog { source: catalog::Source },
     Source,
@@ -1854,7 +1900,7 @@ impl std::fmt::Display for CatalogResultSize {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CatalogSource {
     Choice(catalog::Source),
     None,
@@ -1862,29 +1908,21 @@ pub enum CatalogSource {
 
 impl CatalogSource {
     pub fn all() -> Vec<CatalogSource> {
-        vec![
-            CatalogSource::Choice(catalog::Source::Curse),
-            CatalogSource::Choice(catalog::Source::Tukui),
-            CatalogSource::Choice(catalog::Source::WowI),
-            CatalogSource::Choice(catalog::Source::TownlongYak),
-        ]
+        catalog::Source::all()
+            .into_iter()
+            .map(CatalogSource::Choice)
+            .collect()
     }
 }
 
 impl std::fmt::Display for CatalogSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let empty_display_string = &localized_string("select-catalog-source-picklist")[..];
-        let s = match self {
-            CatalogSource::Choice(source) => match source {
-                catalog::Source::Curse => "Curse",
-                catalog::Source::Tukui => "Tukui",
-                catalog::Source::WowI => "WowInterface",
-                catalog::Source::TownlongYak => "TownlongYak",
-                catalog::Source::Other => panic!("Unsupported catalog source"),
-            },
-            CatalogSource::None => empty_display_string,
-        };
-        write!(f, "{}", s)
+        match self {
+            CatalogSource::Choice(source) => write!(f, "{}", source),
+            CatalogSource::None => {
+                write!(f, "{}", localized_string("select-catalog-source-picklist"))
+            }
+        }
     }
 }
 
@@ -1896,23 +1934,10 @@ pub struct ThemeState {
 
 impl Default for ThemeState {
     fn default() -> Self {
-        let themes = vec![
-            ("Alliance".to_string(), Theme::alliance()),
-            ("Ayu".to_string(), Theme::ayu()),
-            ("Dark".to_string(), Theme::dark()),
-            ("Dracula".to_string(), Theme::dracula()),
-            ("Ferra".to_string(), Theme::ferra()),
-            ("Forest Night".to_string(), Theme::forest_night()),
-            ("Gruvbox".to_string(), Theme::gruvbox()),
-            ("Horde".to_string(), Theme::horde()),
-            ("Light".to_string(), Theme::light()),
-            ("Nord".to_string(), Theme::nord()),
-            ("One Dark".to_string(), Theme::one_dark()),
-            ("Outrun".to_string(), Theme::outrun()),
-            ("Solarized Dark".to_string(), Theme::solarized_dark()),
-            ("Solarized Light".to_string(), Theme::solarized_light()),
-            ("Sort".to_string(), Theme::sort()),
-        ];
+        let themes = Theme::bundled()
+            .into_iter()
+            .map(|theme| (theme.name.clone(), theme))
+            .collect();
 
         ThemeState {
             themes,
@@ -1942,6 +1967,7 @@ impl Default for ScaleState {
 pub enum BackupFolderKind {
     AddOns,
     WTF,
+    Config,
 }
 
 #[derive(Default)]
@@ -1984,7 +2010,7 @@ pub struct SelfUpdateState {
 #[derive(Debug)]
 pub struct SelfUpdateChannelState {
     picklist: pick_list::State<SelfUpdateChannel>,
-    options: [SelfUpdateChannel; 2],
+    options: [SelfUpdateChannel; 3],
 }
 
 #[derive(Debug, Default)]
@@ -2166,231 +2192,127 @@ async fn load_caches() -> Result<(FingerprintCache, AddonCache)> {
     Ok((fingerprint_cache, addon_cache))
 }
 
-fn apply_config(ajour: &mut Ajour, config: Config) {
-    // Set column widths from the config
-    match &config.column_config {
-        ColumnConfig::V1 {
-            local_version_width,
-            remote_version_width,
-            status_width,
-        } => {
-            ajour
-                .header_state
-                .columns
-                .get_mut(1)
-                .as_mut()
-                .unwrap()
-                .width = Length::Units(*local_version_width);
-            ajour
-                .header_state
-                .columns
-                .get_mut(2)
-                .as_mut()
-                .unwrap()
-                .width = Length::Units(*remote_version_width);
-            ajour
-                .header_state
-                .columns
-                .get_mut(3)
-                .as_mut()
-                .unwrap()
-                .width = Length::Units(*status_width);
-        }
-        ColumnConfig::V2 { columns } => {
-            ajour.header_state.columns.iter_mut().for_each(|a| {
-                if let Some((idx, column)) = columns
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(idx, column)| {
-                        if column.key == a.key.as_string() {
-                            Some((idx, column))
-                        } else {
-                            None
-                        }
-                    })
-                    .next()
-                {
-                    a.width = column.width.map_or(Length::Fill, Length::Units);
-                    a.hidden = column.hidden;
-                    a.order = idx;
-                }
-            });
+fn apply_config(ajour: &mut Ajour, mut config: Config) {
+    // Migrate older config formats. The migrated config is persisted with the
+    // next actual change instead of rewriting it on launch.
+    config.migrate_schema();
 
-            ajour.column_settings.columns.iter_mut().for_each(|a| {
-                if let Some(idx) = columns
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(idx, column)| {
-                        if column.key == a.key.as_string() {
-                            Some(idx)
-                        } else {
-                            None
-                        }
-                    })
-                    .next()
-                {
-                    a.order = idx;
-                }
-            });
-
-            // My Addons
-            ajour.header_state.columns.sort_by_key(|c| c.order);
-            ajour.column_settings.columns.sort_by_key(|c| c.order);
+    // Set the inital mode flavor, starting at the default flavor if one is set
+    if config.default_flavor.is_some() {
+        if let Some(flavor) = config.wow.primary_flavor(config.default_flavor) {
+            config.wow.flavor = flavor;
         }
-        ColumnConfig::V3 {
-            my_addons_columns,
-            catalog_columns,
-            aura_columns,
-        } => {
-            ajour.header_state.columns.iter_mut().for_each(|a| {
-                if let Some((idx, column)) = my_addons_columns
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(idx, column)| {
-                        if column.key == a.key.as_string() {
-                            Some((idx, column))
-                        } else {
-                            None
-                        }
-                    })
-                    .next()
-                {
-                    // Always force "Title" column as Length::Fill
-                    //
-                    // Shouldn't be an issue here, as it was for catalog column fix
-                    // below, but will cover things in case anyone accidently manually
-                    // modifies their config and sets a fixed width on this column.
-                    a.width = if a.key == ColumnKey::Title {
-                        Length::Fill
-                    } else {
-                        column.width.map_or(Length::Fill, Length::Units)
-                    };
+    }
 
-                    a.hidden = column.hidden;
-                    a.order = idx;
-                }
-            });
+    // Set columns from the config, for the flavor shown first
+    let my_addons_columns = config.column_config.my_addons_columns(&config.wow.flavor);
+    let catalog_columns = config.column_config.catalog_columns();
+    let aura_columns = config.column_config.aura_columns();
 
-            ajour.column_settings.columns.iter_mut().for_each(|a| {
-                if let Some(idx) = my_addons_columns
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(idx, column)| {
-                        if column.key == a.key.as_string() {
-                            Some(idx)
-                        } else {
-                            None
-                        }
-                    })
-                    .next()
-                {
-                    a.order = idx;
-                }
-            });
-
-            ajour
-                .catalog_column_settings
-                .columns
-                .iter_mut()
-                .for_each(|a| {
-                    if let Some(idx) = catalog_columns
-                        .iter()
-                        .enumerate()
-                        .filter_map(|(idx, column)| {
-                            if column.key == a.key.as_string() {
-                                Some(idx)
-                            } else {
-                                None
-                            }
-                        })
-                        .next()
-                    {
-                        a.order = idx;
-                    }
-                });
+    apply_my_addons_columns(ajour, &my_addons_columns);
 
-            ajour.catalog_header_state.columns.iter_mut().for_each(|a| {
-                if let Some((idx, column)) = catalog_columns
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(idx, column)| {
-                        if column.key == a.key.as_string() {
-                            Some((idx, column))
-                        } else {
-                            None
-                        }
-                    })
-                    .next()
-                {
-                    // Always force "Title" column as Length::Fill
-                    //
-                    // An older version of ajour used a different column as the fill
-                    // column and some users have migration issues when updating to
-                    // a newer version, causing NO columns to be set as Fill and
-                    // making resizing columns work incorrectly
-                    a.width = if a.key == CatalogColumnKey::Title {
-                        Length::Fill
+    ajour
+        .catalog_column_settings
+        .columns
+        .iter_mut()
+        .for_each(|a| {
+            if let Some(idx) = catalog_columns
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, column)| {
+                    if column.key == a.key.as_string() {
+                        Some(idx)
                     } else {
-                        column.width.map_or(Length::Fill, Length::Units)
-                    };
+                        None
+                    }
+                })
+                .next()
+            {
+                a.order = idx;
+            }
+        });
 
-                    a.hidden = column.hidden;
-                    a.order = idx;
+    ajour.catalog_header_state.columns.iter_mut().for_each(|a| {
+        if let Some((idx, column)) = catalog_columns
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, column)| {
+                if column.key == a.key.as_string() {
+                    Some((idx, column))
+                } else {
+                    None
                 }
-            });
+            })
+            .next()
+        {
+            // Always force "Title" column as Length::Fill
+            //
+            // An older version of ajour used a different column as the fill
+            // column and some users have migration issues when updating to
+            // a newer version, causing NO columns to be set as Fill and
+            // making resizing columns work incorrectly
+            a.width = if a.key == CatalogColumnKey::Title {
+                Length::Fill
+            } else {
+                column.width.map_or(Length::Fill, Length::Units)
+            };
+
+            a.hidden = column.hidden;
+            a.order = idx;
+        }
+    });
 
-            ajour.aura_header_state.columns.iter_mut().for_each(|a| {
-                if let Some((_idx, column)) = aura_columns
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(idx, column)| {
-                        if column.key == a.key.as_string() {
-                            Some((idx, column))
-                        } else {
-                            None
-                        }
-                    })
-                    .next()
-                {
-                    // Always force "Title" column as Length::Fill
-                    //
-                    // An older version of ajour used a different column as the fill
-                    // column and some users have migration issues when updating to
-                    // a newer version, causing NO columns to be set as Fill and
-                    // making resizing columns work incorrectly
-                    a.width = if a.key == AuraColumnKey::Title {
-                        Length::Fill
-                    } else {
-                        column.width.map_or(Length::Fill, Length::Units)
-                    };
+    ajour.aura_header_state.columns.iter_mut().for_each(|a| {
+        if let Some((_idx, column)) = aura_columns
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, column)| {
+                if column.key == a.key.as_string() {
+                    Some((idx, column))
+                } else {
+                    None
                 }
-            });
+            })
+            .next()
+        {
+            // Always force "Title" column as Length::Fill
+            //
+            // An older version of ajour used a different column as the fill
+            // column and some users have migration issues when updating to
+            // a newer version, causing NO columns to be set as Fill and
+            // making resizing columns work incorrectly
+            a.width = if a.key == AuraColumnKey::Title {
+                Length::Fill
+            } else {
+                column.width.map_or(Length::Fill, Length::Units)
+            };
+        }
+    });
 
-            // My Addons
-            ajour.header_state.columns.sort_by_key(|c| c.order);
-            ajour.column_settings.columns.sort_by_key(|c| c.order);
+    // My Addons
+    ajour.header_state.columns.sort_by_key(|c| c.order);
+    ajour.column_settings.columns.sort_by_key(|c| c.order);
 
-            // Catalog
-            ajour.catalog_header_state.columns.sort_by_key(|c| c.order);
-            ajour
-                .catalog_column_settings
-                .columns
-                .sort_by_key(|c| c.order);
+    // Catalog
+    ajour.catalog_header_state.columns.sort_by_key(|c| c.order);
+    ajour
+        .catalog_column_settings
+        .columns
+        .sort_by_key(|c| c.order);
 
-            // No sorting on Aura columns currently
-        }
-    }
+    // No sorting on Aura columns currently
 
-    // Use theme from config. Set to "Dark" if not defined.
-    ajour.theme_state.current_theme_name = config.theme.as_deref().unwrap_or("Dark").to_string();
+    // Use theme from config. Set to "Dark" if not defined or missing.
+    ajour.theme_state.current_theme_name = config.theme_or_default();
 
     // Use scale from config. Set to 1.0 if not defined.
-    ajour.scale_state.scale = config.scale.unwrap_or(1.0);
+    ajour.scale_state.scale = config.normalized_scale();
 
-    // Set the inital mode flavor
     ajour.mode = Mode::MyAddons(config.wow.flavor);
+    ajour.download_limiter = config.download_limiter();
 
-    ajour.config = config;
+    // Starts clean, so only changes from here on are saved.
+    ajour.config = Tracked::from_saved(config);
 
     // @see (casperstorm): Migration from single World of Warcraft directory to multiple directories.
     // This is essentially deprecrating `ajour.config.wow.directory`.
@@ -2407,5 +2329,54 @@ fn apply_config(ajour: &mut Ajour, config: Config) {
         ajour.config.wow.directory = None;
     }
 
-    let _ = &ajour.config.save();
+    let _ = ajour.config.save_if_dirty();
+}
+
+/// Sets the My Addons header and column settings from `my_addons_columns`.
+fn apply_my_addons_columns(ajour: &mut Ajour, my_addons_columns: &[ColumnConfigV2]) {
+    ajour.header_state.columns.iter_mut().for_each(|a| {
+        if let Some((idx, column)) = my_addons_columns
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, column)| {
+                if column.key == a.key.as_string() {
+                    Some((idx, column))
+                } else {
+                    None
+                }
+            })
+            .next()
+        {
+            // Always force "Title" column as Length::Fill
+            //
+            // Shouldn't be an issue here, as it was for catalog column fix
+            // below, but will cover things in case anyone accidently manually
+            // modifies their config and sets a fixed width on this column.
+            a.width = if a.key == ColumnKey::Title {
+                Length::Fill
+            } else {
+                column.width.map_or(Length::Fill, Length::Units)
+            };
+
+            a.hidden = column.hidden;
+            a.order = idx;
+        }
+    });
+
+    ajour.column_settings.columns.iter_mut().for_each(|a| {
+        if let Some(idx) = my_addons_columns
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, column)| {
+                if column.key == a.key.as_string() {
+                    Some(idx)
+                } else {
+                    None
+                }
+            })
+            .next()
+        {
+            a.order = idx;
+        }
+    });
 }
@@ -1,29 +1,37 @@
 use {
     super::{
-        Ajour, AuraColumnKey, BackupFolderKind, CatalogCategory, CatalogColumnKey, CatalogRow,
-        CatalogSource, ColumnKey, DownloadReason, ExpandType, GlobalReleaseChannel, InstallAddon,
-        InstallKind, InstallStatus, Interaction, Message, Mode, ReleaseChannel, SelfUpdateStatus,
-        SortDirection, State,
+        apply_my_addons_columns, Ajour, AuraColumnKey, BackupFolderKind, CatalogCategory,
+        CatalogColumnKey, CatalogRow, CatalogSource, ColumnKey, DownloadReason, ExpandType,
+        GlobalReleaseChannel, InstallAddon, InstallKind, InstallStatus, Interaction, Message, Mode,
+        ReleaseChannel, SelfUpdateStatus, SortDirection, State,
     },
     crate::localization::{localized_string, LANG},
     crate::{log_error, Result},
     ajour_core::{
         addon::{Addon, AddonFolder, AddonState},
-        backup::{backup_folders, latest_backup, BackupFolder},
+        backup::{
+            backup_archives, config_backup_folders, latest_backup, prune_backups, BackupFolder,
+            BackupKind, BackupTag,
+        },
         cache::{
             catalog_download_latest_or_use_cache, remove_addon_cache_entry, update_addon_cache,
             AddonCache, AddonCacheEntry, FingerprintCache,
         },
         catalog,
-        config::{ColumnConfig, ColumnConfigV2, Flavor},
+        config::{
+            clear_directory_cache, ColumnConfig, ColumnConfigV2, Flavor, MAX_SCALE, MIN_SCALE,
+        },
         error::{DownloadError, FilesystemError, ParseError, RepositoryError},
-        fs::{delete_addons, delete_saved_variables, install_addon, PersistentData},
-        network::download_addon,
+        fs::{config_dir, delete_addons, delete_saved_variables, install_addon},
+        network::{download_addon, DownloadLimiter},
         parse::{read_addon_directory, update_addon_fingerprint},
         repository::{
             batch_refresh_repository_packages, Changelog, RepositoryKind, RepositoryPackage,
         },
-        utility::{download_update_to_temp_file, get_latest_release, wow_path_resolution},
+        utility::{
+            download_update_to_temp_file, expand_path, get_latest_release, unset_path_variables,
+            wow_path_resolution,
+        },
     },
     ajour_weak_auras::{Aura, AuraStatus},
     ajour_widgets::header::ResizeEvent,
@@ -62,13 +70,14 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             // Begin to parse addon folder(s).
             let mut commands = vec![];
 
-            // If a backup directory is selected, find the latest backup
-            if let Some(dir) = &ajour.config.backup_directory {
-                commands.push(Command::perform(
-                    latest_backup(dir.to_owned()),
-                    Message::LatestBackup,
-                ));
-            }
+            // Find the latest backup
+            commands.push(Command::perform(
+                latest_backup(
+                    ajour.config.backup_directory_or_default(),
+                    ajour.config.backup_filename_pattern.clone(),
+                ),
+                Message::LatestBackup,
+            ));
 
             // Check if any new flavor has been added since last time.
             // Get missing flavors.
@@ -106,23 +115,39 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
 
                 // Check if the current flavor we are looping still exists.
                 // It might have been uninstalled since last time, if we can't find it we remove it.
-                if let Some(flavor_path) = ajour.config.wow.directories.get(&flavor) {
-                    if !flavor_path.exists() {
+                // Paths using an environment variable which isn't set are kept, as
+                // the variable may be set again next time.
+                if let Some(raw_path) = ajour.config.wow.directories.get(&flavor) {
+                    let unset = unset_path_variables(raw_path);
+
+                    if !unset.is_empty() {
+                        log::warn!(
+                            "keeping {} directory {:?}, as {} is not set",
+                            flavor,
+                            raw_path,
+                            unset.join(", ")
+                        );
+                    } else if !expand_path(raw_path).exists() {
                         ajour.config.wow.directories.remove(&flavor);
                     }
                 }
             }
 
-            let flavors = ajour.config.wow.directories.keys().collect::<Vec<_>>();
+            let flavors = ajour
+                .config
+                .sorted_directories()
+                .into_iter()
+                .map(|(flavor, _)| flavor)
+                .collect::<Vec<_>>();
             for flavor in flavors {
-                if let Some(addon_directory) = ajour.config.get_addon_directory_for_flavor(flavor) {
+                if let Ok(addon_directory) = ajour.config.ensure_addon_directory(&flavor) {
                     log::debug!(
                         "preparing to parse addons in {:?}",
                         addon_directory.display()
                     );
 
                     // Sets loading
-                    ajour.state.insert(Mode::MyAddons(*flavor), State::Loading);
+                    ajour.state.insert(Mode::MyAddons(flavor), State::Loading);
 
                     // Add commands
                     commands.push(Command::perform(
@@ -130,7 +155,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                             ajour.addon_cache.clone(),
                             ajour.fingerprint_cache.clone(),
                             addon_directory.clone(),
-                            *flavor,
+                            flavor,
                         ),
                         Message::ParsedAddons,
                     ));
@@ -138,7 +163,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     // Check if Weak Auras is installed for each flavor. If any of them returns
                     // true, we will show the My WeakAuras button
                     commands.push(Command::perform(
-                        is_weak_auras_installed(*flavor, addon_directory),
+                        is_weak_auras_installed(flavor, addon_directory),
                         Message::CheckWeakAurasInstalled,
                     ));
                 } else {
@@ -154,18 +179,13 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
 
             // If we dont have current flavor in valid flavors we select a new.
             let flavor = ajour.config.wow.flavor;
-            let flavors = ajour
-                .config
-                .wow
-                .directories
-                .keys()
-                .collect::<Vec<_>>()
-                .clone();
-            if !flavors.iter().any(|f| *f == &flavor) {
-                if let Some(flavor) = flavors.first() {
-                    ajour.config.wow.flavor = **flavor;
-                    ajour.mode = Mode::MyAddons(**flavor);
-                    ajour.config.save()?;
+            let flavors = ajour.config.sorted_directories();
+            if !flavors.iter().any(|(f, _)| *f == flavor) {
+                if let Some((flavor, _)) = flavors.first() {
+                    let flavor = *flavor;
+                    ajour.config.wow.flavor = flavor;
+                    ajour.mode = Mode::MyAddons(flavor);
+                    ajour.config.save_if_dirty()?;
                 }
             }
 
@@ -232,13 +252,10 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                 ajour
                     .config
                     .addons
-                    .ignored
-                    .entry(flavor)
-                    .or_default()
-                    .push(addon.primary_folder_id.clone());
+                    .ignore(flavor, addon.primary_folder_id.clone());
 
                 // Persist the newly updated config.
-                let _ = &ajour.config.save();
+                let _ = ajour.config.save_if_dirty();
             }
         }
         Message::Interaction(Interaction::Unignore(id)) => {
@@ -260,11 +277,10 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             };
 
             // Update the config.
-            let ignored_addon_ids = ajour.config.addons.ignored.entry(flavor).or_default();
-            ignored_addon_ids.retain(|i| i != &id);
+            ajour.config.addons.unignore(&flavor, &id);
 
             // Persist the newly updated config.
-            let _ = &ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
         }
         Message::Interaction(Interaction::OpenDirectory(path)) => {
             log::debug!("Interaction::OpenDirectory({:?})", path);
@@ -319,7 +335,9 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     // If a flavor is supplied we only update path for that specific flavor.
                     let flavor_path = ajour.config.get_flavor_directory_for_flavor(&flavor, &path);
                     if flavor_path.exists() {
-                        ajour.config.wow.directories.insert(flavor, flavor_path);
+                        if let Err(error) = ajour.config.set_flavor_directory(flavor, flavor_path) {
+                            log::error!("{}", error);
+                        }
                     }
                 } else {
                     // If no flavor is supplied it will find as many flavors as possible in the path.
@@ -328,7 +346,11 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                         let flavor_path =
                             ajour.config.get_flavor_directory_for_flavor(flavor, &path);
                         if flavor_path.exists() {
-                            ajour.config.wow.directories.insert(*flavor, flavor_path);
+                            if let Err(error) =
+                                ajour.config.set_flavor_directory(*flavor, flavor_path)
+                            {
+                                log::error!("{}", error);
+                            }
                         }
                     }
                 }
@@ -336,8 +358,11 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                 // Clear addons.
                 ajour.addons = HashMap::new();
 
+                // Addon directories need to be resolved again.
+                clear_directory_cache();
+
                 // Save config.
-                let _ = &ajour.config.save();
+                let _ = ajour.config.save_if_dirty();
 
                 let state = ajour.state.clone();
                 for (mode, _) in state {
@@ -356,7 +381,10 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             // Update the game flavor
             ajour.config.wow.flavor = flavor;
             // Persist the newly updated config.
-            let _ = &ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
+            // Show the columns of the flavor.
+            let my_addons_columns = ajour.config.column_config.my_addons_columns(&flavor);
+            apply_my_addons_columns(ajour, &my_addons_columns);
 
             match ajour.mode {
                 Mode::MyAddons(_) => {
@@ -528,6 +556,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                             global_release_channel,
                             addon.clone(),
                             to_directory,
+                            ajour.download_limiter.clone(),
                         ),
                         Message::DownloadedAddon,
                     ));
@@ -545,15 +574,27 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     // Close details if shown.
                     ajour.expanded_type = ExpandType::None;
 
-                    // Update all updatable addons, expect ignored.
+                    // Update all updatable addons, expect ignored and pinned.
                     let global_release_channel = ajour.config.addons.global_release_channel;
-                    let ignored_ids = ajour.config.addons.ignored.entry(flavor).or_default();
+                    let config = &ajour.config;
                     let mut addons: Vec<_> = ajour
                         .addons
                         .entry(flavor)
                         .or_default()
                         .iter_mut()
-                        .filter(|a| !ignored_ids.iter().any(|i| i == &a.primary_folder_id))
+                        .filter(|a| {
+                            if let Some(version) =
+                                config.pinned_version(&flavor, &a.primary_folder_id)
+                            {
+                                log::debug!(
+                                    "skipping update of {}, pinned at {}",
+                                    a.primary_folder_id,
+                                    version
+                                );
+                            }
+
+                            !config.should_skip_update(&flavor, &a.primary_folder_id)
+                        })
                         .collect();
 
                     let mut commands = vec![];
@@ -571,6 +612,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                                         global_release_channel,
                                         addon,
                                         to_directory,
+                                        ajour.download_limiter.clone(),
                                     ),
                                     Message::DownloadedAddon,
                                 ))
@@ -580,7 +622,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     return Ok(Command::batch(commands));
                 }
                 Mode::MyWeakAuras(flavor) => {
-                    if let Some(addon_dir) = ajour.config.get_addon_directory_for_flavor(&flavor) {
+                    if let Some(addon_dir) = ajour.config.resolve_addon_directory(&flavor) {
                         let state = ajour.weak_auras_state.entry(flavor).or_default();
 
                         state.is_updating = true;
@@ -687,7 +729,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                         ajour.header_state.previous_column_key = Some(ColumnKey::Status);
 
                         // If auto update is enabled, trigger a refresh all
-                        if ajour.config.auto_update {
+                        if ajour.config.should_auto_update(&flavor) {
                             return handle_message(
                                 ajour,
                                 Message::Interaction(Interaction::UpdateAll(Mode::MyAddons(
@@ -706,7 +748,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             log::debug!("Interaction::ToggleAutoUpdateAddons({})", auto_update);
 
             ajour.config.auto_update = auto_update;
-            let _ = ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
         }
         Message::ParsedAddons((flavor, result)) => {
             let global_release_channel = ajour.config.addons.global_release_channel;
@@ -718,22 +760,15 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                 Ok(addons) => {
                     log::debug!("Message::ParsedAddons({}, {} addons)", flavor, addons.len(),);
 
-                    // Ignored addon ids.
-                    let ignored_ids = ajour.config.addons.ignored.entry(flavor).or_default();
-
                     // Check if addons is updatable.
-                    let release_channels = ajour
-                        .config
-                        .addons
-                        .release_channels
-                        .entry(flavor)
-                        .or_default();
+                    let config_addons = &ajour.config.addons;
+                    let release_channels = config_addons.release_channels.get(&flavor);
                     let mut addons = addons
                         .into_iter()
                         .map(|mut a| {
                             // Check if we have saved release channel for addon.
                             if let Some(release_channel) =
-                                release_channels.get(&a.primary_folder_id)
+                                release_channels.and_then(|r| r.get(&a.primary_folder_id))
                             {
                                 a.release_channel = *release_channel;
                             } else {
@@ -750,7 +785,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                                 }
                             }
 
-                            if ignored_ids.iter().any(|ia| &a.primary_folder_id == ia) {
+                            if config_addons.is_ignored(&flavor, &a.primary_folder_id) {
                                 a.state = AddonState::Ignored;
                             };
 
@@ -758,15 +793,17 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                         })
                         .collect::<Vec<Addon>>();
 
-                    // Sort the addons.
+                    // Sort the addons the way they were last sorted.
+                    let (column_key, sort_direction) = saved_addon_sort(ajour);
+
                     sort_addons(
                         &mut addons,
                         global_release_channel,
-                        SortDirection::Desc,
-                        ColumnKey::Status,
+                        sort_direction,
+                        column_key,
                     );
-                    ajour.header_state.previous_sort_direction = Some(SortDirection::Desc);
-                    ajour.header_state.previous_column_key = Some(ColumnKey::Status);
+                    ajour.header_state.previous_sort_direction = Some(sort_direction);
+                    ajour.header_state.previous_column_key = Some(column_key);
 
                     // Sets the flavor state to ready.
                     ajour.state.insert(Mode::MyAddons(flavor), State::Ready);
@@ -774,8 +811,14 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     // Insert the addons into the HashMap.
                     ajour.addons.insert(flavor, addons);
 
+                    // Not saved on its own, as that would rewrite the config on
+                    // every parse. It's persisted with the next config change.
+                    ajour
+                        .config
+                        .record_update_check(flavor, std::time::SystemTime::now());
+
                     // If auto update is enabled, trigger a refresh all
-                    if ajour.config.auto_update {
+                    if ajour.config.should_auto_update(&flavor) {
                         return handle_message(
                             ajour,
                             Message::Interaction(Interaction::UpdateAll(Mode::MyAddons(flavor))),
@@ -851,7 +894,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     .expect("Expected a valid path");
                 let to_directory = ajour
                     .config
-                    .get_addon_directory_for_flavor(&flavor)
+                    .resolve_addon_directory(&flavor)
                     .expect("Expected a valid path");
 
                 if addon.state == AddonState::Downloading {
@@ -990,7 +1033,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                             perform_hash_addon(
                                 ajour
                                     .config
-                                    .get_addon_directory_for_flavor(&flavor)
+                                    .resolve_addon_directory(&flavor)
                                     .expect("Expected a valid path"),
                                 folder.id.clone(),
                                 cache.clone(),
@@ -1074,6 +1117,11 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
 
             ajour.header_state.previous_sort_direction = Some(sort_direction);
             ajour.header_state.previous_column_key = Some(column_key);
+
+            ajour
+                .config
+                .set_sort(column_key.as_string(), sort_direction.into());
+            let _ = ajour.config.save_if_dirty();
         }
         Message::Interaction(Interaction::SortCatalogColumn(column_key)) => {
             // First time clicking a column should sort it in Ascending order, otherwise
@@ -1185,7 +1233,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     }
 
                     // Persist the newly updated config.
-                    let _ = &ajour.config.save();
+                    let _ = ajour.config.save_if_dirty();
 
                     addon.release_channel = release_channel;
 
@@ -1205,8 +1253,12 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
 
             ajour.theme_state.current_theme_name = theme_name.clone();
 
-            ajour.config.theme = Some(theme_name);
-            let _ = ajour.config.save();
+            match ajour.config.set_theme(Some(theme_name)) {
+                Ok(_) => {
+                    let _ = ajour.config.save_if_dirty();
+                }
+                Err(error) => log::warn!("{}", error),
+            }
         }
         Message::ThemesLoaded(mut themes) => {
             log::debug!("Message::ThemesLoaded({} themes)", themes.len());
@@ -1302,10 +1354,10 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
         Message::Interaction(Interaction::ScaleUp) => {
             let prev_scale = ajour.scale_state.scale;
 
-            ajour.scale_state.scale = ((prev_scale + 0.1).min(2.0) * 10.0).round() / 10.0;
+            ajour.scale_state.scale = ((prev_scale + 0.1).min(MAX_SCALE) * 10.0).round() / 10.0;
 
-            ajour.config.scale = Some(ajour.scale_state.scale);
-            let _ = ajour.config.save();
+            ajour.config.set_scale(Some(ajour.scale_state.scale));
+            let _ = ajour.config.save_if_dirty();
 
             log::debug!(
                 "Interaction::ScaleUp({} -> {})",
@@ -1316,10 +1368,10 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
         Message::Interaction(Interaction::ScaleDown) => {
             let prev_scale = ajour.scale_state.scale;
 
-            ajour.scale_state.scale = ((prev_scale - 0.1).max(0.5) * 10.0).round() / 10.0;
+            ajour.scale_state.scale = ((prev_scale - 0.1).max(MIN_SCALE) * 10.0).round() / 10.0;
 
-            ajour.config.scale = Some(ajour.scale_state.scale);
-            let _ = ajour.config.save();
+            ajour.config.set_scale(Some(ajour.scale_state.scale));
+            let _ = ajour.config.save_if_dirty();
 
             log::debug!(
                 "Interaction::ScaleDown({} -> {})",
@@ -1334,10 +1386,13 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                 // Update the backup directory path.
                 ajour.config.backup_directory = Some(path.clone());
                 // Persist the newly updated config.
-                let _ = &ajour.config.save();
+                let _ = ajour.config.save_if_dirty();
 
                 // Check if a latest backup exists in path
-                return Ok(Command::perform(latest_backup(path), Message::LatestBackup));
+                return Ok(Command::perform(
+                    latest_backup(path, ajour.config.backup_filename_pattern.clone()),
+                    Message::LatestBackup,
+                ));
             }
         }
 
@@ -1348,39 +1403,55 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             // app is processing the backup. We will unflag this on completion.
             ajour.backup_state.backing_up = true;
 
-            let mut src_folders = vec![];
+            let mut archives = vec![];
 
-            // Shouldn't panic since button is only shown if backup directory is chosen
-            let dest = ajour.config.backup_directory.as_ref().unwrap();
+            let dest = ajour.config.backup_directory_or_default();
 
-            // Backup WTF & AddOn directories for both flavors if they exist
+            // Only tag the kind if the archives hold a single kind of content
+            let kind = match (ajour.config.backup_addons, ajour.config.backup_wtf) {
+                (true, false) => Some(BackupKind::Addons),
+                (false, true) => Some(BackupKind::Wtf),
+                _ => None,
+            };
+
+            // Backup WTF & AddOn directories for each flavor if they exist.
+            // The `Interface` folder is backed up rather than just the addons,
+            // as some users save custom data there that they would like retained
             for flavor in Flavor::ALL.iter() {
                 if let Some(wow_dir) = ajour.config.get_root_directory_for_flavor(flavor) {
-                    if ajour.config.backup_addons {
-                        let addon_dir =
-                            ajour.config.get_addon_directory_for_flavor(flavor).unwrap();
-
-                        // Backup starting with `Interface` folder as some users save
-                        // custom data here that they would like retained
-                        if let Some(interface_dir) = addon_dir.parent() {
-                            if interface_dir.exists() {
-                                src_folders.push(BackupFolder::new(interface_dir, &wow_dir));
-                            }
-                        }
-                    }
-
-                    if ajour.config.backup_wtf {
-                        let wtf_dir = ajour.config.get_wtf_directory_for_flavor(flavor).unwrap();
+                    let src_folders = ajour
+                        .config
+                        .backup_targets(flavor)
+                        .into_iter()
+                        .filter(|(kind, _)| *kind != BackupKind::Config)
+                        .map(|(_, path)| BackupFolder::new(path, &wow_dir))
+                        .collect();
 
-                        if wtf_dir.exists() {
-                            src_folders.push(BackupFolder::new(&wtf_dir, &wow_dir));
-                        }
-                    }
+                    let tag = BackupTag {
+                        flavor: Some(*flavor),
+                        kind,
+                    };
+                    archives.push((tag, src_folders));
                 }
             }
 
+            // Backup Ajour config and themes
+            if ajour.config.backup_config {
+                let tag = BackupTag {
+                    flavor: None,
+                    kind: Some(BackupKind::Config),
+                };
+                archives.push((tag, config_backup_folders(&config_dir())));
+            }
+
             return Ok(Command::perform(
-                backup_folders(src_folders, dest.to_owned()),
+                backup_archives(
+                    archives,
+                    dest,
+                    ajour.config.backup_compression_level,
+                    ajour.config.backup_exclude_patterns.clone(),
+                    ajour.config.backup_filename_pattern.clone(),
+                ),
                 Message::BackupFinished,
             ));
         }
@@ -1398,9 +1469,12 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                 BackupFolderKind::WTF => {
                     ajour.config.backup_wtf = is_checked;
                 }
+                BackupFolderKind::Config => {
+                    ajour.config.backup_config = is_checked;
+                }
             }
 
-            let _ = ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
         }
         Message::LatestBackup(as_of) => {
             log::debug!("Message::LatestBackup({:?})", &as_of);
@@ -1412,6 +1486,18 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
 
             ajour.backup_state.backing_up = false;
             ajour.backup_state.last_backup = Some(as_of);
+
+            // Remove older archives beyond the configured count
+            if let Some(keep) = ajour.config.backup_keep_count {
+                let dir = ajour.config.backup_directory_or_default();
+
+                let pattern = ajour.config.backup_filename_pattern.as_deref();
+
+                match prune_backups(&dir, keep, pattern) {
+                    Ok(removed) => log::debug!("Pruned {} old backups", removed.len()),
+                    Err(error) => log_error(&error.into()),
+                }
+            }
         }
         Message::BackupFinished(error @ Err(_)) => {
             let error = error
@@ -1624,6 +1710,17 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                             );
                         map
                     });
+            // A custom catalog can mix addons from any source, so it gets
+            // the categories of all of them.
+            let all_categories = categories_per_source
+                .values()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            categories_per_source.insert(
+                CatalogSource::Choice(catalog::Source::Custom { url: String::new() }).to_string(),
+                all_categories,
+            );
             categories_per_source.iter_mut().for_each(move |s| {
                 s.1.sort();
                 s.1.dedup();
@@ -1634,6 +1731,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             let catalog_source_choice = ajour
                 .config
                 .catalog_source
+                .clone()
                 .map(CatalogSource::Choice)
                 .unwrap_or(CatalogSource::None);
 
@@ -1661,6 +1759,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             };
             let fuzzy_matcher = SkimMatcherV2::default().score_config(fuzzy_match_config);
 
+            let (saved_column_key, saved_sort_direction) = saved_addon_sort(ajour);
             let addons = ajour.addons.entry(ajour.config.wow.flavor).or_default();
             let global_release_channel = ajour.config.addons.global_release_channel;
 
@@ -1690,15 +1789,15 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     a.fuzzy_score.take();
                 });
 
-                // Use default sort
+                // Go back to the saved sort
                 sort_addons(
                     addons,
                     global_release_channel,
-                    SortDirection::Desc,
-                    ColumnKey::Status,
+                    saved_sort_direction,
+                    saved_column_key,
                 );
-                ajour.header_state.previous_sort_direction = Some(SortDirection::Desc);
-                ajour.header_state.previous_column_key = Some(ColumnKey::Status);
+                ajour.header_state.previous_sort_direction = Some(saved_sort_direction);
+                ajour.header_state.previous_column_key = Some(saved_column_key);
             }
         }
         Message::Interaction(Interaction::CatalogQuery(query)) => {
@@ -1732,7 +1831,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             // Add new status for this addon as Downloading
             install_addons.push(InstallAddon {
                 id: id.clone(),
-                kind,
+                kind: kind.clone(),
                 status: InstallStatus::Downloading,
                 addon: None,
             });
@@ -1762,9 +1861,27 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             log::debug!("Interaction::CatalogResultSizeSelected({:?})", source);
 
             // Catalog source
-            if let CatalogSource::Choice(source) = source {
-                ajour.config.catalog_source = Some(source);
-                let _ = ajour.config.save();
+            if let CatalogSource::Choice(source) = &source {
+                let was_custom = matches!(
+                    ajour.config.catalog_source,
+                    Some(catalog::Source::Custom { .. })
+                );
+
+                ajour.config.catalog_source = Some(source.clone());
+                let _ = ajour.config.save_if_dirty();
+
+                // A custom catalog replaces the default one, so switching away
+                // from it means we need to fetch the default catalog again.
+                if was_custom {
+                    ajour.state.insert(Mode::Catalog, State::Loading);
+
+                    return Ok(Command::perform(
+                        catalog_download_latest_or_use_cache(Some(
+                            ajour.config.effective_catalog_source(),
+                        )),
+                        Message::CatalogDownloaded,
+                    ));
+                }
             }
 
             ajour.catalog_search_state.categories = ajour
@@ -1805,6 +1922,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                                 global_release_channel,
                                 addon,
                                 to_directory,
+                                ajour.download_limiter.clone(),
                             ),
                             Message::DownloadedAddon,
                         ));
@@ -1956,7 +2074,9 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                     log::debug!("Message::RefreshCatalog: catalog needs to be refreshed");
 
                     return Ok(Command::perform(
-                        catalog_download_latest_or_use_cache(),
+                        catalog_download_latest_or_use_cache(Some(
+                            ajour.config.effective_catalog_source(),
+                        )),
                         Message::CatalogDownloaded,
                     ));
                 }
@@ -1966,13 +2086,13 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             log::debug!("Interaction::ToggleHideIgnoredAddons({})", is_checked);
 
             ajour.config.hide_ignored_addons = is_checked;
-            let _ = ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
         }
         Message::Interaction(Interaction::ToggleDeleteSavedVariables(is_checked)) => {
             log::debug!("Interaction::ToggleDeleteSavedVariables({})", is_checked);
 
             ajour.config.addons.delete_saved_variables = is_checked;
-            let _ = ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
         }
         Message::CatalogDownloaded(error @ Err(_)) => {
             let error = error.context("Failed to download catalog").unwrap_err();
@@ -1989,22 +2109,25 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
 
             ajour.config.self_update_channel = channel;
 
-            let _ = ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
 
             return Ok(Command::perform(
-                get_latest_release(ajour.config.self_update_channel),
+                get_latest_release(
+                    ajour.config.self_update_channel,
+                    ajour.config.self_update_url_override.clone(),
+                ),
                 Message::LatestRelease,
             ));
         }
         Message::Interaction(Interaction::PickLocalizationLanguage(lang)) => {
             log::debug!("Interaction::PickLocalizationLanguage({:?})", lang);
 
-            // Update config.
-            ajour.config.language = lang;
-            let _ = ajour.config.save();
+            // Update config and global LANG refcell.
+            if ajour.config.set_language(lang).is_some() {
+                let _ = ajour.config.save_if_dirty();
 
-            // Update global LANG refcell.
-            *LANG.get().expect("LANG not set").write().unwrap() = lang.language_code();
+                *LANG.get().expect("LANG not set").write().unwrap() = lang.language_code();
+            }
         }
         Message::Interaction(Interaction::PickGlobalReleaseChannel(channel)) => {
             log::debug!("Interaction::PickGlobalReleaseChannel({:?})", channel);
@@ -2012,13 +2135,13 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             // Update all addon states, expect ignored, if needed.
             let flavors = &Flavor::ALL[..];
             for flavor in flavors {
-                let ignored_ids = ajour.config.addons.ignored.entry(*flavor).or_default();
+                let config_addons = &ajour.config.addons;
                 let mut addons: Vec<_> = ajour
                     .addons
                     .entry(*flavor)
                     .or_default()
                     .iter_mut()
-                    .filter(|a| !ignored_ids.iter().any(|i| i == &a.primary_folder_id))
+                    .filter(|a| !config_addons.is_ignored(flavor, &a.primary_folder_id))
                     .collect();
                 for addon in addons.iter_mut() {
                     // Check if addon is updatable.
@@ -2033,13 +2156,16 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             }
 
             ajour.config.addons.global_release_channel = channel;
-            let _ = ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
         }
         Message::CheckLatestRelease(_) => {
             log::debug!("Message::CheckLatestRelease");
 
             return Ok(Command::perform(
-                get_latest_release(ajour.config.self_update_channel),
+                get_latest_release(
+                    ajour.config.self_update_channel,
+                    ajour.config.self_update_url_override.clone(),
+                ),
                 Message::LatestRelease,
             ));
         }
@@ -2075,7 +2201,11 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                 // If we have an account already selected, use that as the picklist selection
                 // or if there is only a single account to choose, use that
                 // and trigger a parse for this without user interaction
-                let account_from_config = ajour.config.weak_auras_account.get(&flavor).cloned();
+                // An account which no longer exists is ignored.
+                let account_from_config = ajour
+                    .config
+                    .weak_auras_account_path(&flavor)
+                    .and(ajour.config.weak_auras_accounts(&flavor).into_iter().next());
                 let get_single_account = || match &state.accounts[..] {
                     [a] => Some(a.clone()),
                     _ => None,
@@ -2107,14 +2237,15 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
                 let state = ajour.weak_auras_state.entry(flavor).or_default();
 
                 if state.chosen_account.as_ref() != Some(&account) {
-                    state.chosen_account = Some(account.clone());
-
-                    // Persist to config
+                    // Persist to config. The selection replaces the previously
+                    // chosen account, while other configured accounts are kept.
+                    if let Some(previous) = state.chosen_account.replace(account.clone()) {
+                        ajour.config.remove_weak_auras_account(&flavor, &previous);
+                    }
                     ajour
                         .config
-                        .weak_auras_account
-                        .insert(flavor, account.clone());
-                    let _ = ajour.config.save();
+                        .select_weak_auras_account(flavor, account.clone());
+                    let _ = ajour.config.save_if_dirty();
 
                     if let Some(wtf_path) = ajour.config.get_wtf_directory_for_flavor(&flavor) {
                         state.auras.drain(..);
@@ -2203,7 +2334,7 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
             );
 
             ajour.config.alternating_row_colors = is_set;
-            let _ = ajour.config.save();
+            let _ = ajour.config.save_if_dirty();
         }
         Message::Error(error) => {
             log_error(&error);
@@ -2217,14 +2348,27 @@ pub fn handle_message(ajour: &mut Ajour, message: Message) -> Result<Command<Mes
 
             // Minimizing Ajour on Windows will call this function with 0, 0.
             // We don't want to save that in config, because then it will start with zero size.
+            //
+            // iced doesn't report the window position, or if the window is maximized.
+            // The window is never opened maximized either, so it's saved as not
+            // maximized and the saved position is kept.
             if width > 0 && height > 0 {
-                ajour.config.window_size = Some((width, height));
-                let _ = ajour.config.save();
+                ajour.config.set_window_state((width, height), None, false);
+                let _ = ajour.config.save_if_dirty();
             }
         }
         Message::RuntimeEvent(iced_native::Event::Keyboard(
-            iced_native::keyboard::Event::KeyReleased { key_code, .. },
+            iced_native::keyboard::Event::KeyReleased {
+                key_code,
+                modifiers,
+            },
         )) => {
+            // Command + 0 cycles through the scale presets
+            if key_code == iced_native::keyboard::KeyCode::Key0 && modifiers.is_command_pressed() {
+                ajour.scale_state.scale = ajour.config.cycle_scale();
+                let _ = ajour.config.save_if_dirty();
+            }
+
             if key_code == iced_native::keyboard::KeyCode::Escape {
                 match ajour.mode {
                     Mode::Settings | Mode::About => {
@@ -2285,7 +2429,10 @@ async fn perform_download_addon(
     global_release_channel: GlobalReleaseChannel,
     addon: Addon,
     to_directory: PathBuf,
+    download_limiter: DownloadLimiter,
 ) -> (DownloadReason, Flavor, String, Result<(), DownloadError>) {
+    let _permit = download_limiter.acquire().await;
+
     (
         reason,
         flavor,
@@ -2354,7 +2501,7 @@ async fn perform_fetch_latest_addon(
                     catalog::Source::Tukui => RepositoryKind::Tukui,
                     catalog::Source::WowI => RepositoryKind::WowI,
                     catalog::Source::TownlongYak => RepositoryKind::TownlongYak,
-                    catalog::Source::Other => {
+                    catalog::Source::Custom { .. } | catalog::Source::Other => {
                         panic!("Unsupported catalog source")
                     }
                 };
@@ -2444,6 +2591,21 @@ async fn perform_batch_refresh_repository_packages(
     )
 }
 
+/// Returns the column and direction My Addons is sorted by, as saved with
+/// `Config::sort_state`. Columns which no longer exist sort by status.
+fn saved_addon_sort(ajour: &Ajour) -> (ColumnKey, SortDirection) {
+    let sort = ajour.config.sort_state();
+
+    ajour
+        .header_state
+        .columns
+        .iter()
+        .map(|c| c.key)
+        .find(|key| key.as_string() == sort.column_key)
+        .map(|key| (key, sort.direction.into()))
+        .unwrap_or((ColumnKey::Status, SortDirection::Desc))
+}
+
 fn sort_addons(
     addons: &mut [Addon],
     global_release_channel: GlobalReleaseChannel,
@@ -2724,45 +2886,24 @@ fn query_and_sort_catalog(ajour: &mut Ajour) {
         let category = &ajour.catalog_search_state.category;
         let result_size = ajour.catalog_search_state.result_size.as_usize();
 
-        // Increase penalty for gaps between matching characters
-        let fuzzy_match_config = SkimScoreConfig {
-            gap_start: -12,
-            gap_extension: -6,
-            ..Default::default()
-        };
-        let fuzzy_matcher = SkimMatcherV2::default().score_config(fuzzy_match_config);
-
         let mut catalog_rows_and_score = catalog
             .addons
             .iter()
             .filter(|a| !a.game_versions.is_empty())
-            .filter_map(|a| {
-                if let Some(query) = &query {
-                    let title_score = fuzzy_matcher
-                        .fuzzy_match(&a.name, &query)
-                        .unwrap_or_default();
-                    let description_score = fuzzy_matcher
-                        .fuzzy_match(&a.summary, &query)
-                        .unwrap_or_default()
-                        / 2;
-
-                    let max_score = title_score.max(description_score);
-
-                    if max_score > 0 {
-                        Some((a, max_score))
-                    } else {
-                        None
-                    }
-                } else {
-                    Some((a, 0))
-                }
+            .filter_map(|a| match &query {
+                Some(query) => a.search_score(query).map(|score| (a, score)),
+                None => Some((a, 0)),
             })
             .filter(|(a, _)| {
                 a.game_versions
                     .iter()
-                    .any(|gc| gc.flavor == flavor.base_flavor())
+                    .any(|gc| gc.flavor.base_flavor() == flavor.base_flavor())
+            })
+            .filter(|(a, _)| match source {
+                // Custom catalogs are shown regardless of the source of each addon
+                Some(catalog::Source::Custom { .. }) => true,
+                source => Some(&a.source) == source.as_ref(),
             })
-            .filter(|(a, _)| Some(a.source) == *source)
             .filter(|(a, _)| match category {
                 CatalogCategory::All => true,
                 CatalogCategory::Choice(name) => a.categories.iter().any(|c| c == name),
@@ -2849,13 +2990,28 @@ fn save_column_configs(ajour: &mut Ajour) {
         .map(ColumnConfigV2::from)
         .collect();
 
+    // My Addons columns are saved for the current flavor only.
+    let flavor = ajour.config.wow.flavor;
+    let flavor_my_addons_columns = match &ajour.config.column_config {
+        ColumnConfig::V3 {
+            flavor_my_addons_columns,
+            ..
+        } => flavor_my_addons_columns.clone(),
+        _ => Default::default(),
+    };
+
     ajour.config.column_config = ColumnConfig::V3 {
-        my_addons_columns,
+        my_addons_columns: ajour.config.column_config.shared_my_addons_columns(),
         catalog_columns,
         aura_columns,
+        flavor_my_addons_columns,
     };
+    ajour
+        .config
+        .column_config
+        .set_my_addons_columns_for(flavor, my_addons_columns);
 
-    let _ = ajour.config.save();
+    let _ = ajour.config.save_if_dirty();
 }
 
 /// Hardcoded binary names for each compilation target
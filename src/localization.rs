@@ -33,7 +33,9 @@ pub static LOCALIZATION_CTX: Lazy<JSONGetText<'static>> = Lazy::new(|| {
         "pt_PT",
         "locale/pt.json",
         "uk_UA",
-        "locale/uk.json"
+        "locale/uk.json",
+        "zh_CN",
+        "locale/zh-Hans.json"
     )
     .unwrap()
 });
@@ -43,16 +45,17 @@ pub static LANG: OnceCell<RwLock<&'static str>> = OnceCell::new();
 pub fn localized_string(key: &str) -> String {
     let lang = LANG.get().expect("LANG not set").read().unwrap();
 
-    if let Some(text) = get_text!(LOCALIZATION_CTX, *lang, key) {
-        let text = text.to_string();
-        if text.is_empty() {
-            key.to_owned()
-        } else {
-            text
-        }
-    } else {
-        key.to_owned()
-    }
+    // Languages without a locale file yet, and empty translations, fall back
+    // to English.
+    let translated = |lang: &str| {
+        get_text!(LOCALIZATION_CTX, lang, key)
+            .map(|text| text.to_string())
+            .filter(|text| !text.is_empty())
+    };
+
+    translated(*lang)
+        .or_else(|| translated("en_US"))
+        .unwrap_or_else(|| key.to_owned())
 }
 
 /// Returns a localized `timeago::Formatter`.
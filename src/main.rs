@@ -75,7 +75,8 @@ pub fn main() {
                     backup_folder,
                     destination,
                     flavors,
-                } => command::backup(backup_folder, destination, flavors),
+                    dry_run,
+                } => command::backup(backup_folder, destination, flavors, dry_run),
                 cli::Command::Update => command::update_both(),
                 cli::Command::UpdateAddons => command::update_all_addons(),
                 cli::Command::UpdateWeakauras => command::update_all_weakauras(),